@@ -0,0 +1,99 @@
+//! Matching diagnostics against a baseline of known diagnostics.
+
+use sml_syntax::{rowan::NodeOrToken, SyntaxKind, SyntaxNode, SyntaxToken};
+use std::cell::RefCell;
+use str_util::SmolStr;
+use text_size_util::TextRange;
+
+/// The known diagnostics in a file, which are used up as diagnostics match them.
+pub(crate) struct Known<'a> {
+  root: &'a SyntaxNode,
+  counts: RefCell<input::Known>,
+}
+
+impl<'a> Known<'a> {
+  pub(crate) fn new(root: &'a SyntaxNode, known: Option<&input::Known>) -> Self {
+    Self { root, counts: RefCell::new(known.cloned().unwrap_or_default()) }
+  }
+
+  /// Returns whether a diagnostic with this code at this range is known. If it is, uses up one of
+  /// the known diagnostics.
+  pub(crate) fn take(&self, code: diagnostic::Code, range: TextRange) -> bool {
+    let mut counts = self.counts.borrow_mut();
+    if counts.is_empty() {
+      return false;
+    }
+    match counts.get_mut(&(code, fingerprint(self.root, code, range))) {
+      Some(n) if *n != 0 => {
+        *n -= 1;
+        true
+      }
+      _ => false,
+    }
+  }
+}
+
+/// Returns a fingerprint for a diagnostic with this code at this range.
+///
+/// The fingerprint depends only on the code, the kind of the smallest node covering the range, and
+/// the name bound by the innermost binding containing that node, so it does not change when code
+/// elsewhere in the file, or even elsewhere in the same binding, is added, removed, or reformatted.
+pub(crate) fn fingerprint(root: &SyntaxNode, code: diagnostic::Code, range: TextRange) -> SmolStr {
+  let node = match root.covering_element(range) {
+    NodeOrToken::Node(node) => node,
+    NodeOrToken::Token(tok) => tok.parent().unwrap_or_else(|| root.clone()),
+  };
+  let mut hasher = Fnv::default();
+  hasher.write(code.to_string().as_bytes());
+  hasher.write(&[0]);
+  hasher.write(format!("{:?}", node.kind()).as_bytes());
+  hasher.write(&[0]);
+  if let Some(name) = binding_name(&node) {
+    hasher.write(name.text().as_bytes());
+  }
+  SmolStr::from(format!("{:016x}", hasher.0))
+}
+
+/// Returns the first name in the innermost binding containing the node, if any.
+fn binding_name(node: &SyntaxNode) -> Option<SyntaxToken> {
+  node
+    .ancestors()
+    .filter(|node| {
+      matches!(
+        node.kind(),
+        SyntaxKind::ValBind
+          | SyntaxKind::FunBind
+          | SyntaxKind::TyBind
+          | SyntaxKind::DatBind
+          | SyntaxKind::ExBind
+          | SyntaxKind::StrBind
+          | SyntaxKind::SigBind
+          | SyntaxKind::FunctorBind
+      )
+    })
+    .find_map(|node| {
+      node
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .find(|tok| tok.kind() == SyntaxKind::Name)
+    })
+}
+
+/// The 64-bit FNV-1a hash. We don't use the std hashers since their output may change between
+/// versions of Rust, and fingerprints are written to disk.
+struct Fnv(u64);
+
+impl Default for Fnv {
+  fn default() -> Self {
+    Self(0xcbf2_9ce4_8422_2325)
+  }
+}
+
+impl Fnv {
+  fn write(&mut self, bytes: &[u8]) {
+    for &b in bytes {
+      self.0 ^= u64::from(b);
+      self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+    }
+  }
+}
@@ -1,5 +1,8 @@
 //! Helpers for working with diagnostics.
 
+use crate::baseline::Known;
+use crate::suppress::Suppressions;
+use paths::{PathId, PathMap, WithPath};
 use sml_syntax::ast::{self, AstNode as _};
 use sml_syntax::SyntaxNode;
 use text_size_util::TextRange;
@@ -15,6 +18,19 @@ pub struct Diagnostic<R> {
   pub code: diagnostic::Code,
   /// The severity.
   pub severity: diagnostic::Severity,
+  /// Other locations related to this diagnostic.
+  pub related: Vec<Related<R>>,
+  /// Extra information about the diagnostic.
+  pub tag: Option<sml_statics::Tag>,
+}
+
+/// A location related to a diagnostic.
+#[derive(Debug)]
+pub struct Related<R> {
+  /// The range, which may be in a different file than the diagnostic.
+  pub range: WithPath<R>,
+  /// The message.
+  pub message: String,
 }
 
 impl<R> Diagnostic<R> {
@@ -26,6 +42,21 @@ impl<R> Diagnostic<R> {
       message: "comment prevents formatting".to_owned(),
       code: diagnostic::Code::n(6001),
       severity: diagnostic::Severity::Warning,
+      related: Vec::new(),
+      tag: None,
+    }
+  }
+
+  /// Returns a diagnostic for a suppression comment at the given range that did not suppress any
+  /// diagnostics.
+  pub fn unused_suppression(range: R) -> Diagnostic<R> {
+    Diagnostic {
+      range,
+      message: "unused suppression comment".to_owned(),
+      code: diagnostic::Code::n(6002),
+      severity: diagnostic::Severity::Warning,
+      related: Vec::new(),
+      tag: Some(sml_statics::Tag::Unnecessary),
     }
   }
 }
@@ -46,34 +77,76 @@ pub struct Options {
 /// entirely warnings and then not emit the actual diagnostics. We'd need to come up with a way to
 /// order the diagnostics.
 pub(crate) fn source_file<F, R>(
-  file: &mlb_statics::SourceFile,
+  source_files: &PathMap<mlb_statics::SourceFile>,
+  path: PathId,
   syms_tys: &sml_statics_types::St,
+  lints: &[sml_lint::Error],
+  input: &input::Input,
   options: Options,
   f: F,
 ) -> Vec<Diagnostic<R>>
 where
   F: Fn(&text_pos::PositionDb, text_size_util::TextRange) -> Option<R>,
 {
+  let file = match source_files.get(&path) {
+    Some(x) => x,
+    None => return Vec::new(),
+  };
   let ignore_after_syntax = match options.ignore {
     config::init::DiagnosticsIgnore::None => false,
     config::init::DiagnosticsIgnore::AfterSyntax => true,
     config::init::DiagnosticsIgnore::All => return Vec::new(),
   };
+  let suppressions = Suppressions::new(file.syntax.parse.root.syntax());
+  let known = Known::new(file.syntax.parse.root.syntax(), input.baseline.get(path));
+  let ignored = |code: diagnostic::Code, range: TextRange| {
+    suppressions.suppresses(code, range) || known.take(code, range)
+  };
   let mut ret: Vec<_> = std::iter::empty()
     .chain(file.syntax.lex_errors.iter().filter_map(|err| {
+      if ignored(err.code(), err.range()) {
+        return None;
+      }
       let range = f(&file.syntax.pos_db, err.range())?;
       let message = err.to_string();
-      Some(Diagnostic { range, message, code: err.code(), severity: err.severity() })
+      Some(Diagnostic {
+        range,
+        message,
+        code: err.code(),
+        severity: err.severity(),
+        related: Vec::new(),
+        tag: None,
+      })
     }))
     .chain(file.syntax.parse.errors.iter().filter_map(|err| {
+      if ignored(err.code(), err.range()) {
+        return None;
+      }
       let range = f(&file.syntax.pos_db, err.range())?;
       let message = err.to_string();
-      Some(Diagnostic { range, message, code: err.code(), severity: err.severity() })
+      Some(Diagnostic {
+        range,
+        message,
+        code: err.code(),
+        severity: err.severity(),
+        related: Vec::new(),
+        tag: None,
+      })
     }))
     .chain(file.syntax.lower.errors.iter().filter_map(|err| {
+      if ignored(err.code(), err.range()) {
+        return None;
+      }
       let range = f(&file.syntax.pos_db, err.range())?;
       let message = err.to_string();
-      Some(Diagnostic { range, message, code: err.code(), severity: err.severity() })
+      Some(Diagnostic {
+        range,
+        message,
+        code: err.code(),
+        severity: err.severity(),
+        related: Vec::new(),
+        tag: None,
+      })
     }))
     .collect();
   let has_any_error = ret.iter().any(|x| matches!(x.severity, diagnostic::Severity::Error));
@@ -82,21 +155,81 @@ where
       let idx = err.idx();
       let syntax = file.syntax.lower.ptrs.hir_to_ast(idx).expect("no pointer for idx");
       let node = syntax.to_node(file.syntax.parse.root.syntax());
-      let range = custom_node_range(node.clone()).unwrap_or_else(|| node.text_range());
-      let range = f(&file.syntax.pos_db, range)?;
+      let text_range = custom_node_range(node.clone()).unwrap_or_else(|| node.text_range());
+      if ignored(err.code(), text_range) {
+        return None;
+      }
+      let range = f(&file.syntax.pos_db, text_range)?;
       let message = err.display(syms_tys, options.lines).to_string();
-      Some(Diagnostic { range, message, code: err.code(), severity: err.severity() })
+      let related = err
+        .related()
+        .into_iter()
+        .filter_map(|(def, message)| {
+          let range = match def {
+            Some(def) => {
+              let idx = def.to_regular_idx()?;
+              let def_file = source_files.get(&idx.path)?;
+              let ptr = def_file.syntax.lower.ptrs.hir_to_ast(idx.val)?;
+              idx.path.wrap(f(&def_file.syntax.pos_db, ptr.text_range())?)
+            }
+            None => path.wrap(f(&file.syntax.pos_db, text_range)?),
+          };
+          Some(Related { range, message })
+        })
+        .collect();
+      Some(Diagnostic {
+        range,
+        message,
+        code: err.code(),
+        severity: err.severity(),
+        related,
+        tag: err.tag(),
+      })
+    }));
+    ret.extend(lints.iter().filter_map(|err| {
+      let code = err.code()?;
+      let severity = match (err.severity(), input.severities.get(path, code)) {
+        (Some(sev), _) | (None, Some(Some(sev))) => sev,
+        (None, _) => return None,
+      };
+      let syntax = file.syntax.lower.ptrs.hir_to_ast(err.idx())?;
+      let node = syntax.to_node(file.syntax.parse.root.syntax());
+      let text_range = custom_node_range(node.clone()).unwrap_or_else(|| node.text_range());
+      if ignored(code, text_range) {
+        return None;
+      }
+      let range = f(&file.syntax.pos_db, text_range)?;
+      Some(Diagnostic {
+        range,
+        message: err.to_string(),
+        code,
+        severity,
+        related: Vec::new(),
+        tag: None,
+      })
     }));
     if matches!(options.format, config::init::FormatEngine::Naive) {
       if let Err(sml_naive_fmt::Error::Comments(ranges)) =
         sml_naive_fmt::check(&file.syntax.parse.root)
       {
         ret.extend(ranges.into_iter().filter_map(|range| {
+          if ignored(diagnostic::Code::n(6001), range) {
+            return None;
+          }
           let range = f(&file.syntax.pos_db, range)?;
           Some(Diagnostic::naive_fmt_comment(range))
         }));
       }
     }
+    // only report unused suppressions when we have gotten all the diagnostics, since otherwise the
+    // suppressions may be for diagnostics we did not get.
+    ret.extend(suppressions.unused().into_iter().filter_map(|range| {
+      if ignored(diagnostic::Code::n(6002), range) {
+        return None;
+      }
+      let range = f(&file.syntax.pos_db, range)?;
+      Some(Diagnostic::unused_suppression(range))
+    }));
   }
   ret
 }
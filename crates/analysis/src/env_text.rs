@@ -0,0 +1,429 @@
+//! Writing environments as SML source, either as a signature or as a stub structure.
+
+use sml_statics_types::env::Env;
+use sml_statics_types::info::{IdStatus, TyInfo, ValInfo};
+use sml_statics_types::ty::{BoundTyVarData, BoundTyVars, Ty, TyData, TyVarKind};
+use sml_statics_types::{def, sym::Equality};
+use std::fmt::Write as _;
+use text_size_util::TextSize;
+
+/// The body of stubbed-out values.
+const UNIMPLEMENTED: &str = "raise Fail \"unimplemented\"";
+
+/// The type to use for types whose definition is not known.
+const PLACEHOLDER_TY: &str = "unit";
+
+/// What to write an env as.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Target {
+  /// A signature, with specifications.
+  Sig,
+  /// A structure, with declarations that stub out everything not already determined.
+  Struct,
+}
+
+pub(crate) enum Item<'a> {
+  Str(&'a Env),
+  Ty(&'a TyInfo),
+  Val(&'a ValInfo),
+}
+
+pub(crate) struct Printer<'a> {
+  st: &'a sml_statics_types::St,
+  file: &'a mlb_statics::SourceFile,
+  path: paths::PathId,
+  target: Target,
+  /// The path of the structure or signature whose env we are writing.
+  prefix: Vec<&'a str_util::Name>,
+  buf: String,
+  indent: usize,
+}
+
+impl<'a> Printer<'a> {
+  pub(crate) fn new(
+    st: &'a sml_statics_types::St,
+    file: &'a mlb_statics::SourceFile,
+    path: paths::PathId,
+    target: Target,
+    prefix: &'a str_util::Name,
+  ) -> Self {
+    Self { st, file, path, target, prefix: vec![prefix], buf: String::new(), indent: 0 }
+  }
+
+  pub(crate) fn set_indent(&mut self, indent: usize) {
+    self.indent = indent;
+  }
+
+  pub(crate) fn buf(&mut self) -> &mut String {
+    &mut self.buf
+  }
+
+  pub(crate) fn finish(self) -> String {
+    self.buf
+  }
+
+  /// Writes a signature or structure expression for the env, or returns `None` if something in it
+  /// can't be written in the source.
+  pub(crate) fn env(&mut self, env: &'a Env) -> Option<()> {
+    let items = self.items(env);
+    let (start, end) = match self.target {
+      Target::Sig => ("sig", "end"),
+      Target::Struct => ("struct", "end"),
+    };
+    if items.is_empty() {
+      write!(self.buf, "{start} {end}").ok()?;
+      return Some(());
+    }
+    self.buf.push_str(start);
+    self.indent += 2;
+    for (name, item) in items {
+      self.buf.push('\n');
+      self.item(name, item)?;
+    }
+    self.indent -= 2;
+    self.buf.push('\n');
+    self.write_indent();
+    self.buf.push_str(end);
+    Some(())
+  }
+
+  /// Returns the items in the env, in the order they were defined in the source.
+  pub(crate) fn items(&self, env: &'a Env) -> Vec<(&'a str_util::Name, Item<'a>)> {
+    let mut items = Vec::<(Option<TextSize>, &'a str_util::Name, Item<'a>)>::new();
+    items.extend(
+      env
+        .str_env
+        .iter()
+        .filter(|(_, env)| env.disallow.is_none())
+        .map(|(name, env)| (self.def_start(env.def.into_iter()), name, Item::Str(env))),
+    );
+    items.extend(
+      env
+        .ty_env
+        .iter()
+        .filter(|(_, ti)| ti.disallow.is_none())
+        .map(|(name, ti)| (self.def_start(ti.def.into_iter()), name, Item::Ty(ti))),
+    );
+    items.extend(
+      env
+        .val_env
+        .iter()
+        .filter(|(_, vi)| vi.disallow.is_none() && !matches!(vi.id_status, IdStatus::Con))
+        .map(|(name, vi)| (self.def_start(vi.defs.iter().copied()), name, Item::Val(vi))),
+    );
+    // keep the order of the source, putting everything defined elsewhere (e.g. brought in by
+    // `open`) at the end.
+    items.sort_by(|(a_start, a_name, _), (b_start, b_name, _)| {
+      (a_start.is_none(), a_start, a_name).cmp(&(b_start.is_none(), b_start, b_name))
+    });
+    items.into_iter().map(|(_, name, item)| (name, item)).collect()
+  }
+
+  /// Returns the earliest start of the defs in this file.
+  fn def_start<I>(&self, defs: I) -> Option<TextSize>
+  where
+    I: Iterator<Item = def::Def>,
+  {
+    defs
+      .filter_map(|def| {
+        let idx = self.local_idx(def)?;
+        Some(self.file.syntax.lower.ptrs.hir_to_ast(idx)?.text_range().start())
+      })
+      .min()
+  }
+
+  fn local_idx(&self, def: def::Def) -> Option<sml_hir::Idx> {
+    let idx = def.to_regular_idx()?;
+    (idx.path == self.path).then_some(idx.val)
+  }
+
+  /// Writes the item at the current indentation, without a trailing newline.
+  pub(crate) fn item(&mut self, name: &'a str_util::Name, item: Item<'a>) -> Option<()> {
+    match item {
+      Item::Str(env) => {
+        self.doc(env.def.into_iter());
+        self.write_indent();
+        match self.target {
+          Target::Sig => write!(self.buf, "structure {name} : ").ok()?,
+          Target::Struct => write!(self.buf, "structure {name} = ").ok()?,
+        }
+        self.prefix.push(name);
+        self.env(env)?;
+        self.prefix.pop();
+      }
+      Item::Ty(ty_info) => {
+        self.doc(ty_info.def.into_iter());
+        self.write_indent();
+        self.ty_info(name, ty_info)?;
+      }
+      Item::Val(val_info) => {
+        self.doc(val_info.defs.iter().copied());
+        self.write_indent();
+        let bound_vars = &val_info.ty_scheme.bound_vars;
+        let ty_data = self.st.tys.data(val_info.ty_scheme.ty);
+        match (val_info.id_status, self.target) {
+          (IdStatus::Con, _) => {}
+          (IdStatus::Exn(_), _) => {
+            write!(self.buf, "exception {name}").ok()?;
+            if let TyData::Fn(data) = ty_data {
+              self.buf.push_str(" of ");
+              self.ty(bound_vars, data.param, Prec::Arrow)?;
+            }
+          }
+          (IdStatus::Val, Target::Sig) => {
+            write!(self.buf, "val {name} : ").ok()?;
+            self.ty(bound_vars, val_info.ty_scheme.ty, Prec::Arrow)?;
+          }
+          // `raise` is expansive, so use `fun` for functions, so they are polymorphic if need be,
+          // and annotate everything else, so it is not generalized. polymorphic non-functions can't
+          // be stubbed out this way, but the annotation at least puts the error on the stub.
+          (IdStatus::Val, Target::Struct) => match ty_data {
+            TyData::Fn(_) => write!(self.buf, "fun {name} _ = {UNIMPLEMENTED}").ok()?,
+            _ => {
+              write!(self.buf, "val {name} : ").ok()?;
+              self.ty(bound_vars, val_info.ty_scheme.ty, Prec::Arrow)?;
+              write!(self.buf, " = {UNIMPLEMENTED}").ok()?;
+            }
+          },
+        }
+      }
+    }
+    Some(())
+  }
+
+  fn ty_info(&mut self, name: &str_util::Name, ty_info: &TyInfo) -> Option<()> {
+    let bound_vars = &ty_info.ty_scheme.bound_vars;
+    let data = self.st.tys.data(ty_info.ty_scheme.ty);
+    let sym_info = match &data {
+      TyData::Con(data) => self.st.syms.get(data.sym),
+      _ => None,
+    };
+    // a type declared right here, as opposed to an alias for some other type.
+    let here = sym_info.filter(|sym_info| {
+      let want = self.prefix.iter().copied().chain(std::iter::once(name));
+      sym_info.path.all_names().eq(want)
+    });
+    if !ty_info.val_env.is_empty() {
+      let sym_info = match here {
+        Some(x) => x,
+        None => {
+          let sym_info = sym_info?;
+          write!(self.buf, "datatype {name} = datatype ").ok()?;
+          self.path(sym_info.path.all_names());
+          return Some(());
+        }
+      };
+      self.buf.push_str("datatype ");
+      self.ty_vars(bound_vars)?;
+      write!(self.buf, "{name} =").ok()?;
+      for (idx, (con, val_info)) in sym_info.ty_info.val_env.iter().enumerate() {
+        self.buf.push_str(if idx == 0 { " " } else { " | " });
+        self.buf.push_str(con.as_str());
+        if let TyData::Fn(data) = self.st.tys.data(val_info.ty_scheme.ty) {
+          self.buf.push_str(" of ");
+          self.ty(&val_info.ty_scheme.bound_vars, data.param, Prec::Arrow)?;
+        }
+      }
+      return Some(());
+    }
+    let is_abstract = match (&data, here) {
+      (TyData::Con(data), Some(_)) => {
+        data.args.len() == bound_vars.len()
+          && data.args.iter().enumerate().all(|(idx, &arg)| {
+            matches!(self.st.tys.data(arg), TyData::BoundVar(bv) if bv.to_usize() == idx)
+          })
+      }
+      _ => false,
+    };
+    if is_abstract {
+      if let Target::Struct = self.target {
+        self.buf.push_str("type ");
+        self.ty_vars(bound_vars)?;
+        write!(self.buf, "{name} = {PLACEHOLDER_TY}").ok()?;
+        return Some(());
+      }
+      let kw = match here?.equality {
+        Equality::Never => "type",
+        Equality::Always | Equality::Sometimes => "eqtype",
+      };
+      write!(self.buf, "{kw} ").ok()?;
+      self.ty_vars(bound_vars)?;
+      self.buf.push_str(name.as_str());
+      return Some(());
+    }
+    self.buf.push_str("type ");
+    self.ty_vars(bound_vars)?;
+    write!(self.buf, "{name} = ").ok()?;
+    self.ty(bound_vars, ty_info.ty_scheme.ty, Prec::Arrow)
+  }
+
+  fn ty_vars(&mut self, bound_vars: &BoundTyVars) -> Option<()> {
+    let names = bound_vars
+      .iter()
+      .enumerate()
+      .map(|(idx, data)| ty_var_name(idx, data))
+      .collect::<Option<Vec<_>>>()?;
+    match names.as_slice() {
+      [] => {}
+      [name] => write!(self.buf, "{name} ").ok()?,
+      names => write!(self.buf, "({}) ", names.join(", ")).ok()?,
+    }
+    Some(())
+  }
+
+  fn ty(&mut self, bound_vars: &BoundTyVars, ty: Ty, prec: Prec) -> Option<()> {
+    match self.st.tys.data(ty) {
+      TyData::None | TyData::UnsolvedMetaVar(_) | TyData::GeneralizedMetaVar(_) => return None,
+      TyData::BoundVar(bv) => {
+        let idx = bv.to_usize();
+        let name = ty_var_name(idx, bound_vars.get(idx)?)?;
+        self.buf.push_str(name.as_str());
+      }
+      TyData::FixedVar(fv) => write!(self.buf, "{}", fv.ty_var).ok()?,
+      TyData::Record(rows) => {
+        let is_tuple = rows.len() > 1
+          && rows.keys().enumerate().all(|(idx, lab)| *lab == sml_hir::Lab::tuple(idx));
+        if rows.is_empty() {
+          self.buf.push_str("unit");
+        } else if is_tuple {
+          let needs_parens = prec > Prec::Star;
+          if needs_parens {
+            self.buf.push('(');
+          }
+          for (idx, &ty) in rows.values().enumerate() {
+            if idx != 0 {
+              self.buf.push_str(" * ");
+            }
+            self.ty(bound_vars, ty, Prec::App)?;
+          }
+          if needs_parens {
+            self.buf.push(')');
+          }
+        } else {
+          self.buf.push('{');
+          for (idx, (lab, &ty)) in rows.iter().enumerate() {
+            if idx != 0 {
+              self.buf.push_str(", ");
+            }
+            write!(self.buf, "{lab} : ").ok()?;
+            self.ty(bound_vars, ty, Prec::Arrow)?;
+          }
+          self.buf.push('}');
+        }
+      }
+      TyData::Con(data) => {
+        match data.args.as_slice() {
+          [] => {}
+          [arg] => {
+            self.ty(bound_vars, *arg, Prec::App)?;
+            self.buf.push(' ');
+          }
+          args => {
+            self.buf.push('(');
+            for (idx, &arg) in args.iter().enumerate() {
+              if idx != 0 {
+                self.buf.push_str(", ");
+              }
+              self.ty(bound_vars, arg, Prec::Arrow)?;
+            }
+            self.buf.push_str(") ");
+          }
+        }
+        match self.st.syms.get(data.sym) {
+          None => self.buf.push_str("exn"),
+          Some(sym_info) => self.path(sym_info.path.all_names()),
+        }
+      }
+      TyData::Fn(data) => {
+        let needs_parens = prec > Prec::Arrow;
+        if needs_parens {
+          self.buf.push('(');
+        }
+        self.ty(bound_vars, data.param, Prec::Star)?;
+        self.buf.push_str(" -> ");
+        self.ty(bound_vars, data.res, Prec::Arrow)?;
+        if needs_parens {
+          self.buf.push(')');
+        }
+      }
+    }
+    Some(())
+  }
+
+  /// Writes the path, relative to the structure we're in if it's from inside that structure.
+  fn path<'n, I>(&mut self, names: I)
+  where
+    I: Iterator<Item = &'n str_util::Name>,
+  {
+    let names: Vec<_> = names.collect();
+    let common = names.iter().zip(self.prefix.iter()).take_while(|(a, b)| a == b).count();
+    let names = if common == names.len() { &names[..] } else { &names[common..] };
+    for (idx, name) in names.iter().enumerate() {
+      if idx != 0 {
+        self.buf.push('.');
+      }
+      self.buf.push_str(name.as_str());
+    }
+  }
+
+  /// Writes the doc comment for the defs, if any. We only do this for signatures, since when
+  /// writing a structure from a signature, the docs are already on the signature.
+  fn doc<I>(&mut self, defs: I)
+  where
+    I: Iterator<Item = def::Def>,
+  {
+    if let Target::Struct = self.target {
+      return;
+    }
+    let file = self.file;
+    let doc = defs.filter_map(|def| self.local_idx(def)).find_map(|idx| file.info.get_doc(idx));
+    let doc = match doc {
+      Some(x) => x,
+      None => return,
+    };
+    self.write_indent();
+    self.buf.push_str("(*!\n");
+    for line in doc.lines() {
+      self.write_indent();
+      if line.is_empty() {
+        self.buf.push_str(" *\n");
+      } else {
+        self.buf.push_str(" * ");
+        self.buf.push_str(line);
+        self.buf.push('\n');
+      }
+    }
+    self.write_indent();
+    self.buf.push_str(" *)\n");
+  }
+
+  fn write_indent(&mut self) {
+    for _ in 0..self.indent {
+      self.buf.push(' ');
+    }
+  }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Prec {
+  Arrow,
+  Star,
+  App,
+}
+
+/// Returns the name of the bound type variable, or `None` if it is overloaded, since those can't be
+/// written in the source.
+fn ty_var_name(idx: usize, data: &BoundTyVarData) -> Option<String> {
+  let ticks = match data {
+    BoundTyVarData::Named(ty_var) => return Some(ty_var.to_string()),
+    BoundTyVarData::Kind(TyVarKind::Regular) => "'",
+    BoundTyVarData::Kind(TyVarKind::Equality) => "''",
+    BoundTyVarData::Kind(TyVarKind::Overloaded(_)) => return None,
+  };
+  let letter = char::from(b'a' + u8::try_from(idx % 26).ok()?);
+  let ret = match idx / 26 {
+    0 => format!("{ticks}{letter}"),
+    n => format!("{ticks}{letter}{n}"),
+  };
+  Some(ret)
+}
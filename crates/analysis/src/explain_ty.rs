@@ -0,0 +1,66 @@
+//! Explaining why something has the type it does.
+
+use crate::TyReason;
+use paths::{PathId, PathMap};
+use std::fmt::Write as _;
+
+/// The most reasons to show on hover.
+const MAX_MD: usize = 5;
+
+/// The most characters of code to show for a reason.
+const MAX_CODE_LEN: usize = 40;
+
+pub(crate) fn get(
+  source_files: &PathMap<mlb_statics::SourceFile>,
+  syms_tys: &sml_statics_types::St,
+  file: &mlb_statics::SourceFile,
+  idx: sml_hir::Idx,
+  lines: config::DiagnosticLines,
+) -> Vec<TyReason> {
+  file
+    .info
+    .get_ty_srcs(syms_tys, idx, lines)
+    .into_iter()
+    .filter_map(|(def, ty)| {
+      let idx = def.to_regular_idx()?;
+      let src_file = source_files.get(&idx.path)?;
+      let ptr = src_file.syntax.lower.ptrs.hir_to_ast(idx.val)?;
+      let range = src_file.syntax.pos_db.range_utf16(ptr.text_range())?;
+      let node = ptr.to_node(src_file.syntax.parse.root.syntax());
+      Some(TyReason { range: idx.path.wrap(range), code: code(&node.text().to_string()), ty })
+    })
+    .collect()
+}
+
+/// Returns a Markdown list of the reasons, for hovering over something in the file at `path`.
+pub(crate) fn md(path: PathId, reasons: &[TyReason]) -> Option<String> {
+  if reasons.is_empty() {
+    return None;
+  }
+  let mut ret = "The type was inferred from:\n\n".to_owned();
+  for reason in reasons.iter().take(MAX_MD) {
+    let line = reason.range.val.start.line + 1;
+    let place = if reason.range.path == path { "" } else { " in another file" };
+    writeln!(ret, "- `{}` on line {line}{place}: `{}`", reason.code, reason.ty).ok()?;
+  }
+  if reasons.len() > MAX_MD {
+    writeln!(ret, "- and {} more", reasons.len() - MAX_MD).ok()?;
+  }
+  Some(ret)
+}
+
+/// Returns the code on a single line, truncated if it is long.
+fn code(s: &str) -> String {
+  let mut ret = String::new();
+  for word in s.split_whitespace() {
+    if !ret.is_empty() {
+      ret.push(' ');
+    }
+    ret.push_str(word);
+  }
+  if ret.chars().count() > MAX_CODE_LEN {
+    ret = ret.chars().take(MAX_CODE_LEN).collect();
+    ret.push_str("...");
+  }
+  ret
+}
@@ -0,0 +1,81 @@
+//! Code actions for generating a signature from the inferred environment of a structure.
+
+use crate::env_text::{Printer, Target};
+use crate::source_files::FileAndToken;
+use crate::{CodeAction, CodeActionKind, TextEdit};
+use sml_statics_types::def;
+use sml_syntax::ast::{self, AstNode as _, SyntaxNodePtr};
+use sml_syntax::SyntaxKind;
+use std::fmt::Write as _;
+use text_size_util::TextRange;
+
+pub(crate) fn get(
+  st: &sml_statics_types::St,
+  path: paths::PathId,
+  ft: &FileAndToken<'_>,
+) -> Vec<CodeAction> {
+  generate(st, path, ft).unwrap_or_default()
+}
+
+fn generate(
+  st: &sml_statics_types::St,
+  path: paths::PathId,
+  ft: &FileAndToken<'_>,
+) -> Option<Vec<CodeAction>> {
+  let str_bind = ft.token.parent()?.ancestors().find_map(ast::StrBind::cast)?;
+  // only offer this on the structure's name, not anywhere in its body.
+  let eq_str_exp = str_bind.eq_str_exp()?;
+  if eq_str_exp.syntax().text_range().start() <= ft.token.text_range().start() {
+    return None;
+  }
+  let str_dec = ast::StructureDec::cast(str_bind.syntax().parent()?)?;
+  // signatures may only be declared at the top level.
+  let is_top_level = str_dec.syntax().ancestors().skip(1).all(|x| {
+    matches!(x.kind(), SyntaxKind::DecInSeq | SyntaxKind::DecWithTail | SyntaxKind::Dec)
+      || ast::Root::can_cast(x.kind())
+  });
+  if !is_top_level {
+    return None;
+  }
+  let name_tok = str_bind.name()?;
+  let name = str_util::Name::new(name_tok.text());
+  let file = ft.file;
+  let env = file.info.basis().env.str_env.get(&name)?;
+  // make sure it's this structure, and not a later one with the same name.
+  let str_dec_idx = file.syntax.lower.ptrs.ast_to_hir(&SyntaxNodePtr::new(str_dec.syntax()))?;
+  if env.def != Some(def::Def::Path(def::Path::Regular(path), str_dec_idx)) {
+    return None;
+  }
+  let sig_name = format!("{}_SIG", name.as_str().to_uppercase());
+  let mut printer = Printer::new(st, file, path, Target::Sig, &name);
+  write!(printer.buf(), "signature {sig_name} = ").ok()?;
+  printer.env(env)?;
+  let mut new_text = printer.finish();
+  new_text.push_str("\n\n");
+  let start = match sml_comment::comment_above(str_dec.syntax()) {
+    Some(tok) => tok.text_range().start(),
+    None => str_dec.syntax().text_range().start(),
+  };
+  let pos_db = &file.syntax.pos_db;
+  let insert = TextEdit { range: pos_db.range_utf16(TextRange::empty(start))?, new_text };
+  let mut ret = Vec::<CodeAction>::new();
+  if str_bind.ascription_tail().is_none() {
+    let name_end = TextRange::empty(name_tok.text_range().end());
+    let ascribe =
+      TextEdit { range: pos_db.range_utf16(name_end)?, new_text: format!(" : {sig_name}") };
+    ret.push(CodeAction {
+      title: "Generate signature and ascribe to it".to_owned(),
+      kind: CodeActionKind::Refactor,
+      edits: vec![insert.clone(), ascribe],
+    });
+  }
+  ret.insert(
+    0,
+    CodeAction {
+      title: "Generate signature".to_owned(),
+      kind: CodeActionKind::Refactor,
+      edits: vec![insert],
+    },
+  );
+  Some(ret)
+}
@@ -0,0 +1,146 @@
+//! Code actions for implementing a signature with a stub structure.
+
+use crate::env_text::{self, Printer, Target};
+use crate::source_files::FileAndToken;
+use crate::{CodeAction, CodeActionKind, TextEdit};
+use sml_statics_types::{def, item::Item};
+use sml_syntax::ast::{self, AstNode as _, SyntaxNodePtr};
+use sml_syntax::SyntaxKind;
+use std::fmt::Write as _;
+use text_size_util::{TextRange, TextSize};
+
+pub(crate) fn get(
+  st: &sml_statics_types::St,
+  path: paths::PathId,
+  ft: &FileAndToken<'_>,
+) -> Vec<CodeAction> {
+  implement(st, path, ft).into_iter().chain(add_missing(st, path, ft)).collect()
+}
+
+/// Write a new structure that opaquely ascribes to the signature under the cursor.
+fn implement(
+  st: &sml_statics_types::St,
+  path: paths::PathId,
+  ft: &FileAndToken<'_>,
+) -> Option<CodeAction> {
+  let sig_bind = ft.token.parent()?.ancestors().find_map(ast::SigBind::cast)?;
+  // only offer this on the signature's name, not anywhere in its body.
+  if sig_bind.sig_exp()?.syntax().text_range().start() <= ft.token.text_range().start() {
+    return None;
+  }
+  let sig_dec = ast::SignatureDec::cast(sig_bind.syntax().parent()?)?;
+  let name = str_util::Name::new(sig_bind.name()?.text());
+  let file = ft.file;
+  let sig = file.info.basis().sig_env.get(&name)?;
+  // make sure it's this signature, and not a later one with the same name.
+  let sig_dec_idx = file.syntax.lower.ptrs.ast_to_hir(&SyntaxNodePtr::new(sig_dec.syntax()))?;
+  if sig.env.def != Some(def::Def::Path(def::Path::Regular(path), sig_dec_idx)) {
+    return None;
+  }
+  let mut printer = Printer::new(st, file, path, Target::Struct, &name);
+  write!(printer.buf(), "\n\nstructure {} :> {name} = ", struct_name(name.as_str())).ok()?;
+  printer.env(&sig.env)?;
+  let range = TextRange::empty(sig_dec.syntax().text_range().end());
+  let range = file.syntax.pos_db.range_utf16(range)?;
+  let edit = TextEdit { range, new_text: printer.finish() };
+  Some(CodeAction {
+    title: "Implement signature".to_owned(),
+    kind: CodeActionKind::Refactor,
+    edits: vec![edit],
+  })
+}
+
+/// Add stubs for the items missing from the structure, or the body of the functor, under the
+/// cursor, which ascribes to a signature.
+fn add_missing(
+  st: &sml_statics_types::St,
+  path: paths::PathId,
+  ft: &FileAndToken<'_>,
+) -> Option<CodeAction> {
+  let (tail, str_exp) = ft.token.parent()?.ancestors().find_map(|node| {
+    if let Some(str_bind) = ast::StrBind::cast(node.clone()) {
+      return Some((str_bind.ascription_tail(), str_bind.eq_str_exp()?.str_exp()));
+    }
+    let fun_bind = ast::FunctorBind::cast(node)?;
+    Some((fun_bind.ascription_tail(), fun_bind.body()))
+  })?;
+  let sig_name = match tail?.sig_exp()? {
+    ast::SigExp::NameSigExp(sig_exp) => str_util::Name::new(sig_exp.name()?.text()),
+    _ => return None,
+  };
+  let str_exp = match str_exp? {
+    ast::StrExp::StructStrExp(str_exp) => str_exp,
+    _ => return None,
+  };
+  let offset = ft.token.text_range().start();
+  if str_exp.decs().any(|dec| dec.syntax().text_range().contains(offset)) {
+    return None;
+  }
+  let file = ft.file;
+  let str_exp_range = str_exp.syntax().text_range();
+  let missing: Vec<_> = file
+    .statics_errors
+    .iter()
+    .filter(|e| {
+      let ptr = file.syntax.lower.ptrs.hir_to_ast(e.idx());
+      ptr.map_or(false, |ptr| ptr.text_range() == str_exp_range)
+    })
+    .filter_map(sml_statics::Error::missing)
+    .collect();
+  if missing.is_empty() {
+    return None;
+  }
+  let sig =
+    file.info.basis().sig_env.get(&sig_name).or_else(|| file.scope.sig_env.get(&sig_name))?;
+  let end = str_exp.end_kw()?;
+  // if the `end` is on its own line, put the new items on lines before it, indented one more level.
+  let line_indent = end
+    .prev_token()
+    .filter(|tok| tok.kind() == SyntaxKind::Whitespace)
+    .and_then(|tok| Some(tok.text().rsplit_once('\n')?.1.len()));
+  let (start, indent, mut new_text) = match line_indent {
+    Some(n) => (end.text_range().start() - TextSize::try_from(n).ok()?, n + 2, String::new()),
+    None => (end.text_range().start(), 2, "\n".to_owned()),
+  };
+  let mut printer = Printer::new(st, file, path, Target::Struct, &sig_name);
+  printer.set_indent(indent);
+  for (name, item) in printer.items(&sig.env) {
+    let is_missing = missing.iter().any(|&(m_item, m_name)| {
+      m_name == name
+        && matches!(
+          (m_item, &item),
+          (Item::Struct, env_text::Item::Str(_))
+            | (Item::Ty, env_text::Item::Ty(_))
+            | (Item::Val, env_text::Item::Val(_))
+        )
+    });
+    if is_missing {
+      printer.item(name, item)?;
+      printer.buf().push('\n');
+    }
+  }
+  new_text.push_str(printer.finish().as_str());
+  let range = file.syntax.pos_db.range_utf16(TextRange::empty(start))?;
+  let edit = TextEdit { range, new_text };
+  Some(CodeAction {
+    title: "Add missing items".to_owned(),
+    kind: CodeActionKind::QuickFix,
+    edits: vec![edit],
+  })
+}
+
+/// Returns a structure name for the signature name, like `HashTable` for `HASH_TABLE`.
+fn struct_name(sig_name: &str) -> String {
+  let base = sig_name.strip_suffix("_SIG").filter(|x| !x.is_empty()).unwrap_or(sig_name);
+  if base.chars().any(|c| c.is_ascii_lowercase()) {
+    return base.to_owned();
+  }
+  base
+    .split('_')
+    .flat_map(|part| {
+      let mut chars = part.chars();
+      let fst = chars.next().map(|c| c.to_ascii_uppercase());
+      fst.into_iter().chain(chars.map(|c| c.to_ascii_lowercase()))
+    })
+    .collect()
+}
@@ -4,20 +4,33 @@
 // TODO remove once rustfmt support lands
 #![allow(clippy::manual_let_else)]
 
+mod baseline;
 mod diagnostic;
+mod env_text;
+mod explain_ty;
+mod gen_sig;
+mod impl_sig;
+mod lint;
 mod matcher;
+mod qualify;
+mod similar;
 mod source_files;
+mod suppress;
+mod ty_annot;
+mod ty_search;
 
 use paths::{PathId, PathMap, WithPath};
 use sml_statics_types::{def, env::Env};
 use sml_syntax::ast::{self, AstNode as _, SyntaxNodePtr};
+use sml_syntax::{rowan::NodeOrToken, SyntaxKind, SyntaxNode, SyntaxToken};
 use std::process::{Command, Stdio};
 use std::{error::Error, fmt, io::Write as _};
 use text_pos::{PositionDb, PositionUtf16, RangeUtf16};
 use text_size_util::TextRange;
 
-pub use crate::diagnostic::{Diagnostic, Options};
+pub use crate::diagnostic::{Diagnostic, Options, Related};
 pub use mlb_statics::StdBasis;
+pub use sml_statics::Tag;
 
 /// The url to go to for information about diagnostics.
 pub const URL: &str = "https://github.com/azdavis/millet/blob/main/docs/diagnostics";
@@ -29,6 +42,11 @@ pub struct Analysis {
   diagnostics_options: diagnostic::Options,
   source_files: PathMap<mlb_statics::SourceFile>,
   syms_tys: sml_statics_types::St,
+  lang: config::lang::Language,
+  raises: sml_raise::Raises,
+  /// The lints for each source file, so code actions need not get them again.
+  lints: PathMap<Vec<sml_lint::Error>>,
+  mlb_errors: Vec<mlb_statics::Error>,
 }
 
 impl Analysis {
@@ -40,6 +58,10 @@ impl Analysis {
       std_basis,
       diagnostics_options,
       source_files: PathMap::default(),
+      lang: config::lang::Language::default(),
+      raises: sml_raise::Raises::default(),
+      lints: PathMap::default(),
+      mlb_errors: Vec::new(),
     }
   }
 
@@ -61,6 +83,16 @@ impl Analysis {
     self.get_many_impl(input, |_, b| Some(b))
   }
 
+  /// Returns a mapping from source paths to diagnostics, like [`Self::get_many_text_range`], but
+  /// without analyzing the input again. Use after [`Self::update_one`].
+  #[must_use]
+  pub fn diagnostics_text_range(
+    &self,
+    input: &input::Input,
+  ) -> PathMap<Vec<Diagnostic<TextRange>>> {
+    self.diagnostics_impl(input, |_, b| Some(b))
+  }
+
   fn get_many_impl<F, R>(&mut self, input: &input::Input, f: F) -> PathMap<Vec<Diagnostic<R>>>
   where
     F: Fn(&text_pos::PositionDb, text_size_util::TextRange) -> Option<R>,
@@ -87,8 +119,18 @@ impl Analysis {
       )
     });
     self.source_files = res.source_files;
+    self.mlb_errors = res.mlb_errors;
+    self.lang = input.lang.clone();
+    self.update_raises();
+    self.diagnostics_impl(input, f)
+  }
+
+  fn diagnostics_impl<F, R>(&self, input: &input::Input, f: F) -> PathMap<Vec<Diagnostic<R>>>
+  where
+    F: Fn(&text_pos::PositionDb, text_size_util::TextRange) -> Option<R>,
+  {
     std::iter::empty()
-      .chain(res.mlb_errors.into_iter().filter_map(|err| {
+      .chain(self.mlb_errors.iter().filter_map(|err| {
         let path = err.path();
         let group = input.groups.get(&path).expect("no such group");
         let err = Diagnostic {
@@ -96,17 +138,27 @@ impl Analysis {
           message: err.to_string(),
           code: err.code(),
           severity: err.severity(),
+          related: Vec::new(),
+          tag: None,
         };
         Some((path, vec![err]))
       }))
-      .chain(self.source_files.iter().map(|(&path, file)| {
-        let ds = diagnostic::source_file(file, &self.syms_tys, self.diagnostics_options, &f);
+      .chain(self.source_files.keys().map(|&path| {
+        let ds = diagnostic::source_file(
+          &self.source_files,
+          path,
+          &self.syms_tys,
+          self.lints(path),
+          input,
+          self.diagnostics_options,
+          &f,
+        );
         (path, ds)
       }))
       .map(|(p, ds)| {
         let iter = ds.into_iter().filter_map(|mut d| {
-          match input.severities.get(&d.code) {
-            Some(&Some(sev)) => d.severity = sev,
+          match input.severities.get(p, d.code) {
+            Some(Some(sev)) => d.severity = sev,
             Some(None) => return None,
             None => {}
           }
@@ -123,6 +175,37 @@ impl Analysis {
     let source_file = self.source_files.get_mut(&path).expect("no source file");
     let contents = input.sources.get(&path).expect("no contents");
     mlb_statics::update_one(&mut self.syms_tys, &input.lang, source_file, path, contents);
+    let source_file = &self.source_files[&path];
+    let (info, arenas) = (&source_file.info, &source_file.syntax.lower.arenas);
+    self.raises.update(path, info, arenas);
+    let lints = sml_lint::get(&self.syms_tys, &self.lang, &self.raises, path, info, arenas);
+    self.lints.insert(path, lints);
+  }
+
+  fn update_raises(&mut self) {
+    let files =
+      self.source_files.iter().map(|(&path, file)| (path, &file.info, &file.syntax.lower.arenas));
+    self.raises = elapsed::log("sml_raise::get", || sml_raise::get(files));
+    self.update_lints();
+  }
+
+  /// Gets the lints again for every file, since they may depend on the raises of other files.
+  fn update_lints(&mut self) {
+    self.lints = elapsed::log("sml_lint::get", || {
+      self
+        .source_files
+        .iter()
+        .map(|(&path, file)| {
+          let info = &file.info;
+          let arenas = &file.syntax.lower.arenas;
+          (path, sml_lint::get(&self.syms_tys, &self.lang, &self.raises, path, info, arenas))
+        })
+        .collect()
+    });
+  }
+
+  fn lints(&self, path: PathId) -> &[sml_lint::Error] {
+    self.lints.get(&path).map_or(&[], Vec::as_slice)
   }
 
   /// Returns a Markdown string with information about this position.
@@ -131,13 +214,25 @@ impl Analysis {
     let ft = source_files::file_and_token(&self.source_files, pos)?;
     let mut parts = Vec::<&str>::new();
     let ty_md: Option<String>;
+    let reasons_md: Option<String>;
+    let raises_md: Option<String>;
     let range = match ft.get_ptr_and_idx() {
       Some((ptr, idx)) => {
         ty_md = ft.file.info.get_ty_md(&self.syms_tys, idx, self.diagnostics_options.lines);
         parts.extend(ty_md.as_deref());
+        let lines = self.diagnostics_options.lines;
+        let reasons = explain_ty::get(&self.source_files, &self.syms_tys, ft.file, idx, lines);
+        reasons_md = explain_ty::md(pos.path, &reasons);
+        parts.extend(reasons_md.as_deref());
         let this = def::Def::Path(def::Path::Regular(pos.path), idx);
         parts.extend(self.get_doc(this));
         let defs = ft.file.info.get_defs(idx);
+        raises_md = std::iter::once(this)
+          .chain(defs.iter().copied())
+          .find_map(|def| self.raises.get(def))
+          .filter(|exns| !exns.is_empty())
+          .map(|exns| format!("may raise: {}", exns.join(", ")));
+        parts.extend(raises_md.as_deref());
         parts.extend(defs.into_iter().filter_map(|def| self.get_doc(def)));
         ptr.text_range()
       }
@@ -153,6 +248,17 @@ impl Analysis {
     Some((parts.join("\n\n---\n\n"), range))
   }
 
+  /// Returns the type of the expression or pattern at this position, and the range of it. If it is
+  /// polymorphic, this is the most general type, as on hover, not the type of this usage.
+  #[must_use]
+  pub fn get_ty(&self, pos: WithPath<PositionUtf16>) -> Option<(String, RangeUtf16)> {
+    let ft = source_files::file_and_token(&self.source_files, pos)?;
+    let (ptr, idx) = ft.get_ptr_and_idx()?;
+    let ty = ft.file.info.get_ty_str(&self.syms_tys, idx, self.diagnostics_options.lines)?;
+    let range = ft.file.syntax.pos_db.range_utf16(ptr.text_range())?;
+    Some((ty, range))
+  }
+
   fn get_doc(&self, def: def::Def) -> Option<&str> {
     match def {
       def::Def::Path(path, idx) => {
@@ -166,6 +272,16 @@ impl Analysis {
     }
   }
 
+  /// Returns the reasons the item at this position has its type, i.e. the places that caused the
+  /// type variables in its type to be solved.
+  #[must_use]
+  pub fn explain_ty(&self, pos: WithPath<PositionUtf16>) -> Option<Vec<TyReason>> {
+    let ft = source_files::file_and_token(&self.source_files, pos)?;
+    let (_, idx) = ft.get_ptr_and_idx()?;
+    let lines = self.diagnostics_options.lines;
+    Some(explain_ty::get(&self.source_files, &self.syms_tys, ft.file, idx, lines))
+  }
+
   /// Returns the range of the definition of the item at this position.
   #[must_use]
   pub fn get_defs(&self, pos: WithPath<PositionUtf16>) -> Option<Vec<WithPath<RangeUtf16>>> {
@@ -216,6 +332,61 @@ impl Analysis {
     Some((range, case.to_string()))
   }
 
+  /// Returns code actions available at this position, other than [`Self::fill_case`].
+  #[must_use]
+  pub fn code_actions(&self, pos: WithPath<PositionUtf16>) -> Vec<CodeAction> {
+    match source_files::file_and_token(&self.source_files, pos) {
+      Some(ft) => {
+        let mut ret = ty_annot::get(&self.syms_tys, &ft);
+        ret.extend(gen_sig::get(&self.syms_tys, pos.path, &ft));
+        ret.extend(impl_sig::get(&self.syms_tys, pos.path, &ft));
+        ret.extend(qualify::get(pos.path, &ft));
+        ret.extend(similar::get(&ft));
+        ret.extend(lint::get(self.lints(pos.path), &ft));
+        ret
+      }
+      None => Vec::new(),
+    }
+  }
+
+  /// Returns the quick fixes for every diagnostic in the source `path` that needs no choices to
+  /// fix. The edits of different fixes may overlap.
+  ///
+  /// These come from the same producers as the quick fixes of [`Self::code_actions`], but only the
+  /// machine-applicable ones are kept: the fixes for lints, and the fix for an undefined name when
+  /// it is the only fix offered for that name, i.e. it has exactly one similar name or exactly one
+  /// structure to qualify it with. [`Self::fill_case`] is never machine-applicable, since the arms
+  /// it adds have holes for bodies.
+  #[must_use]
+  pub fn fixes(&self, path: PathId) -> Vec<CodeAction> {
+    let file = match self.source_files.get(&path) {
+      Some(x) => x,
+      None => return Vec::new(),
+    };
+    let mut ret: Vec<_> =
+      lint::all(self.lints(path), file).into_iter().map(|(_, action)| action).collect();
+    let mut undefined = similar::all(file);
+    undefined.extend(qualify::undefined_all(path, file));
+    let only: Vec<_> = undefined
+      .iter()
+      .enumerate()
+      .map(|(idx, (range, _))| {
+        undefined
+          .iter()
+          .enumerate()
+          .all(|(other_idx, (other, _))| other_idx == idx || other.intersect(*range).is_none())
+      })
+      .collect();
+    ret.extend(
+      undefined
+        .into_iter()
+        .zip(only)
+        .filter_map(|((_, action), only)| only.then_some(action))
+        .filter(|action| matches!(action.kind, CodeActionKind::QuickFix)),
+    );
+    ret
+  }
+
   /// Format the given file, and return the end position of the file.
   ///
   /// # Errors
@@ -231,38 +402,105 @@ impl Analysis {
     tab_size: u32,
   ) -> Result<(String, PositionUtf16), FormatError> {
     let file = self.source_files.get(&path).ok_or(FormatError::NoFile)?;
-    let buf = match self.diagnostics_options.format {
-      config::init::FormatEngine::None => return Err(FormatError::Disabled),
-      config::init::FormatEngine::Naive => {
-        sml_naive_fmt::get(&file.syntax.parse.root, tab_size).map_err(FormatError::NaiveFmt)?
-      }
-      config::init::FormatEngine::Smlfmt => {
-        let contents = file.syntax.parse.root.syntax().to_string();
-        let mut prog = Command::new("smlfmt")
-          .stdin(Stdio::piped())
-          .stdout(Stdio::piped())
-          .spawn()
-          .map_err(SmlfmtError::Spawn)?;
-        let mut stdin = prog.stdin.take().unwrap();
-        stdin.write_all(contents.as_bytes()).map_err(SmlfmtError::WriteAll)?;
-        // explicitly drop to close it
-        drop(stdin);
-        let output = prog.wait_with_output().map_err(SmlfmtError::Wait)?;
-        if !output.status.success() {
-          return Err(SmlfmtError::Unsuccessful(output.stderr).into());
-        }
-        String::from_utf8(output.stdout).map_err(SmlfmtError::Utf8)?
-      }
-    };
+    let buf = format_root(self.diagnostics_options.format, &file.syntax.parse.root, tab_size)?;
     Ok((buf, file.syntax.pos_db.end_position_utf16()))
   }
 
+  /// Format the given contents, which need not be a file in the analysis.
+  ///
+  /// # Errors
+  ///
+  /// When formatting the contents failed.
+  pub fn format_str(&self, contents: &str, tab_size: u32) -> Result<String, FormatError> {
+    let mut fix_env = sml_fixity::STD_BASIS.clone();
+    let (lex_errors, parse) =
+      sml_file_syntax::SourceFileSyntax::lex_and_parse(&mut fix_env, contents);
+    let engine = self.diagnostics_options.format;
+    // only the naive engine formats our syntax tree. smlfmt parses the contents itself, so it
+    // reports its own syntax errors.
+    let naive = matches!(engine, config::init::FormatEngine::Naive);
+    if naive && (!lex_errors.is_empty() || !parse.errors.is_empty()) {
+      return Err(FormatError::NaiveFmt(sml_naive_fmt::Error::Syntax));
+    }
+    format_root(engine, &parse.root, tab_size)
+  }
+
   /// Returns the `PositionDb` for the source `path`.
   #[must_use]
   pub fn source_pos_db(&self, path: PathId) -> Option<&PositionDb> {
     Some(&self.source_files.get(&path)?.syntax.pos_db)
   }
 
+  /// Returns the other source files with definitions the source `path` uses, sorted.
+  #[must_use]
+  pub fn source_deps(&self, path: PathId) -> Option<Vec<PathId>> {
+    Some(self.source_files.get(&path)?.deps(path))
+  }
+
+  /// Returns documentation for the symbols in the source `path`, for generating API docs.
+  #[must_use]
+  pub fn doc_items(&self, path: PathId) -> Option<Vec<DocItem>> {
+    let file = self.source_files.get(&path)?;
+    let uses: Vec<_> = file
+      .info
+      .defs()
+      .filter_map(|(idx, def)| Some((file.syntax.lower.ptrs.hir_to_ast(idx)?.text_range(), def)))
+      .collect();
+    let ret: Vec<_> = file
+      .info
+      .document_symbols(&self.syms_tys, path)
+      .into_iter()
+      .filter_map(|s| self.doc_item(path, &uses, s))
+      .collect();
+    Some(ret)
+  }
+
+  fn doc_item(
+    &self,
+    path: PathId,
+    uses: &[(TextRange, def::Def)],
+    sym: sml_statics::info::DocumentSymbol,
+  ) -> Option<DocItem> {
+    let file = self.source_files.get(&path)?;
+    let text_range = file.syntax.lower.ptrs.hir_to_ast(sym.idx)?.text_range();
+    let range = file.syntax.pos_db.range_utf16(text_range)?;
+    let this = def::Def::Path(def::Path::Regular(path), sym.idx);
+    // the definitions used inside this symbol, except those also inside this symbol.
+    let defs: fast_hash::FxHashSet<_> = uses
+      .iter()
+      .filter(|&&(r, def)| text_range.contains_range(r) && def != this)
+      .map(|&(_, def)| def)
+      .collect();
+    let mut links: Vec<_> = defs
+      .into_iter()
+      .filter_map(|def| source_files::path_and_range(&self.source_files, def.to_regular_idx()?))
+      .filter(|link| link.path != path || !range_contains(range, link.val))
+      .collect();
+    links.sort_unstable_by_key(|x| (x.path, x.val.start.line, x.val.start.col));
+    Some(DocItem {
+      name: sym.name,
+      kind: sym.kind,
+      detail: sym.detail,
+      doc: self.get_doc(this).map(ToOwned::to_owned),
+      range,
+      links,
+      children: sym.children.into_iter().filter_map(|s| self.doc_item(path, uses, s)).collect(),
+    })
+  }
+
+  /// Returns the fingerprint of a diagnostic with this code at this range in the source `path`, for
+  /// writing a baseline.
+  #[must_use]
+  pub fn fingerprint(
+    &self,
+    path: PathId,
+    code: ::diagnostic::Code,
+    range: TextRange,
+  ) -> Option<str_util::SmolStr> {
+    let file = self.source_files.get(&path)?;
+    Some(baseline::fingerprint(file.syntax.parse.root.syntax(), code, range))
+  }
+
   /// Returns the symbols for the file.
   #[must_use]
   pub fn document_symbols(&self, path: PathId) -> Option<Vec<DocumentSymbol>> {
@@ -276,6 +514,31 @@ impl Analysis {
     Some(ret)
   }
 
+  /// Returns the uses of definitions in the source `path`, for indexing. This includes uses of
+  /// local bindings, which are not document symbols, and of the standard basis.
+  #[must_use]
+  pub fn uses(&self, path: PathId) -> Option<Vec<Use>> {
+    let file = self.source_files.get(&path)?;
+    let root = file.syntax.parse.root.syntax();
+    let ret = file.info.defs().filter_map(|(idx, def)| {
+      let node = file.syntax.lower.ptrs.hir_to_ast(idx)?.to_node(root);
+      let tok = use_name(&node)?;
+      let range = file.syntax.pos_db.range_utf16(tok.text_range())?;
+      let def = match def.to_regular_idx() {
+        Some(idx) => {
+          let def_file = self.source_files.get(&idx.path)?;
+          let def_root = def_file.syntax.parse.root.syntax();
+          let def_node = def_file.syntax.lower.ptrs.hir_to_ast(idx.val)?.to_node(def_root);
+          let def_range = def_file.syntax.pos_db.range_utf16(name_range(&def_node, tok.text()))?;
+          UseDef::Source(idx.path.wrap(def_range))
+        }
+        None => UseDef::StdBasis(StdBasisDef(def)),
+      };
+      Some(Use { range, name: tok.text().to_owned(), def })
+    });
+    Some(ret.collect())
+  }
+
   /// Returns all references to the position.
   #[must_use]
   pub fn find_all_references(
@@ -346,6 +609,51 @@ impl Analysis {
     }));
   }
 
+  /// Returns the values in the workspace and std basis whose types match the type `query`, up to
+  /// the order of parameters (whether curried or tupled) and the renaming of type variables.
+  ///
+  /// Returns `None` if the query could not be parsed as a type.
+  #[must_use]
+  pub fn search_by_ty(&self, query: &str) -> Option<Vec<TySearchResult>> {
+    let std_basis_env = &self.std_basis.basis().env;
+    let workspace_envs: Vec<_> =
+      self.source_files.values().map(|file| &file.info.basis().env).collect();
+    let envs: Vec<_> =
+      workspace_envs.iter().copied().chain(std::iter::once(std_basis_env)).collect();
+    let query = ty_search::query(&envs, &self.syms_tys, query)?;
+    let mut workspace = Vec::<TySearchResult>::new();
+    for env in workspace_envs {
+      ty_search::get(&self.syms_tys, env, &query, &mut |name, val_info| {
+        workspace.push(self.ty_search_result(name, val_info));
+      });
+    }
+    workspace.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    let mut std_basis = Vec::<TySearchResult>::new();
+    ty_search::get(&self.syms_tys, std_basis_env, &query, &mut |name, val_info| {
+      std_basis.push(self.ty_search_result(name, val_info));
+    });
+    std_basis.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    workspace.append(&mut std_basis);
+    Some(workspace)
+  }
+
+  fn ty_search_result(
+    &self,
+    name: String,
+    val_info: &sml_statics_types::info::ValInfo,
+  ) -> TySearchResult {
+    let ty_scheme = val_info.ty_scheme.display(&self.syms_tys, config::DiagnosticLines::One);
+    TySearchResult {
+      name,
+      ty_scheme: ty_scheme.to_string(),
+      def: val_info
+        .defs
+        .iter()
+        .find_map(|def| source_files::path_and_range(&self.source_files, def.to_regular_idx()?)),
+      doc: val_info.defs.iter().find_map(|&def| self.get_doc(def)).map(ToOwned::to_owned),
+    }
+  }
+
   /// Returns all inlay hints for the range.
   #[must_use]
   pub fn inlay_hints(&self, range: WithPath<RangeUtf16>) -> Option<Vec<InlayHint>> {
@@ -442,6 +750,37 @@ impl Analysis {
   }
 }
 
+fn format_root(
+  engine: config::init::FormatEngine,
+  root: &ast::Root,
+  tab_size: u32,
+) -> Result<String, FormatError> {
+  let buf = match engine {
+    config::init::FormatEngine::None => return Err(FormatError::Disabled),
+    config::init::FormatEngine::Naive => {
+      sml_naive_fmt::get(root, tab_size).map_err(FormatError::NaiveFmt)?
+    }
+    config::init::FormatEngine::Smlfmt => {
+      let contents = root.syntax().to_string();
+      let mut prog = Command::new("smlfmt")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(SmlfmtError::Spawn)?;
+      let mut stdin = prog.stdin.take().unwrap();
+      stdin.write_all(contents.as_bytes()).map_err(SmlfmtError::WriteAll)?;
+      // explicitly drop to close it
+      drop(stdin);
+      let output = prog.wait_with_output().map_err(SmlfmtError::Wait)?;
+      if !output.status.success() {
+        return Err(SmlfmtError::Unsuccessful(output.stderr).into());
+      }
+      String::from_utf8(output.stdout).map_err(SmlfmtError::Utf8)?
+    }
+  };
+  Ok(buf)
+}
+
 fn inlay_hint_pat(
   st: &sml_statics_types::St,
   file: &mlb_statics::SourceFile,
@@ -497,6 +836,22 @@ impl From<SmlfmtError> for FormatError {
   }
 }
 
+impl fmt::Display for FormatError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      FormatError::Disabled => f.write_str("formatting is disabled"),
+      FormatError::NoFile => f.write_str("no such file"),
+      FormatError::NaiveFmt(sml_naive_fmt::Error::Syntax) => {
+        f.write_str("cannot format a file with syntax errors")
+      }
+      FormatError::NaiveFmt(sml_naive_fmt::Error::Comments(_)) => {
+        f.write_str("cannot format comments in some positions")
+      }
+      FormatError::Smlfmt(e) => e.fmt(f),
+    }
+  }
+}
+
 impl fmt::Display for SmlfmtError {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self {
@@ -542,23 +897,97 @@ pub struct DocumentSymbol {
   pub children: Vec<DocumentSymbol>,
 }
 
+fn range_contains(outer: RangeUtf16, inner: RangeUtf16) -> bool {
+  let pos = |p: PositionUtf16| (p.line, p.col);
+  pos(outer.start) <= pos(inner.start) && pos(inner.end) <= pos(outer.end)
+}
+
 fn symbol(
   file: &sml_file_syntax::SourceFileSyntax,
   sym: sml_statics::info::DocumentSymbol,
 ) -> Option<DocumentSymbol> {
-  let text_range = file.lower.ptrs.hir_to_ast(sym.idx)?.text_range();
-  let range = file.pos_db.range_utf16(text_range)?;
+  let node = file.lower.ptrs.hir_to_ast(sym.idx)?.to_node(file.parse.root.syntax());
+  let range = file.pos_db.range_utf16(node.text_range())?;
+  let selection_range = file.pos_db.range_utf16(name_range(&node, sym.name.as_str()))?;
   Some(DocumentSymbol {
     name: sym.name,
     kind: sym.kind,
     detail: sym.detail,
     range,
-    // TODO improve
-    selection_range: range,
+    selection_range,
     children: sym.children.into_iter().filter_map(|s| symbol(file, s)).collect(),
   })
 }
 
+/// Returns the range of the first token in the node that is the name, e.g. `f` in `fun f x = x`, or
+/// the range of the whole node if there is none.
+fn name_range(node: &SyntaxNode, name: &str) -> TextRange {
+  node
+    .descendants_with_tokens()
+    .filter_map(NodeOrToken::into_token)
+    .find(|tok| tok.text() == name)
+    .map_or(node.text_range(), |tok| tok.text_range())
+}
+
+/// Returns the name token of the node that uses a definition, without any structure qualifiers,
+/// e.g. `map` in `List.map` or `+` in `1 + 2`.
+fn use_name(node: &SyntaxNode) -> Option<SyntaxToken> {
+  node.children_with_tokens().find_map(|child| match child {
+    NodeOrToken::Node(node) => match node.kind() {
+      SyntaxKind::Path => {
+        node.children().filter(|x| x.kind() == SyntaxKind::NameStarEqDot).last()?.first_token()
+      }
+      SyntaxKind::NameStarEq => node.first_token(),
+      _ => None,
+    },
+    NodeOrToken::Token(tok) => (tok.kind() == SyntaxKind::Name).then_some(tok),
+  })
+}
+
+/// A use of a definition in a source file.
+#[derive(Debug)]
+pub struct Use {
+  /// The range of the name used.
+  pub range: RangeUtf16,
+  /// The name used, without any structure qualifiers.
+  pub name: String,
+  /// The definition.
+  pub def: UseDef,
+}
+
+/// Where the definition of a use is.
+#[derive(Debug)]
+pub enum UseDef {
+  /// In a source file, at the range of the name.
+  Source(WithPath<RangeUtf16>),
+  /// In the standard basis.
+  StdBasis(StdBasisDef),
+}
+
+/// A definition in the standard basis. Equal values are the same definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StdBasisDef(def::Def);
+
+/// Documentation for a symbol.
+#[derive(Debug)]
+pub struct DocItem {
+  /// The name of the symbol.
+  pub name: String,
+  /// What kind of symbol this is.
+  pub kind: sml_namespace::SymbolKind,
+  /// The type or type scheme of this symbol, if any.
+  pub detail: Option<String>,
+  /// The doc comment on this symbol, if any.
+  pub doc: Option<String>,
+  /// The range of the whole symbol.
+  pub range: text_pos::RangeUtf16,
+  /// The definitions this symbol uses from outside itself, like the signature a structure ascribes
+  /// to, sorted.
+  pub links: Vec<WithPath<text_pos::RangeUtf16>>,
+  /// Children of this symbol.
+  pub children: Vec<DocItem>,
+}
+
 /// An inlay hint.
 #[derive(Debug)]
 pub struct InlayHint {
@@ -579,6 +1008,59 @@ pub enum InlayHintKind {
   Ty,
 }
 
+/// A code action, which edits a single file.
+#[derive(Debug)]
+pub struct CodeAction {
+  /// The title.
+  pub title: String,
+  /// The kind.
+  pub kind: CodeActionKind,
+  /// The edits to the file.
+  pub edits: Vec<TextEdit>,
+}
+
+/// A kind of code action.
+#[derive(Debug, Clone, Copy)]
+pub enum CodeActionKind {
+  /// A fix for a diagnostic.
+  QuickFix,
+  /// A rewrite of some code.
+  Refactor,
+}
+
+/// An edit to a file.
+#[derive(Debug, Clone)]
+pub struct TextEdit {
+  /// The range to replace.
+  pub range: RangeUtf16,
+  /// The text to replace it with.
+  pub new_text: String,
+}
+
+/// A value found by [`Analysis::search_by_ty`].
+#[derive(Debug)]
+pub struct TySearchResult {
+  /// The qualified name of the value.
+  pub name: String,
+  /// The type scheme of the value.
+  pub ty_scheme: String,
+  /// The definition site of the value, if it was defined in the workspace.
+  pub def: Option<WithPath<RangeUtf16>>,
+  /// Markdown documentation for the value.
+  pub doc: Option<String>,
+}
+
+/// A reason found by [`Analysis::explain_ty`].
+#[derive(Debug)]
+pub struct TyReason {
+  /// The range of the code that caused a type variable to be solved.
+  pub range: WithPath<RangeUtf16>,
+  /// The code, shortened to a single line.
+  pub code: String,
+  /// The type that the type variable was solved to.
+  pub ty: String,
+}
+
 /// A completion item.
 #[derive(Debug)]
 pub struct CompletionItem {
@@ -0,0 +1,86 @@
+//! Quick fixes for lints.
+
+use crate::source_files::FileAndToken;
+use crate::{CodeAction, CodeActionKind, TextEdit};
+use sml_syntax::ast::{self, AstNode as _};
+use text_size_util::TextRange;
+
+/// Offer to fix each of the lints, from the file at the cursor, at the cursor that has a mechanical
+/// fix.
+pub(crate) fn get(lints: &[sml_lint::Error], ft: &FileAndToken<'_>) -> Vec<CodeAction> {
+  let offset = ft.token.text_range().start();
+  all(lints, ft.file)
+    .into_iter()
+    .filter_map(|(range, action)| range.contains_inclusive(offset).then_some(action))
+    .collect()
+}
+
+/// Returns the fix for each of the lints, from the file, that has a mechanical fix, with the range
+/// of the code that has the lint.
+pub(crate) fn all(
+  lints: &[sml_lint::Error],
+  file: &mlb_statics::SourceFile,
+) -> Vec<(TextRange, CodeAction)> {
+  let root = file.syntax.parse.root.syntax();
+  lints
+    .iter()
+    .filter_map(|error| {
+      let node = file.syntax.lower.ptrs.hir_to_ast(error.idx())?.to_node(root);
+      let fix = error.fix()?;
+      let mut new_text = String::new();
+      for part in fix.parts {
+        match part {
+          sml_lint::FixPart::Str(s) => new_text.push_str(s),
+          sml_lint::FixPart::Syntax { idx, atomic } => {
+            let node = file.syntax.lower.ptrs.hir_to_ast(idx)?.to_node(root);
+            let parens = atomic && !ast::Exp::cast(node.clone()).map_or(true, |x| is_atomic(&x));
+            if parens {
+              new_text.push('(');
+            }
+            new_text.push_str(node.text().to_string().trim());
+            if parens {
+              new_text.push(')');
+            }
+          }
+        }
+      }
+      let range = file.syntax.pos_db.range_utf16(node.text_range())?;
+      let action = CodeAction {
+        title: fix.title.to_owned(),
+        kind: CodeActionKind::QuickFix,
+        edits: vec![TextEdit { range, new_text }],
+      };
+      Some((node.text_range(), action))
+    })
+    .collect()
+}
+
+fn is_atomic(exp: &ast::Exp) -> bool {
+  match exp {
+    ast::Exp::SConExp(_)
+    | ast::Exp::PathExp(_)
+    | ast::Exp::RecordExp(_)
+    | ast::Exp::SelectorExp(_)
+    | ast::Exp::ParenExp(_)
+    | ast::Exp::TupleExp(_)
+    | ast::Exp::ListExp(_)
+    | ast::Exp::VectorExp(_)
+    | ast::Exp::SeqExp(_)
+    | ast::Exp::LetExp(_)
+    | ast::Exp::HoleExp(_)
+    | ast::Exp::WildcardExp(_) => true,
+    ast::Exp::OpAndalsoExp(_)
+    | ast::Exp::OpOrelseExp(_)
+    | ast::Exp::AppExp(_)
+    | ast::Exp::InfixExp(_)
+    | ast::Exp::TypedExp(_)
+    | ast::Exp::AndalsoExp(_)
+    | ast::Exp::OrelseExp(_)
+    | ast::Exp::HandleExp(_)
+    | ast::Exp::RaiseExp(_)
+    | ast::Exp::IfExp(_)
+    | ast::Exp::WhileExp(_)
+    | ast::Exp::CaseExp(_)
+    | ast::Exp::FnExp(_) => false,
+  }
+}
@@ -0,0 +1,299 @@
+//! Code actions for qualifying names with the structures that define them.
+
+use crate::source_files::FileAndToken;
+use crate::{CodeAction, CodeActionKind, TextEdit};
+use fast_hash::FxHashSet;
+use sml_statics_types::{def, env::Env, item::Item};
+use sml_syntax::ast::{self, AstNode as _, SyntaxNodePtr};
+use sml_syntax::{SyntaxKind, SyntaxNode};
+use text_size_util::{TextRange, TextSize};
+
+pub(crate) fn get(path: paths::PathId, ft: &FileAndToken<'_>) -> Vec<CodeAction> {
+  let mut ret: Vec<_> = remove_open(ft).into_iter().collect();
+  ret.extend(qualify_undefined(path, ft));
+  ret
+}
+
+/// Remove the structure under the cursor from its `open`, or the whole `open` if it opens only one
+/// structure, qualifying the names that were resolved through it.
+///
+/// Not offered if an infix use of a name may have been resolved through it, since qualifying that
+/// would mean rewriting the infix application.
+fn remove_open(ft: &FileAndToken<'_>) -> Option<CodeAction> {
+  let open_dec = ft.token.parent()?.ancestors().find_map(ast::OpenDec::cast)?;
+  let all_paths: Vec<_> = open_dec.paths().collect();
+  let offset = ft.token.text_range().start();
+  let open_path = match all_paths.iter().find(|x| x.syntax().text_range().contains(offset)) {
+    Some(x) => x.clone(),
+    None => match all_paths.as_slice() {
+      [x] => x.clone(),
+      _ => return None,
+    },
+  };
+  let names = path_names(&open_path)?;
+  let file = ft.file;
+  let env = opened_env(file, open_dec.syntax(), &open_path)?;
+  let container = open_dec.syntax().ancestors().find(|x| {
+    matches!(
+      x.kind(),
+      SyntaxKind::Root
+        | SyntaxKind::StructStrExp
+        | SyntaxKind::LetExp
+        | SyntaxKind::LetStrExp
+        | SyntaxKind::LocalDec
+    )
+  })?;
+  let open_end = open_dec.syntax().text_range().end();
+  let qualifier: String = names.iter().flat_map(|x| [x.as_str(), "."]).collect();
+  let mut edits = Vec::<TextEdit>::new();
+  for node in container.descendants() {
+    if node.text_range().start() < open_end {
+      continue;
+    }
+    if infix_name(&node).map_or(false, |name| env.val_env.get(&name).is_some()) {
+      return None;
+    }
+    let (path, item) = match path_and_item(&node) {
+      Some(x) => x,
+      None => continue,
+    };
+    let idx = match file.syntax.lower.ptrs.ast_to_hir(&SyntaxNodePtr::new(&node)) {
+      Some(x) => x,
+      None => continue,
+    };
+    let path_names = match path_names(&path) {
+      Some(x) => x,
+      None => continue,
+    };
+    let defs = item_defs(env, &path_names, item);
+    if defs.is_empty() || file.info.get_defs(idx).is_disjoint(&defs) {
+      continue;
+    }
+    let range = TextRange::empty(path.syntax().text_range().start());
+    let range = file.syntax.pos_db.range_utf16(range)?;
+    edits.push(TextEdit { range, new_text: qualifier.clone() });
+  }
+  let qualifier = qualifier.trim_end_matches('.');
+  let (remove, title) = if all_paths.len() == 1 {
+    // remove the whole `open`, along with the whitespace after it.
+    let mut remove = open_dec.syntax().text_range();
+    if let Some(tok) = open_dec.syntax().last_token().and_then(|x| x.next_token()) {
+      if tok.kind() == SyntaxKind::Whitespace {
+        remove = remove.cover(tok.text_range());
+      }
+    }
+    (remove, format!("Remove `open {qualifier}` and qualify names from it"))
+  } else {
+    // remove just this path, along with the whitespace separating it from the others.
+    let mut remove = open_path.syntax().text_range();
+    let is_last = all_paths.last().map_or(false, |x| x.syntax() == open_path.syntax());
+    let tok = if is_last {
+      open_path.syntax().first_token().and_then(|x| x.prev_token())
+    } else {
+      open_path.syntax().last_token().and_then(|x| x.next_token())
+    };
+    if let Some(tok) = tok.filter(|x| x.kind() == SyntaxKind::Whitespace) {
+      remove = remove.cover(tok.text_range());
+    }
+    (remove, format!("Remove `{qualifier}` from `open` and qualify names from it"))
+  };
+  let range = file.syntax.pos_db.range_utf16(remove)?;
+  edits.push(TextEdit { range, new_text: String::new() });
+  Some(CodeAction { title, kind: CodeActionKind::Refactor, edits })
+}
+
+/// Returns the name of the infix operator applied by the node, if it is an infix application.
+fn infix_name(node: &SyntaxNode) -> Option<str_util::Name> {
+  let node = node.clone();
+  let name = match node.kind() {
+    SyntaxKind::InfixExp => ast::InfixExp::cast(node)?.name_star_eq()?,
+    SyntaxKind::InfixPat => ast::InfixPat::cast(node)?.name_star_eq()?,
+    _ => return None,
+  };
+  Some(str_util::Name::new(name.token.text()))
+}
+
+/// Returns the env for the structure opened by the `open` at the node.
+fn opened_env<'a>(
+  file: &'a mlb_statics::SourceFile,
+  node: &SyntaxNode,
+  path: &ast::Path,
+) -> Option<&'a Env> {
+  // the structure may be a sibling of the `open` inside some enclosing structures, so try looking
+  // it up from the innermost enclosing structure out to the top level.
+  let mut enclosing: Vec<_> = node
+    .ancestors()
+    .filter_map(ast::StrBind::cast)
+    .filter_map(|x| Some(str_util::Name::new(x.name()?.text())))
+    .collect();
+  enclosing.reverse();
+  let top = &file.info.basis().env;
+  (0..=enclosing.len())
+    .rev()
+    .find_map(|n| {
+      let mut env = top;
+      for name in &enclosing[..n] {
+        env = env.str_env.get(name)?;
+      }
+      crate::get_env(env, path)
+    })
+    .or_else(|| crate::get_env(&file.scope.env, path))
+}
+
+/// Returns the path in the node, and what kind of item it refers to.
+pub(crate) fn path_and_item(node: &SyntaxNode) -> Option<(ast::Path, Item)> {
+  let node = node.clone();
+  let ret = match node.kind() {
+    SyntaxKind::PathExp => (ast::PathExp::cast(node)?.path()?, Item::Val),
+    SyntaxKind::ConPat => (ast::ConPat::cast(node)?.path()?, Item::Val),
+    SyntaxKind::ConTy => (ast::ConTy::cast(node)?.path()?, Item::Ty),
+    SyntaxKind::OneArgConTy => (ast::OneArgConTy::cast(node)?.path()?, Item::Ty),
+    SyntaxKind::PathStrExp => (ast::PathStrExp::cast(node)?.path()?, Item::Struct),
+    _ => return None,
+  };
+  Some(ret)
+}
+
+fn path_names(path: &ast::Path) -> Option<Vec<str_util::Name>> {
+  path
+    .name_star_eq_dots()
+    .map(|x| {
+      // NOTE: assumes that a NameStarEqDot's first token is the name
+      let tok = x.syntax().first_token()?;
+      Some(str_util::Name::new(tok.text()))
+    })
+    .collect()
+}
+
+/// Returns the defs of the item at the path in the env.
+fn item_defs(mut env: &Env, names: &[str_util::Name], item: Item) -> FxHashSet<def::Def> {
+  let mut ret = FxHashSet::<def::Def>::default();
+  let (last, prefix) = match names.split_last() {
+    Some(x) => x,
+    None => return ret,
+  };
+  for name in prefix {
+    env = match env.str_env.get(name) {
+      Some(x) => x,
+      None => return ret,
+    };
+  }
+  match item {
+    Item::Val => ret.extend(env.val_env.get(last).into_iter().flat_map(|x| x.defs.iter().copied())),
+    Item::Ty => ret.extend(env.ty_env.get(last).and_then(|x| x.def)),
+    Item::Struct => ret.extend(env.str_env.get(last).and_then(|x| x.def)),
+    Item::TyVar | Item::Sig | Item::Functor => {}
+  }
+  ret
+}
+
+/// Offer to qualify the undefined name under the cursor with each structure that defines it before
+/// the name.
+fn qualify_undefined(path: paths::PathId, ft: &FileAndToken<'_>) -> Vec<CodeAction> {
+  let offset = ft.token.text_range().start();
+  undefined_all(path, ft.file)
+    .into_iter()
+    .filter_map(|(range, action)| range.contains_inclusive(offset).then_some(action))
+    .collect()
+}
+
+/// Returns the qualifications of each undefined name, from the file at `path`, with each structure
+/// that defines it before the name, with the range of the code that has the undefined name.
+pub(crate) fn undefined_all(
+  path: paths::PathId,
+  file: &mlb_statics::SourceFile,
+) -> Vec<(TextRange, CodeAction)> {
+  let mut ret = Vec::<(TextRange, CodeAction)>::new();
+  for error in &file.statics_errors {
+    let (item, name) = match error.undefined() {
+      Some(x) => x,
+      None => continue,
+    };
+    let node = match file.syntax.lower.ptrs.hir_to_ast(error.idx()) {
+      Some(ptr) => ptr.to_node(file.syntax.parse.root.syntax()),
+      None => continue,
+    };
+    let name_path = match path_and_item(&node) {
+      Some((name_path, _)) => name_path,
+      None => continue,
+    };
+    // only qualify names that aren't already qualified.
+    if path_names(&name_path).map_or(true, |names| names.as_slice() != std::slice::from_ref(name)) {
+      continue;
+    }
+    let start = name_path.syntax().text_range().start();
+    let range = match file.syntax.pos_db.range_utf16(TextRange::empty(start)) {
+      Some(x) => x,
+      None => continue,
+    };
+    // the basis has everything in this file, including what is defined after the name.
+    let before = |def: def::Def| defined_before(file, path, start, def);
+    let mut qualifiers = std::collections::BTreeSet::<String>::new();
+    for env in [&file.scope.env, &file.info.basis().env] {
+      defining_structures(env, &mut Vec::new(), &before, name, item, &mut qualifiers);
+    }
+    ret.extend(qualifiers.into_iter().map(|qualifier| {
+      let action = CodeAction {
+        title: format!("Qualify as `{qualifier}{name}`"),
+        kind: CodeActionKind::QuickFix,
+        edits: vec![TextEdit { range, new_text: qualifier }],
+      };
+      (node.text_range(), action)
+    }));
+  }
+  ret
+}
+
+/// Returns whether the def is not in the file at `path`, or it is but ends before `offset`.
+fn defined_before(
+  file: &mlb_statics::SourceFile,
+  path: paths::PathId,
+  offset: TextSize,
+  def: def::Def,
+) -> bool {
+  let idx = match def.to_regular_idx() {
+    Some(x) if x.path == path => x.val,
+    _ => return true,
+  };
+  file.syntax.lower.ptrs.hir_to_ast(idx).map_or(true, |ptr| ptr.text_range().end() <= offset)
+}
+
+/// Adds the qualified names, with trailing `.`, of all the structures in the env (including nested
+/// ones) that define the item, where both the structures and the item satisfy `before`.
+fn defining_structures<'e, F>(
+  env: &'e Env,
+  prefix: &mut Vec<&'e str_util::Name>,
+  before: &F,
+  name: &str_util::Name,
+  item: Item,
+  ac: &mut std::collections::BTreeSet<String>,
+) where
+  F: Fn(def::Def) -> bool,
+{
+  for (str_name, env) in env.str_env.iter() {
+    if env.disallow.is_some() || !env.def.into_iter().all(before) {
+      continue;
+    }
+    prefix.push(str_name);
+    let defines = match item {
+      Item::Val => env
+        .val_env
+        .get(name)
+        .map_or(false, |x| x.disallow.is_none() && x.defs.iter().copied().all(before)),
+      Item::Ty => env
+        .ty_env
+        .get(name)
+        .map_or(false, |x| x.disallow.is_none() && x.def.into_iter().all(before)),
+      Item::Struct => env
+        .str_env
+        .get(name)
+        .map_or(false, |x| x.disallow.is_none() && x.def.into_iter().all(before)),
+      Item::TyVar | Item::Sig | Item::Functor => false,
+    };
+    if defines {
+      ac.insert(prefix.iter().flat_map(|x| [x.as_str(), "."]).collect());
+    }
+    defining_structures(env, prefix, before, name, item, ac);
+    prefix.pop();
+  }
+}
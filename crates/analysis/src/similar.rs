@@ -0,0 +1,74 @@
+//! Quick fixes for replacing undefined names with similar names that are in scope.
+
+use crate::source_files::FileAndToken;
+use crate::{CodeAction, CodeActionKind, TextEdit};
+use sml_statics_types::item::Item;
+use sml_syntax::ast::{self, AstNode as _};
+use sml_syntax::{SyntaxNode, SyntaxToken};
+use text_size_util::TextRange;
+
+/// Offer to replace the undefined name under the cursor with each similar name.
+pub(crate) fn get(ft: &FileAndToken<'_>) -> Vec<CodeAction> {
+  let offset = ft.token.text_range().start();
+  all(ft.file)
+    .into_iter()
+    .filter_map(|(range, action)| range.contains_inclusive(offset).then_some(action))
+    .collect()
+}
+
+/// Returns the replacements of each undefined name, from the file, with each similar name, with the
+/// range of the undefined name.
+pub(crate) fn all(file: &mlb_statics::SourceFile) -> Vec<(TextRange, CodeAction)> {
+  let mut ret = Vec::<(TextRange, CodeAction)>::new();
+  for error in &file.statics_errors {
+    if error.similar().is_empty() {
+      continue;
+    }
+    let node = match file.syntax.lower.ptrs.hir_to_ast(error.idx()) {
+      Some(ptr) => ptr.to_node(file.syntax.parse.root.syntax()),
+      None => continue,
+    };
+    let tok = match undefined_name(node, error) {
+      Some(x) => x,
+      None => continue,
+    };
+    let range = match file.syntax.pos_db.range_utf16(tok.text_range()) {
+      Some(x) => x,
+      None => continue,
+    };
+    ret.extend(error.similar().iter().map(|(name, _)| {
+      let action = CodeAction {
+        title: format!("Replace with `{name}`"),
+        kind: CodeActionKind::QuickFix,
+        edits: vec![TextEdit { range, new_text: name.as_str().to_owned() }],
+      };
+      (tok.text_range(), action)
+    }));
+  }
+  ret
+}
+
+/// Returns the token for the undefined name in the node with the error.
+fn undefined_name(node: SyntaxNode, error: &sml_statics::Error) -> Option<SyntaxToken> {
+  let (item, name) = error.undefined()?;
+  let tok = match crate::qualify::path_and_item(&node) {
+    Some((path, _)) => {
+      let mut toks = path.name_star_eq_dots().filter_map(|x| x.syntax().first_token());
+      match item {
+        // an undefined structure may be any part of the path, e.g. `Lst` in `Lst.length`.
+        Item::Struct => toks.find(|tok| tok.text() == name.as_str())?,
+        _ => toks.last().filter(|tok| tok.text() == name.as_str())?,
+      }
+    }
+    None => {
+      if let Some(sig_exp) = ast::NameSigExp::cast(node.clone()) {
+        sig_exp.name()?
+      } else if let Some(str_exp) = ast::AppStrExp::cast(node) {
+        str_exp.name()?
+      } else {
+        return None;
+      }
+    }
+  };
+  Some(tok)
+}
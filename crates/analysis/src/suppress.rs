@@ -0,0 +1,101 @@
+//! Suppressing diagnostics with comments in source files.
+
+use sml_syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+use std::cell::Cell;
+use text_size_util::TextRange;
+
+const IGNORE: &str = "millet-ignore";
+const IGNORE_FILE: &str = "millet-ignore-file";
+
+/// The suppression comments in a file.
+pub(crate) struct Suppressions {
+  all: Vec<Suppression>,
+}
+
+struct Suppression {
+  /// The range of the comment itself.
+  comment: TextRange,
+  /// Where diagnostics are suppressed, or `None` for the whole file.
+  scope: Option<TextRange>,
+  /// The codes to suppress, or empty for all codes.
+  codes: Vec<diagnostic::Code>,
+  used: Cell<bool>,
+}
+
+impl Suppressions {
+  pub(crate) fn new(root: &SyntaxNode) -> Self {
+    let all = root
+      .descendants_with_tokens()
+      .filter_map(sml_syntax::rowan::NodeOrToken::into_token)
+      .filter(|tok| tok.kind() == SyntaxKind::BlockComment)
+      .filter_map(|tok| suppression(&tok))
+      .collect();
+    Self { all }
+  }
+
+  /// Returns whether a diagnostic with this code at this range is suppressed. If it is, notes that
+  /// the comments suppressing it were used.
+  pub(crate) fn suppresses(&self, code: diagnostic::Code, range: TextRange) -> bool {
+    let mut ret = false;
+    for s in &self.all {
+      let in_scope = s.scope.map_or(true, |scope| scope.contains_inclusive(range.start()));
+      if in_scope && (s.codes.is_empty() || s.codes.contains(&code)) {
+        s.used.set(true);
+        ret = true;
+      }
+    }
+    ret
+  }
+
+  /// Returns the ranges of the comments that have not suppressed anything.
+  pub(crate) fn unused(&self) -> Vec<TextRange> {
+    self.all.iter().filter(|s| !s.used.get()).map(|s| s.comment).collect()
+  }
+}
+
+/// Parses a comment like `(* millet-ignore 5029 5036 optional reason *)`. The codes are optional.
+/// With no codes, all diagnostics are suppressed.
+fn suppression(tok: &SyntaxToken) -> Option<Suppression> {
+  let text = tok.text().strip_prefix("(*")?.strip_suffix("*)")?;
+  let mut words = text.split_whitespace().peekable();
+  let whole_file = match words.next()? {
+    IGNORE => false,
+    IGNORE_FILE => true,
+    _ => return None,
+  };
+  let mut codes = Vec::<diagnostic::Code>::new();
+  while let Some(code) = words.peek().and_then(|w| w.parse::<u16>().ok()) {
+    codes.push(diagnostic::Code::n(code));
+    words.next();
+  }
+  let scope = if whole_file { None } else { Some(scope(tok)) };
+  Some(Suppression { comment: tok.text_range(), scope, codes, used: Cell::new(false) })
+}
+
+/// Returns the range after the comment that it suppresses diagnostics in: the largest node that
+/// starts right after the comment, like a declaration or expression.
+fn scope(tok: &SyntaxToken) -> TextRange {
+  let end = tok.text_range().end();
+  let mut next = match tok.next_token() {
+    Some(x) => x,
+    None => return TextRange::empty(end),
+  };
+  while matches!(next.kind(), SyntaxKind::Whitespace | SyntaxKind::BlockComment) {
+    next = match next.next_token() {
+      Some(x) => x,
+      None => return TextRange::empty(end),
+    };
+  }
+  let start = next.text_range().start();
+  let node = std::iter::successors(next.parent(), SyntaxNode::parent)
+    .take_while(|node| {
+      node.text_range().start() == start
+        && !matches!(
+          node.kind(),
+          SyntaxKind::Root | SyntaxKind::Dec | SyntaxKind::DecWithTail | SyntaxKind::Matcher
+        )
+    })
+    .last();
+  let scope_end = node.map_or(next.text_range().end(), |node| node.text_range().end());
+  TextRange::new(end, scope_end)
+}
@@ -0,0 +1,368 @@
+//! Code actions for adding the inferred types as explicit type annotations.
+
+use crate::source_files::FileAndToken;
+use crate::{CodeAction, CodeActionKind, TextEdit};
+use fast_hash::{FxHashMap, FxHashSet};
+use sml_syntax::ast::{self, AstNode as _, SyntaxNodePtr};
+use sml_syntax::{SyntaxKind, SyntaxNode};
+use text_size_util::{TextRange, TextSize};
+
+pub(crate) fn get(st: &sml_statics_types::St, ft: &FileAndToken<'_>) -> Vec<CodeAction> {
+  let mut ret = Vec::<CodeAction>::new();
+  ret.extend(pat(st, ft));
+  if let Some(case) = fun_bind_case_head(ft) {
+    ret.extend(fun_return(st, ft.file, &case));
+    ret.extend(fun_params(st, ft.file, &case));
+  }
+  ret
+}
+
+/// Annotate the pattern under the cursor.
+fn pat(st: &sml_statics_types::St, ft: &FileAndToken<'_>) -> Option<CodeAction> {
+  let (ptr, idx) = ft.get_ptr_and_idx()?;
+  let pat = match idx {
+    sml_hir::Idx::Pat(pat) => pat,
+    _ => return None,
+  };
+  let root = ft.file.syntax.parse.root.syntax();
+  let node = ast::Pat::cast(ptr.to_node(root))?;
+  let mut cx = Cx::new(ft.file);
+  let edit = cx.pat(st, &node, pat)?;
+  let edits = cx.finish(node.syntax(), vec![edit])?;
+  Some(CodeAction {
+    title: "Add type annotation".to_owned(),
+    kind: CodeActionKind::Refactor,
+    edits,
+  })
+}
+
+/// Annotate the return type of the `fun` case under the cursor.
+fn fun_return(
+  st: &sml_statics_types::St,
+  file: &mlb_statics::SourceFile,
+  case: &ast::FunBindCase,
+) -> Option<CodeAction> {
+  if case.ty_annotation().is_some() {
+    return None;
+  }
+  let arm = fun_arm(file, case)?;
+  let body = match &file.syntax.lower.arenas.exp[arm.exp?] {
+    sml_hir::Exp::Typed(_, _, sml_hir::TypedFlavor::Fun) => return None,
+    _ => arm.exp?,
+  };
+  let mut cx = Cx::new(file);
+  let annot = cx.annot(st, body.into(), file.info.show_ty_annot(st, body)?)?;
+  let end = case.pats().last()?.syntax().text_range().end();
+  let edit = cx.edit(TextRange::empty(end), annot)?;
+  let edits = cx.finish(case.syntax(), vec![edit])?;
+  Some(CodeAction {
+    title: "Add return type annotation".to_owned(),
+    kind: CodeActionKind::Refactor,
+    edits,
+  })
+}
+
+/// Annotate all the parameters of the `fun` case under the cursor.
+fn fun_params(
+  st: &sml_statics_types::St,
+  file: &mlb_statics::SourceFile,
+  case: &ast::FunBindCase,
+) -> Option<CodeAction> {
+  if let Some(ast::FunBindCaseHead::InfixFunBindCaseHead(_)) = case.fun_bind_case_head() {
+    return None;
+  }
+  let arm = fun_arm(file, case)?;
+  let arenas = &file.syntax.lower.arenas;
+  let pat = arm.pat?;
+  // see the lowering of `fun`: multiple params are combined into a tuple.
+  let pats: Vec<_> = if case.pats().count() == 1 {
+    vec![pat]
+  } else {
+    match &arenas.pat[pat] {
+      sml_hir::Pat::Record { rows, .. } => rows.iter().filter_map(|&(_, pat)| pat).collect(),
+      _ => return None,
+    }
+  };
+  let mut cx = Cx::new(file);
+  let mut edits = Vec::<TextEdit>::new();
+  for (node, pat) in case.pats().zip(pats) {
+    edits.extend(cx.pat(st, &node, pat));
+  }
+  if edits.is_empty() {
+    return None;
+  }
+  let edits = cx.finish(case.syntax(), edits)?;
+  Some(CodeAction {
+    title: "Add type annotations to parameters".to_owned(),
+    kind: CodeActionKind::Refactor,
+    edits,
+  })
+}
+
+/// Returns the `fun` case whose head (i.e. not the body) contains the cursor.
+fn fun_bind_case_head(ft: &FileAndToken<'_>) -> Option<ast::FunBindCase> {
+  let case = ft.token.parent()?.ancestors().find_map(ast::FunBindCase::cast)?;
+  let offset = ft.token.text_range().start();
+  let in_body = case.eq_exp().map_or(false, |x| x.syntax().text_range().start() <= offset);
+  (!in_body).then_some(case)
+}
+
+/// Returns the HIR arm for the `fun` case.
+fn fun_arm<'a>(
+  file: &'a mlb_statics::SourceFile,
+  case: &ast::FunBindCase,
+) -> Option<&'a sml_hir::Arm> {
+  let arenas = &file.syntax.lower.arenas;
+  let fun_bind = ast::FunBind::cast(case.syntax().parent()?)?;
+  let fun_dec = ast::FunDec::cast(fun_bind.syntax().parent()?)?;
+  let bind_idx = fun_dec.fun_binds().position(|x| x.syntax() == fun_bind.syntax())?;
+  let case_idx = fun_bind.fun_bind_cases().position(|x| x.syntax() == case.syntax())?;
+  let dec = match file.syntax.lower.ptrs.ast_to_hir(&SyntaxNodePtr::new(fun_dec.syntax()))? {
+    sml_hir::Idx::Dec(dec) => dec,
+    _ => return None,
+  };
+  let val_bind = match &arenas.dec[dec] {
+    sml_hir::Dec::Val(_, val_binds, sml_hir::ValFlavor::Fun) => val_binds.get(bind_idx)?,
+    _ => return None,
+  };
+  // see the lowering of `fun`: the params are bound one at a time, and then cased on.
+  let mut exp = val_bind.exp?;
+  let func = loop {
+    match &arenas.exp[exp] {
+      sml_hir::Exp::Fn(arms, sml_hir::FnFlavor::FunArg) => exp = arms.first()?.exp?,
+      sml_hir::Exp::App(func, _) => break (*func)?,
+      _ => return None,
+    }
+  };
+  match &arenas.exp[func] {
+    sml_hir::Exp::Fn(arms, sml_hir::FnFlavor::FunCase { .. }) => arms.get(case_idx),
+    _ => None,
+  }
+}
+
+/// A `val` or `fun` declaration, which can bind type variables.
+enum ValOrFunDec {
+  Val(ast::ValDec),
+  Fun(ast::FunDec),
+}
+
+impl ValOrFunDec {
+  fn cast(node: SyntaxNode) -> Option<Self> {
+    ast::ValDec::cast(node.clone())
+      .map(Self::Val)
+      .or_else(|| ast::FunDec::cast(node).map(Self::Fun))
+  }
+
+  fn ty_var_seq(&self) -> Option<ast::TyVarSeq> {
+    match self {
+      ValOrFunDec::Val(dec) => dec.ty_var_seq(),
+      ValOrFunDec::Fun(dec) => dec.ty_var_seq(),
+    }
+  }
+
+  fn syntax(&self) -> &SyntaxNode {
+    match self {
+      ValOrFunDec::Val(dec) => dec.syntax(),
+      ValOrFunDec::Fun(dec) => dec.syntax(),
+    }
+  }
+
+  fn keyword_end(&self) -> Option<TextSize> {
+    let kw = match self {
+      ValOrFunDec::Val(dec) => dec.val_kw(),
+      ValOrFunDec::Fun(dec) => dec.fun_kw(),
+    };
+    Some(kw?.text_range().end())
+  }
+}
+
+/// Context for making annotations for a single code action.
+struct Cx<'a> {
+  file: &'a mlb_statics::SourceFile,
+  /// Type variables written in the file. We avoid clashing with these.
+  written: FxHashSet<String>,
+  /// Names for generated type variables, which are not valid syntax, to valid new names.
+  generated: FxHashMap<String, String>,
+  /// Names for generated type variables to the patterns and expressions whose types contain them.
+  uses: FxHashMap<String, Vec<sml_hir::Idx>>,
+  /// Whether two different generated type variables had the same name.
+  ambiguous: bool,
+}
+
+impl<'a> Cx<'a> {
+  fn new(file: &'a mlb_statics::SourceFile) -> Self {
+    let written = file
+      .syntax
+      .parse
+      .root
+      .syntax()
+      .descendants_with_tokens()
+      .filter_map(|x| x.into_token())
+      .filter(|x| x.kind() == SyntaxKind::TyVar)
+      .map(|x| x.text().to_owned())
+      .collect();
+    Self {
+      file,
+      written,
+      generated: FxHashMap::default(),
+      uses: FxHashMap::default(),
+      ambiguous: false,
+    }
+  }
+
+  /// Returns an edit to annotate the pattern, or `None` if it already has one or can't be
+  /// annotated.
+  fn pat(
+    &mut self,
+    st: &sml_statics_types::St,
+    node: &ast::Pat,
+    pat: sml_hir::la_arena::Idx<sml_hir::Pat>,
+  ) -> Option<TextEdit> {
+    if matches!(node, ast::Pat::TypedPat(_))
+      || node.syntax().parent().map_or(false, |x| ast::TypedPat::can_cast(x.kind()))
+    {
+      return None;
+    }
+    if let sml_hir::Pat::Typed(_, _) = &self.file.syntax.lower.arenas.pat[pat] {
+      return None;
+    }
+    let annot = self.annot(st, pat.into(), self.file.info.show_pat_ty_annot(st, pat)?)?;
+    let range = node.syntax().text_range();
+    let is_val_bind_pat =
+      node.syntax().parent().map_or(false, |x| ast::ValBind::can_cast(x.kind()));
+    if is_val_bind_pat {
+      return self.edit(TextRange::empty(range.end()), annot);
+    }
+    if let ast::Pat::ParenPat(paren) = node {
+      let r_round = paren.r_round()?;
+      return self.edit(TextRange::empty(r_round.text_range().start()), annot);
+    }
+    let text = node.syntax().to_string();
+    self.edit(range, format!("({text}{annot})"))
+  }
+
+  /// Returns the annotation for the type of the idx, with generated type variables renamed to valid
+  /// names, or `None` if it couldn't be written in the source.
+  fn annot(
+    &mut self,
+    st: &sml_statics_types::St,
+    idx: sml_hir::Idx,
+    annot: String,
+  ) -> Option<String> {
+    if annot.contains('<') || annot.contains("...") {
+      return None;
+    }
+    for (name, idxs) in self.file.info.generalized_ty_vars(st, idx) {
+      match self.uses.get(&name) {
+        None => {
+          self.uses.insert(name, idxs);
+        }
+        Some(old) => self.ambiguous = self.ambiguous || *old != idxs,
+      }
+    }
+    let mut ret = String::with_capacity(annot.len());
+    let mut chars = annot.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+      if c != '?' {
+        ret.push(c);
+        continue;
+      }
+      let mut end = start + c.len_utf8();
+      while let Some(&(idx, c)) = chars.peek() {
+        if c != '?' && !c.is_ascii_alphanumeric() {
+          break;
+        }
+        end = idx + c.len_utf8();
+        chars.next();
+      }
+      ret.push_str(self.generated_name(&annot[start..end]));
+    }
+    let has_hole =
+      ret.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '\'')).any(|x| x == "_");
+    (!has_hole).then_some(ret)
+  }
+
+  fn generated_name(&mut self, generated: &str) -> &str {
+    if !self.generated.contains_key(generated) {
+      let ticks = generated.chars().take_while(|&c| c == '?').count();
+      let base: String =
+        std::iter::repeat('\'').take(ticks).chain(generated.chars().skip(ticks)).collect();
+      let mut name = base.clone();
+      let mut n = 1usize;
+      while self.written.contains(&name) || self.generated.values().any(|x| *x == name) {
+        name = format!("{base}{n}");
+        n += 1;
+      }
+      self.generated.insert(generated.to_owned(), name);
+    }
+    self.generated[generated].as_str()
+  }
+
+  fn edit(&self, range: TextRange, new_text: String) -> Option<TextEdit> {
+    let range = self.file.syntax.pos_db.range_utf16(range)?;
+    Some(TextEdit { range, new_text })
+  }
+
+  /// Adds edits to bind the type variables we made up, if any, each on the declaration enclosing
+  /// `node` that generalizes it.
+  ///
+  /// That is the innermost `val` or `fun` declaration containing every pattern and expression whose
+  /// type contains the variable. For instance, in `fun f x = let val y = x in y end`, the type of
+  /// `y` is the type of `x`, so it is bound on `f`, not `y`.
+  fn finish(self, node: &SyntaxNode, mut edits: Vec<TextEdit>) -> Option<Vec<TextEdit>> {
+    if self.ambiguous {
+      return None;
+    }
+    let root = self.file.syntax.parse.root.syntax();
+    let decs: Vec<_> = node.ancestors().filter_map(ValOrFunDec::cast).collect();
+    let mut new_ty_vars = vec![Vec::<String>::new(); decs.len()];
+    for (generated, name) in self.generated {
+      let ranges: Vec<_> = self
+        .uses
+        .get(&generated)?
+        .iter()
+        .filter_map(|&idx| Some(self.file.syntax.lower.ptrs.hir_to_ast(idx)?.to_node(root)))
+        .map(|x| x.text_range())
+        .collect();
+      let dec = decs.iter().position(|dec| {
+        let dec_range = dec.syntax().text_range();
+        ranges.iter().all(|&r| dec_range.contains_range(r))
+      })?;
+      new_ty_vars[dec].push(name);
+    }
+    for (dec, new_ty_vars) in decs.into_iter().zip(new_ty_vars) {
+      if !new_ty_vars.is_empty() {
+        edits.push(binder_edit(self.file, &dec, new_ty_vars)?);
+      }
+    }
+    Some(edits)
+  }
+}
+
+/// Returns an edit to bind the new type variables on the declaration.
+fn binder_edit(
+  file: &mlb_statics::SourceFile,
+  dec: &ValOrFunDec,
+  mut new_ty_vars: Vec<String>,
+) -> Option<TextEdit> {
+  new_ty_vars.sort_unstable();
+  let (range, ty_vars, leading_space) = match dec.ty_var_seq() {
+    Some(seq) => {
+      let mut ty_vars: Vec<_> =
+        seq.ty_var_args().filter_map(|x| x.ty_var()).map(|x| x.text().to_owned()).collect();
+      ty_vars.extend(new_ty_vars);
+      (seq.syntax().text_range(), ty_vars, false)
+    }
+    None => (TextRange::empty(dec.keyword_end()?), new_ty_vars, true),
+  };
+  let mut new_text = if leading_space { " ".to_owned() } else { String::new() };
+  if let [ty_var] = ty_vars.as_slice() {
+    new_text.push_str(ty_var);
+  } else {
+    new_text.push('(');
+    new_text.push_str(ty_vars.join(", ").as_str());
+    new_text.push(')');
+  }
+  let range = file.syntax.pos_db.range_utf16(range)?;
+  Some(TextEdit { range, new_text })
+}
@@ -0,0 +1,304 @@
+//! Searching for values by their types.
+//!
+//! Types are compared after normalizing functions: the parameters of a curried function and the
+//! components of a tupled parameter are all considered parameters, whose order does not matter.
+//! Type variables match up to renaming. A `_` in the query matches any type.
+
+use fast_hash::FxHashMap;
+use sml_statics_types::env::Env;
+use sml_statics_types::ty::{Ty, TyData};
+use sml_statics_types::{info::ValInfo, sym::Sym};
+use std::collections::BTreeMap;
+
+/// The most parameters we'll try all the orderings of.
+const MAX_PERMUTED_PARAMS: usize = 6;
+
+/// A type, normalized for comparison.
+#[derive(Debug)]
+pub(crate) enum NormTy {
+  /// Only in queries, from `_`.
+  Any,
+  Var(usize),
+  Record(BTreeMap<sml_hir::Lab, NormTy>),
+  Con(Vec<NormTy>, Vec<str_util::Name>),
+  Fn(Vec<NormTy>, Box<NormTy>),
+}
+
+impl NormTy {
+  fn fun(param: NormTy, res: NormTy) -> NormTy {
+    let mut params = match param {
+      NormTy::Record(rows) if is_tuple(&rows) => rows.into_values().collect(),
+      param => vec![param],
+    };
+    match res {
+      NormTy::Fn(res_params, res) => {
+        params.extend(res_params);
+        NormTy::Fn(params, res)
+      }
+      res => NormTy::Fn(params, Box::new(res)),
+    }
+  }
+}
+
+fn is_tuple(rows: &BTreeMap<sml_hir::Lab, NormTy>) -> bool {
+  rows.len() > 1 && rows.keys().enumerate().all(|(idx, lab)| *lab == sml_hir::Lab::tuple(idx))
+}
+
+/// Parses the query as a type, resolving type names in the envs.
+///
+/// Returns `None` if the query could not be parsed.
+pub(crate) fn query(envs: &[&Env], st: &sml_statics_types::St, query: &str) -> Option<NormTy> {
+  let mut fix_env = sml_fixity::STD_BASIS.clone();
+  let lang = config::lang::Language::default();
+  let contents = format!("type t = {query}");
+  let syntax = sml_file_syntax::SourceFileSyntax::new(&mut fix_env, &lang, contents.as_str());
+  if !syntax.lex_errors.is_empty()
+    || !syntax.parse.errors.is_empty()
+    || syntax.lower.errors.iter().any(|e| e.severity() == diagnostic::Severity::Error)
+  {
+    return None;
+  }
+  let arenas = &syntax.lower.arenas;
+  let ty = arenas.dec.iter().find_map(|(_, dec)| match dec {
+    sml_hir::Dec::Ty(ty_binds) => Some(ty_binds.first()?.ty),
+    _ => None,
+  })??;
+  let mut cx = QueryCx { arenas, envs, st, vars: FxHashMap::default() };
+  cx.get(ty)
+}
+
+struct QueryCx<'a> {
+  arenas: &'a sml_hir::Arenas,
+  envs: &'a [&'a Env],
+  st: &'a sml_statics_types::St,
+  vars: FxHashMap<&'a sml_hir::TyVar, usize>,
+}
+
+impl QueryCx<'_> {
+  fn get(&mut self, ty: sml_hir::la_arena::Idx<sml_hir::Ty>) -> Option<NormTy> {
+    let arenas = self.arenas;
+    let ret = match &arenas.ty[ty] {
+      sml_hir::Ty::Hole => NormTy::Any,
+      sml_hir::Ty::Var(ty_var) => {
+        let len = self.vars.len();
+        NormTy::Var(*self.vars.entry(ty_var).or_insert(len))
+      }
+      sml_hir::Ty::Record(rows) => {
+        let rows = rows.iter().map(|(lab, ty)| Some((lab.clone(), self.get((*ty)?)?)));
+        NormTy::Record(rows.collect::<Option<_>>()?)
+      }
+      sml_hir::Ty::Con(args, path) => {
+        let args = args.iter().map(|&ty| self.get(ty?)).collect::<Option<Vec<_>>>()?;
+        let path = self.resolve(path.prefix(), path.last()).unwrap_or_else(|| {
+          path.prefix().iter().chain(std::iter::once(path.last())).cloned().collect()
+        });
+        NormTy::Con(args, path)
+      }
+      sml_hir::Ty::Fn(param, res) => {
+        let param = self.get((*param)?)?;
+        let res = self.get((*res)?)?;
+        NormTy::fun(param, res)
+      }
+    };
+    Some(ret)
+  }
+
+  /// Resolves type abbreviations like `Int.int` to the path of the type they stand for.
+  fn resolve(
+    &self,
+    prefix: &[str_util::Name],
+    last: &str_util::Name,
+  ) -> Option<Vec<str_util::Name>> {
+    let ty_info = self.envs.iter().find_map(|&env| {
+      let mut env = env;
+      for name in prefix {
+        env = env.str_env.get(name)?;
+      }
+      env.ty_env.get(last)
+    })?;
+    let data = match self.st.tys.data(ty_info.ty_scheme.ty) {
+      TyData::Con(data) => data,
+      _ => return None,
+    };
+    // only resolve abbreviations that pass their params straight through, like
+    // `type 'a t = 'a list`.
+    let passes_through = data.args.len() == ty_info.ty_scheme.bound_vars.len()
+      && data.args.iter().enumerate().all(
+        |(idx, &arg)| matches!(self.st.tys.data(arg), TyData::BoundVar(bv) if bv.to_usize() == idx),
+      );
+    passes_through.then(|| sym_path(self.st, data.sym))
+  }
+}
+
+fn sym_path(st: &sml_statics_types::St, sym: Sym) -> Vec<str_util::Name> {
+  match st.syms.get(sym) {
+    None => vec![str_util::Name::new("exn")],
+    Some(sym_info) => sym_info.path.all_names().cloned().collect(),
+  }
+}
+
+/// Returns the normalized form of the type of the value.
+fn val_info(st: &sml_statics_types::St, val_info: &ValInfo) -> Option<NormTy> {
+  let mut vars = FxHashMap::<Ty, usize>::default();
+  ty(st, &mut vars, val_info.ty_scheme.ty)
+}
+
+fn ty(st: &sml_statics_types::St, vars: &mut FxHashMap<Ty, usize>, t: Ty) -> Option<NormTy> {
+  let (t, data) = st.tys.canonicalize(t);
+  let ret = match data {
+    TyData::None => return None,
+    TyData::BoundVar(_)
+    | TyData::UnsolvedMetaVar(_)
+    | TyData::GeneralizedMetaVar(_)
+    | TyData::FixedVar(_) => {
+      let len = vars.len();
+      NormTy::Var(*vars.entry(t).or_insert(len))
+    }
+    TyData::Record(rows) => NormTy::Record(
+      rows.into_iter().map(|(lab, t)| Some((lab, ty(st, vars, t)?))).collect::<Option<_>>()?,
+    ),
+    TyData::Con(data) => {
+      let args = data.args.iter().map(|&t| ty(st, vars, t)).collect::<Option<Vec<_>>>()?;
+      NormTy::Con(args, sym_path(st, data.sym))
+    }
+    TyData::Fn(data) => NormTy::fun(ty(st, vars, data.param)?, ty(st, vars, data.res)?),
+  };
+  Some(ret)
+}
+
+/// A renaming of type variables, which must be a bijection.
+#[derive(Debug, Default, Clone)]
+struct Renaming {
+  query_to_val: FxHashMap<usize, usize>,
+  val_to_query: FxHashMap<usize, usize>,
+}
+
+impl Renaming {
+  fn bind(&mut self, query: usize, val: usize) -> bool {
+    match (self.query_to_val.get(&query), self.val_to_query.get(&val)) {
+      (None, None) => {
+        self.query_to_val.insert(query, val);
+        self.val_to_query.insert(val, query);
+        true
+      }
+      (Some(&v), Some(&q)) => v == val && q == query,
+      (Some(_), None) | (None, Some(_)) => false,
+    }
+  }
+}
+
+fn matches(query: &NormTy, val: &NormTy, renaming: &mut Renaming) -> bool {
+  match (query, val) {
+    (NormTy::Any, _) => true,
+    (NormTy::Var(q), NormTy::Var(v)) => renaming.bind(*q, *v),
+    (NormTy::Record(q_rows), NormTy::Record(v_rows)) => {
+      q_rows.len() == v_rows.len()
+        && q_rows
+          .iter()
+          .zip(v_rows.iter())
+          .all(|((q_lab, q), (v_lab, v))| q_lab == v_lab && matches(q, v, renaming))
+    }
+    (NormTy::Con(q_args, q_path), NormTy::Con(v_args, v_path)) => {
+      path_matches(q_path, v_path)
+        && q_args.len() == v_args.len()
+        && q_args.iter().zip(v_args.iter()).all(|(q, v)| matches(q, v, renaming))
+    }
+    (NormTy::Fn(q_params, q_res), NormTy::Fn(v_params, v_res)) => {
+      if q_params.len() != v_params.len() || !matches(q_res, v_res, renaming) {
+        return false;
+      }
+      if q_params.len() > MAX_PERMUTED_PARAMS {
+        return q_params.iter().zip(v_params.iter()).all(|(q, v)| matches(q, v, renaming));
+      }
+      let mut used = vec![false; v_params.len()];
+      params_match(q_params, v_params, &mut used, renaming)
+    }
+    _ => false,
+  }
+}
+
+/// Whether the query params match the unused val params in some order.
+fn params_match(
+  query: &[NormTy],
+  val: &[NormTy],
+  used: &mut [bool],
+  renaming: &mut Renaming,
+) -> bool {
+  let (fst, rest) = match query.split_first() {
+    Some(x) => x,
+    None => return true,
+  };
+  for (idx, v) in val.iter().enumerate() {
+    if used[idx] {
+      continue;
+    }
+    let mut new_renaming = renaming.clone();
+    if !matches(fst, v, &mut new_renaming) {
+      continue;
+    }
+    used[idx] = true;
+    if params_match(rest, val, used, &mut new_renaming) {
+      *renaming = new_renaming;
+      return true;
+    }
+    used[idx] = false;
+  }
+  false
+}
+
+/// Type names match if they have the same last name, and if the query was qualified, the same
+/// qualification.
+fn path_matches(query: &[str_util::Name], val: &[str_util::Name]) -> bool {
+  match query {
+    [] => false,
+    [name] => val.last() == Some(name),
+    _ => query == val,
+  }
+}
+
+/// Calls `f` on every value in the env (including those in nested structures) whose type matches
+/// the query, along with its qualified name.
+pub(crate) fn get<'e, F>(st: &sml_statics_types::St, env: &'e Env, query: &NormTy, f: &mut F)
+where
+  F: FnMut(String, &'e ValInfo),
+{
+  go(st, &mut Vec::new(), env, query, f);
+}
+
+fn go<'e, F>(
+  st: &sml_statics_types::St,
+  prefix: &mut Vec<&'e str_util::Name>,
+  env: &'e Env,
+  query: &NormTy,
+  f: &mut F,
+) where
+  F: FnMut(String, &'e ValInfo),
+{
+  for (name, vi) in env.val_env.iter() {
+    if vi.disallow.is_some() {
+      continue;
+    }
+    let val_ty = match val_info(st, vi) {
+      Some(x) => x,
+      None => continue,
+    };
+    if !matches(query, &val_ty, &mut Renaming::default()) {
+      continue;
+    }
+    let mut qualified = String::new();
+    for &p in prefix.iter() {
+      qualified.push_str(p.as_str());
+      qualified.push('.');
+    }
+    qualified.push_str(name.as_str());
+    f(qualified, vi);
+  }
+  for (name, env) in env.str_env.iter() {
+    if env.disallow.is_some() {
+      continue;
+    }
+    prefix.push(name);
+    go(st, prefix, env, query, f);
+    prefix.pop();
+  }
+}
@@ -30,6 +30,16 @@ impl<K, V> ChainMap<K, V> {
   pub fn append(&mut self, other: &mut Self) {
     self.stack.append(&mut other.stack);
   }
+
+  /// Append other onto self, sharing the underlying maps with other, and skipping those already in
+  /// self. So appending the same (or a cloned) map many times only adds it once.
+  pub fn append_shared(&mut self, other: &Self) {
+    for m in &other.stack {
+      if !self.stack.iter().any(|x| Rc::ptr_eq(x, m)) {
+        self.stack.push(Rc::clone(m));
+      }
+    }
+  }
 }
 
 impl<K, V> ChainMap<K, V>
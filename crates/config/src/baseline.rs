@@ -0,0 +1,32 @@
+//! A baseline file, recording known diagnostics that should not be reported.
+
+use serde::{Deserialize, Serialize};
+use str_util::SmolStr;
+
+/// The conventional path of the baseline file.
+pub const PATH: &str = "millet-baseline.json";
+
+/// The root of a baseline file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Root {
+  /// The version. Should be 1.
+  pub version: u16,
+  /// The known diagnostics.
+  #[serde(default)]
+  pub diagnostics: Vec<Entry>,
+}
+
+/// Known diagnostics with the same code and fingerprint in a file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Entry {
+  /// The path of the file, relative to the root, with `/` as the separator.
+  pub path: SmolStr,
+  /// The error code.
+  pub code: SmolStr,
+  /// A fingerprint of the syntax at the diagnostics.
+  pub fingerprint: SmolStr,
+  /// How many such diagnostics there are.
+  pub count: usize,
+}
@@ -19,7 +19,7 @@ pub struct Root {
   pub workspace: Workspace,
   /// The diagnostics config.
   #[serde(default)]
-  pub diagnostics: FxHashMap<SmolStr, Diagnostic>,
+  pub diagnostics: Diagnostics,
   /// The language config.
   #[serde(default)]
   pub language: Language,
@@ -48,6 +48,31 @@ pub enum PathVar {
   WorkspacePath(SmolStr),
 }
 
+/// The diagnostics config.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Diagnostics {
+  /// Configuration for some paths, overriding the configuration for all paths.
+  #[serde(default, rename = "override")]
+  pub overrides: Vec<DiagnosticsOverride>,
+  /// The path of a baseline file of known diagnostics, relative to the config file.
+  pub baseline: Option<SmolStr>,
+  /// Configuration for error codes, for all paths.
+  #[serde(flatten)]
+  pub codes: FxHashMap<SmolStr, Diagnostic>,
+}
+
+/// The diagnostics config for some paths.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DiagnosticsOverride {
+  /// Glob patterns for the paths, relative to the config file.
+  pub paths: Vec<SmolStr>,
+  /// Configuration for error codes, for the paths.
+  #[serde(flatten)]
+  pub codes: FxHashMap<SmolStr, Diagnostic>,
+}
+
 /// Configuration for an error code.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -87,6 +112,12 @@ pub struct Language {
   /// Configuration for structures.
   #[serde(default)]
   pub structure: FxHashMap<SmolStr, bool>,
+  /// Configuration for discarding the results of values.
+  #[serde(default)]
+  pub discard: FxHashMap<SmolStr, bool>,
+  /// Names of functions that should not let exceptions escape.
+  #[serde(default)]
+  pub entry_points: Vec<SmolStr>,
 }
 
 /// Configuration for declarations.
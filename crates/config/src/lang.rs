@@ -16,4 +16,8 @@ pub struct Language {
   pub val: FxHashSet<sml_path::Path>,
   /// Disallowed structure paths.
   pub structure: FxHashSet<sml_path::Path>,
+  /// Value paths whose results may be discarded.
+  pub discard: FxHashSet<sml_path::Path>,
+  /// Names of functions that should not let exceptions escape.
+  pub entry_points: FxHashSet<str_util::Name>,
 }
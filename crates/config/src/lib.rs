@@ -4,6 +4,7 @@
 // TODO remove once rustfmt support lands
 #![allow(clippy::manual_let_else)]
 
+pub mod baseline;
 pub mod file;
 pub mod init;
 pub mod lang;
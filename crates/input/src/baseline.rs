@@ -0,0 +1,74 @@
+//! Reading a baseline file of known diagnostics.
+
+use crate::types::Baseline;
+use crate::util::{get_path_id, Error, ErrorKind, ErrorSource};
+use std::path::Path;
+
+/// Reads the baseline file at `path`. Paths in the baseline are relative to `root`.
+pub(crate) fn get<F>(
+  fs: &F,
+  paths: &mut paths::Store,
+  root: &paths::CanonicalPathBuf,
+  path: &Path,
+  errors: &mut Vec<Error>,
+) -> Baseline
+where
+  F: paths::FileSystem,
+{
+  let mut ret = Baseline { path: Some(path.to_owned()), ..Baseline::default() };
+  let contents = match fs.read_to_string(path) {
+    Ok(x) => x,
+    Err(e) => {
+      errors.push(Error::from_io(path.to_owned(), e));
+      return ret;
+    }
+  };
+  let parsed: config::baseline::Root = match serde_json::from_str(&contents) {
+    Ok(x) => x,
+    Err(e) => {
+      errors.push(Error::new(
+        ErrorSource::default(),
+        path.to_owned(),
+        ErrorKind::CouldNotParseBaseline(e),
+      ));
+      return ret;
+    }
+  };
+  if parsed.version != 1 {
+    errors.push(Error::new(
+      ErrorSource::default(),
+      path.to_owned(),
+      ErrorKind::InvalidBaselineVersion(parsed.version),
+    ));
+    return ret;
+  }
+  for entry in parsed.diagnostics {
+    let file = root.as_path().join(entry.path.as_str());
+    // the file may have been removed since the baseline was written.
+    if !fs.is_file(file.as_path()) {
+      continue;
+    }
+    let code = match entry.code.parse::<diagnostic::Code>() {
+      Ok(x) => x,
+      Err(e) => {
+        errors.push(Error::new(
+          ErrorSource::default(),
+          path.to_owned(),
+          ErrorKind::InvalidErrorCode(entry.code, e),
+        ));
+        continue;
+      }
+    };
+    let source = ErrorSource { path: Some(path.to_owned()), range: None };
+    let id = match get_path_id(fs, paths, source, &file) {
+      Ok(x) => x,
+      Err(e) => {
+        errors.push(e);
+        continue;
+      }
+    };
+    let key = (code, entry.fingerprint);
+    *ret.map.entry(id).or_default().entry(key).or_default() += entry.count;
+  }
+  ret
+}
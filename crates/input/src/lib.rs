@@ -5,6 +5,7 @@
 // TODO remove once rustfmt support lands
 #![allow(clippy::manual_let_else)]
 
+mod baseline;
 mod lower_cm;
 mod lower_mlb;
 mod root;
@@ -15,7 +16,7 @@ mod util;
 use paths::{PathId, PathMap};
 use util::{ErrorKind, ErrorSource, GroupPathKind};
 
-pub use types::{Group, Severities};
+pub use types::{Baseline, Group, Known, Severities};
 pub use util::Error;
 
 /// The input to analysis.
@@ -31,6 +32,8 @@ pub struct Input {
   pub severities: types::Severities,
   /// The language config.
   pub lang: config::lang::Language,
+  /// Known diagnostics, which should not be reported.
+  pub baseline: types::Baseline,
   /// Errors when getting input.
   pub errors: Vec<Error>,
 }
@@ -49,6 +52,7 @@ impl Input {
     let root = root::Root::new(fs, paths, root, &mut ret.errors);
     ret.severities = root.config.severities;
     ret.lang = root.config.lang;
+    ret.baseline = root.config.baseline;
     for group in root.groups {
       let path = paths.get_path(group.path).as_path();
       let parent = path.parent().expect("group path with no parent");
@@ -81,4 +85,20 @@ impl Input {
     }
     ret
   }
+
+  /// Replaces the baseline with the one in the file at `path`. Paths in the baseline are relative
+  /// to `root`.
+  ///
+  /// Errors are added to `self.errors`.
+  pub fn read_baseline<F>(
+    &mut self,
+    fs: &F,
+    paths: &mut paths::Store,
+    root: &paths::CanonicalPathBuf,
+    path: &std::path::Path,
+  ) where
+    F: paths::FileSystem,
+  {
+    self.baseline = baseline::get(fs, paths, root, path, &mut self.errors);
+  }
 }
@@ -1,6 +1,6 @@
 //! Getting the root groups.
 
-use crate::types::Severities;
+use crate::types::{Baseline, Severities, SeverityMap};
 use crate::util::{
   get_path_id, read_dir, str_path, Error, ErrorKind, ErrorSource, GroupPathKind, NoRootFlavor,
 };
@@ -33,7 +33,7 @@ impl Root {
     let (config, glob) = match config_file {
       Ok(s) => {
         flavor = NoRootFlavor::NoGlob;
-        Config::from_file(root, &config_path, &s, errors)
+        Config::from_file(fs, paths, root, &config_path, &s, errors)
       }
       Err(_) => (Config::default(), None),
     };
@@ -103,15 +103,21 @@ pub(crate) struct Config {
   pub(crate) path_vars: slash_var_path::UnresolvedEnv,
   pub(crate) severities: Severities,
   pub(crate) lang: config::lang::Language,
+  pub(crate) baseline: Baseline,
 }
 
 impl Config {
-  fn from_file(
+  fn from_file<F>(
+    fs: &F,
+    paths: &mut paths::Store,
     root: &paths::CanonicalPathBuf,
     config_path: &Path,
     contents: &str,
     errors: &mut Vec<Error>,
-  ) -> (Config, Option<str_util::SmolStr>) {
+  ) -> (Config, Option<str_util::SmolStr>)
+  where
+    F: paths::FileSystem,
+  {
     let mut ret = Config::default();
     let parsed: config::file::Root = match toml::from_str(contents) {
       Ok(x) => x,
@@ -158,41 +164,138 @@ impl Config {
       };
       ret.path_vars.insert(key, EnvEntry { kind, suffix });
     }
-    for (code, config) in parsed.diagnostics {
-      let code = match code.parse::<diagnostic::Code>() {
-        Ok(x) => x,
-        Err(e) => {
-          errors.push(Error::new(
-            ErrorSource::default(),
-            config_path.to_owned(),
-            ErrorKind::InvalidErrorCode(code, e),
-          ));
-          continue;
-        }
-      };
-      let sev = match config.severity {
-        config::file::Severity::Ignore => None,
-        config::file::Severity::Warning => Some(diagnostic::Severity::Warning),
-        config::file::Severity::Error => Some(diagnostic::Severity::Error),
-      };
-      ret.severities.insert(code, sev);
+    ret.severities.all = severities(errors, config_path, parsed.diagnostics.codes);
+    for ov in parsed.diagnostics.overrides {
+      let mut override_paths = FxHashSet::<PathId>::default();
+      for glob in ov.paths {
+        glob_paths(fs, paths, &mut override_paths, root, glob.as_str(), config_path, errors);
+      }
+      let map = severities(errors, config_path, ov.codes);
+      ret.severities.overrides.push((override_paths, map));
+    }
+    if let Some(path) = parsed.diagnostics.baseline {
+      let path = root.as_path().join(path.as_str());
+      ret.baseline = crate::baseline::get(fs, paths, root, &path, errors);
     }
     ret.lang.fixity_across_files = parsed.language.fixity_across_files;
     ret.lang.dec = parsed.language.dec;
     ret.lang.exp = parsed.language.exp;
-    disallow(errors, config_path, parsed.language.val, &mut ret.lang.val);
-    disallow(errors, config_path, parsed.language.structure, &mut ret.lang.structure);
+    path_set(errors, config_path, parsed.language.val, false, &mut ret.lang.val);
+    path_set(errors, config_path, parsed.language.structure, false, &mut ret.lang.structure);
+    path_set(errors, config_path, parsed.language.discard, true, &mut ret.lang.discard);
+    for name in parsed.language.entry_points {
+      match str_util::Name::try_new(name.as_str()) {
+        Some(x) => {
+          ret.lang.entry_points.insert(x);
+        }
+        None => errors.push(Error::new(
+          ErrorSource::default(),
+          config_path.to_owned(),
+          ErrorKind::EmptyStrInPath(name),
+        )),
+      }
+    }
     (ret, parsed.workspace.root)
   }
 }
 
-fn disallow(
+fn severities(
+  errors: &mut Vec<Error>,
+  config_path: &Path,
+  map: FxHashMap<str_util::SmolStr, config::file::Diagnostic>,
+) -> SeverityMap {
+  let mut ret = SeverityMap::default();
+  for (code, config) in map {
+    let code = match code.parse::<diagnostic::Code>() {
+      Ok(x) => x,
+      Err(e) => {
+        errors.push(Error::new(
+          ErrorSource::default(),
+          config_path.to_owned(),
+          ErrorKind::InvalidErrorCode(code, e),
+        ));
+        continue;
+      }
+    };
+    let sev = match config.severity {
+      config::file::Severity::Ignore => None,
+      config::file::Severity::Warning => Some(diagnostic::Severity::Warning),
+      config::file::Severity::Error => Some(diagnostic::Severity::Error),
+    };
+    ret.insert(code, sev);
+  }
+  ret
+}
+
+/// Adds the ids of the paths matching the glob, relative to the root, to `ac`.
+///
+/// It is not an error for the glob to match no paths, since e.g. a directory of generated files may
+/// not have been generated yet.
+///
+/// This only matches the paths that exist now. That's fine, since the input is gotten again, and so
+/// this is called again, whenever the config or a group file changes, which is the only way new
+/// source files can be added to the input.
+fn glob_paths<F>(
+  fs: &F,
+  paths: &mut paths::Store,
+  ac: &mut FxHashSet<PathId>,
+  root: &paths::CanonicalPathBuf,
+  glob: &str,
+  config_path: &Path,
+  errors: &mut Vec<Error>,
+) where
+  F: paths::FileSystem,
+{
+  let source = ErrorSource { path: Some(config_path.to_owned()), range: None };
+  let path = root.as_path().join(glob);
+  let glob = match str_path(source.clone(), &path) {
+    Ok(x) => x,
+    Err(e) => {
+      errors.push(e);
+      return;
+    }
+  };
+  let glob_paths = match fs.glob(glob) {
+    Ok(x) => x,
+    Err(e) => {
+      errors.push(Error::new(
+        ErrorSource::default(),
+        config_path.to_owned(),
+        ErrorKind::GlobPattern(e),
+      ));
+      return;
+    }
+  };
+  for path in glob_paths {
+    let path = match path {
+      Ok(x) => x,
+      Err(e) => {
+        errors.push(Error::from_io(config_path.to_owned(), e.into_error()));
+        continue;
+      }
+    };
+    let path = root.as_path().join(path);
+    if !fs.is_file(path.as_path()) {
+      continue;
+    }
+    match get_path_id(fs, paths, source.clone(), &path) {
+      Ok(id) => {
+        ac.insert(id);
+      }
+      Err(e) => errors.push(e),
+    }
+  }
+}
+
+/// Adds the paths in the map whose value is `want` to the set.
+fn path_set(
   errors: &mut Vec<Error>,
   config_path: &Path,
   map: FxHashMap<str_util::SmolStr, bool>,
+  want: bool,
   set: &mut FxHashSet<sml_path::Path>,
 ) {
-  for (path, allowed) in map {
+  for (path, val) in map {
     let parts: Option<Vec<_>> = path.split('.').map(str_util::Name::try_new).collect();
     let parts = match parts {
       Some(x) => x,
@@ -205,7 +308,7 @@ fn disallow(
         continue;
       }
     };
-    if !allowed {
+    if val == want {
       let p = sml_path::Path::try_new(parts).expect("split always returns non-empty iter");
       set.insert(p);
     }
@@ -1,9 +1,58 @@
 //! Pervasive types.
 
-use fast_hash::FxHashMap;
+use fast_hash::{FxHashMap, FxHashSet};
+use paths::PathId;
 
 /// A mapping to override diagnostic severity.
-pub type Severities = FxHashMap<diagnostic::Code, Option<diagnostic::Severity>>;
+pub(crate) type SeverityMap = FxHashMap<diagnostic::Code, Option<diagnostic::Severity>>;
+
+/// Overrides for diagnostic severity, for all paths or only some.
+#[derive(Debug, Default)]
+pub struct Severities {
+  pub(crate) all: SeverityMap,
+  /// Later overrides take precedence over earlier ones.
+  pub(crate) overrides: Vec<(FxHashSet<PathId>, SeverityMap)>,
+}
+
+impl Severities {
+  /// Returns the overridden severity for diagnostics with this code in this path, if any.
+  /// `Some(None)` means the diagnostics should be ignored.
+  #[must_use]
+  pub fn get(&self, path: PathId, code: diagnostic::Code) -> Option<Option<diagnostic::Severity>> {
+    self
+      .overrides
+      .iter()
+      .rev()
+      .filter(|(paths, _)| paths.contains(&path))
+      .find_map(|(_, map)| map.get(&code).copied())
+      .or_else(|| self.all.get(&code).copied())
+  }
+}
+
+/// Counts of known diagnostics in a file, keyed by code and fingerprint.
+pub type Known = FxHashMap<(diagnostic::Code, str_util::SmolStr), usize>;
+
+/// Known diagnostics, which should not be reported.
+#[derive(Debug, Default)]
+pub struct Baseline {
+  pub(crate) path: Option<std::path::PathBuf>,
+  pub(crate) map: FxHashMap<PathId, Known>,
+}
+
+impl Baseline {
+  /// Returns the path of the baseline file, if there is one. This is set even if the file could
+  /// not be read, since it may be created later.
+  #[must_use]
+  pub fn path(&self) -> Option<&std::path::Path> {
+    self.path.as_deref()
+  }
+
+  /// Returns the known diagnostics in this path.
+  #[must_use]
+  pub fn get(&self, path: PathId) -> Option<&Known> {
+    self.map.get(&path)
+  }
+}
 
 /// A description of how to check a group of source files.
 #[derive(Debug)]
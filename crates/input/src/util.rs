@@ -25,6 +25,8 @@ pub(crate) enum ErrorKind {
   FunSig,
   NonUtf8Path,
   EmptyStrInPath(str_util::SmolStr),
+  CouldNotParseBaseline(serde_json::Error),
+  InvalidBaselineVersion(u16),
 }
 
 #[derive(Debug)]
@@ -62,6 +64,10 @@ impl fmt::Display for ErrorDisplay<'_> {
       ErrorKind::InvalidConfigVersion(n) => {
         write!(f, "invalid config version: expected 1, found {n}")
       }
+      ErrorKind::CouldNotParseBaseline(e) => write!(f, "couldn't parse baseline: {e}"),
+      ErrorKind::InvalidBaselineVersion(n) => {
+        write!(f, "invalid baseline version: expected 1, found {n}")
+      }
       ErrorKind::Cm(e) => write!(f, "couldn't process SML/NJ CM file: {e}"),
       ErrorKind::Mlb(e) => write!(f, "couldn't process ML Basis file: {e}"),
       ErrorKind::Cycle => f.write_str("there is a cycle involving this path"),
@@ -151,6 +157,8 @@ impl Error {
       // other errors not here have 1017-1019
       ErrorKind::NonUtf8Path => Code::n(1020),
       ErrorKind::EmptyStrInPath(_) => Code::n(1021),
+      ErrorKind::CouldNotParseBaseline(_) => Code::n(1022),
+      ErrorKind::InvalidBaselineVersion(_) => Code::n(1023),
     }
   }
 
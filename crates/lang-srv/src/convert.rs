@@ -24,12 +24,34 @@ pub(crate) fn file_url(path: &std::path::Path) -> Result<Url> {
 }
 
 pub(crate) fn diagnostics(
+  paths: &paths::Store,
   errors: Vec<analysis::Diagnostic<text_pos::RangeUtf16>>,
   more_info_hint: bool,
 ) -> Vec<lsp_types::Diagnostic> {
   errors
     .into_iter()
-    .map(|err| diagnostic(err.message, Some(err.range), err.code, err.severity, more_info_hint))
+    .map(|err| {
+      let mut ret =
+        diagnostic(err.message, Some(err.range), err.code, err.severity, more_info_hint);
+      let mut related: Vec<_> = err
+        .related
+        .into_iter()
+        .filter_map(|r| {
+          let location = lsp_location(paths, r.range)?;
+          Some(lsp_types::DiagnosticRelatedInformation { location, message: r.message })
+        })
+        .collect();
+      // put the more info hint, if any, after the actually related locations.
+      related.extend(ret.related_information.take().into_iter().flatten());
+      ret.related_information = (!related.is_empty()).then_some(related);
+      ret.tags = err.tag.map(|tag| {
+        vec![match tag {
+          analysis::Tag::Unnecessary => lsp_types::DiagnosticTag::UNNECESSARY,
+          analysis::Tag::Deprecated => lsp_types::DiagnosticTag::DEPRECATED,
+        }]
+      });
+      ret
+    })
     .collect()
 }
 
@@ -152,9 +174,31 @@ pub(crate) fn quick_fix(
   range: text_pos::RangeUtf16,
   new_text: String,
 ) -> lsp_types::CodeActionOrCommand {
+  let edits = vec![analysis::TextEdit { range, new_text }];
+  code_action(url, analysis::CodeAction { title, kind: analysis::CodeActionKind::QuickFix, edits })
+}
+
+pub(crate) fn code_action(
+  url: Url,
+  action: analysis::CodeAction,
+) -> lsp_types::CodeActionOrCommand {
+  let kind = match action.kind {
+    analysis::CodeActionKind::QuickFix => lsp_types::CodeActionKind::QUICKFIX,
+    analysis::CodeActionKind::Refactor => lsp_types::CodeActionKind::REFACTOR_REWRITE,
+  };
+  let edits = action
+    .edits
+    .into_iter()
+    .map(|edit| {
+      lsp_types::OneOf::Left(lsp_types::TextEdit {
+        range: lsp_range(edit.range),
+        new_text: edit.new_text,
+      })
+    })
+    .collect();
   lsp_types::CodeActionOrCommand::CodeAction(lsp_types::CodeAction {
-    title,
-    kind: Some(lsp_types::CodeActionKind::QUICKFIX),
+    title: action.title,
+    kind: Some(kind),
     edit: Some(lsp_types::WorkspaceEdit {
       document_changes: Some(lsp_types::DocumentChanges::Edits(vec![
         lsp_types::TextDocumentEdit {
@@ -162,10 +206,7 @@ pub(crate) fn quick_fix(
             uri: url,
             version: None,
           },
-          edits: vec![lsp_types::OneOf::Left(lsp_types::TextEdit {
-            range: lsp_range(range),
-            new_text,
-          })],
+          edits,
         },
       ])),
       ..Default::default()
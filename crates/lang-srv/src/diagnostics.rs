@@ -60,7 +60,7 @@ pub(crate) fn try_publish(st: &mut St) -> bool {
         continue;
       }
     };
-    let ds = convert::diagnostics(errors, st.cx.options.diagnostics.more_info_hint.0);
+    let ds = convert::diagnostics(&st.cx.paths, errors, st.cx.options.diagnostics.more_info_hint.0);
     if ds.is_empty() {
       continue;
     }
@@ -0,0 +1,57 @@
+//! Extensions to the language server protocol.
+
+use serde::{Deserialize, Serialize};
+
+/// Search for values by their type.
+#[derive(Debug)]
+pub(crate) enum SearchByTy {}
+
+impl lsp_types::request::Request for SearchByTy {
+  type Params = SearchByTyParams;
+  type Result = Vec<SearchByTyItem>;
+  const METHOD: &'static str = "millet/searchByType";
+}
+
+/// The params for [`SearchByTy`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SearchByTyParams {
+  /// The type to search for, like `'a list -> int`.
+  pub(crate) query: String,
+}
+
+/// A result of [`SearchByTy`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SearchByTyItem {
+  /// The qualified name of the value, like `List.length`.
+  pub(crate) name: String,
+  /// The type of the value.
+  pub(crate) ty: String,
+  /// Where the value was defined, if in the workspace.
+  pub(crate) location: Option<lsp_types::Location>,
+  /// Markdown documentation for the value.
+  pub(crate) documentation: Option<String>,
+}
+
+/// Explain why the item at a position has its type.
+#[derive(Debug)]
+pub(crate) enum ExplainTy {}
+
+impl lsp_types::request::Request for ExplainTy {
+  type Params = lsp_types::TextDocumentPositionParams;
+  type Result = Option<Vec<ExplainTyItem>>;
+  const METHOD: &'static str = "millet/explainType";
+}
+
+/// A result of [`ExplainTy`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ExplainTyItem {
+  /// Where the code that caused a type variable to be solved is.
+  pub(crate) location: lsp_types::Location,
+  /// The code, shortened to a single line.
+  pub(crate) code: String,
+  /// The type that the type variable was solved to.
+  pub(crate) ty: String,
+}
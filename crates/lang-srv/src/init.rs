@@ -60,11 +60,20 @@ pub(crate) fn init(init: lsp_types::InitializeParams, sender: Sender<Message>) -
       .unwrap_or_default();
   if want_file_ops {
     if let Mode::Root(root) = &ret.mode {
-      // we'd like to only listen to millet.toml, not all toml, but "nested alternate groups are
-      // not allowed" at time of writing.
-      let glob_pattern =
-        format!("{}/**/*.{{sml,sig,fun,cm,mlb,toml}}", root.path.as_path().display());
-      let watchers = vec![lsp_types::FileSystemWatcher { glob_pattern, kind: None }];
+      // the baseline path is the one configured at startup. if the config later changes it, we
+      // don't re-register.
+      let root_path = root.path.as_path();
+      let mut glob_patterns = vec![
+        format!("{}/**/*.{{sml,sig,fun,cm,mlb}}", root_path.display()),
+        root_path.join(config::file::PATH).display().to_string(),
+      ];
+      if let Some(path) = root.input.baseline.path() {
+        glob_patterns.push(path.display().to_string());
+      }
+      let watchers: Vec<_> = glob_patterns
+        .into_iter()
+        .map(|glob_pattern| lsp_types::FileSystemWatcher { glob_pattern, kind: None })
+        .collect();
       let did_changed_registration =
         convert::registration::<lsp_types::notification::DidChangeWatchedFiles, _>(
           lsp_types::DidChangeWatchedFilesRegistrationOptions { watchers },
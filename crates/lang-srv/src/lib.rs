@@ -9,6 +9,7 @@ mod capabilities;
 mod convert;
 mod cx;
 mod diagnostics;
+mod ext;
 mod helpers;
 mod init;
 mod notification;
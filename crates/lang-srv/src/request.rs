@@ -1,7 +1,7 @@
 //! Handle requests.
 
 use crate::state::{Mode, St};
-use crate::{convert, helpers};
+use crate::{convert, ext, helpers};
 use anyhow::Result;
 use lsp_server::{Request, Response};
 use std::ops::ControlFlow;
@@ -68,7 +68,10 @@ fn go(st: &mut St, mut r: Request) -> ControlFlow<Result<()>, Request> {
     let range = convert::analysis_range(params.range);
     let mut actions = Vec::<lsp_types::CodeActionOrCommand>::new();
     if let Some((range, new_text)) = st.analysis.fill_case(path.wrap(range.start)) {
-      actions.push(convert::quick_fix("Fill case".to_owned(), url, range, new_text));
+      actions.push(convert::quick_fix("Fill case".to_owned(), url.clone(), range, new_text));
+    }
+    for action in st.analysis.code_actions(path.wrap(range.start)) {
+      actions.push(convert::code_action(url.clone(), action));
     }
     st.cx.send_response(Response::new_ok(id, actions));
     Ok(())
@@ -149,5 +152,41 @@ fn go(st: &mut St, mut r: Request) -> ControlFlow<Result<()>, Request> {
     st.cx.send_response(Response::new_ok(id, res));
     Ok(())
   })?;
+  r = helpers::try_req::<ext::SearchByTy, _>(r, |id, params| {
+    let res = match st.analysis.search_by_ty(params.query.as_str()) {
+      Some(xs) => {
+        let items: Vec<_> = xs
+          .into_iter()
+          .map(|x| ext::SearchByTyItem {
+            name: x.name,
+            ty: x.ty_scheme,
+            location: x.def.and_then(|def| convert::lsp_location(&st.cx.paths, def)),
+            documentation: x.doc,
+          })
+          .collect();
+        Response::new_ok(id, items)
+      }
+      None => Response::new_err(
+        id,
+        lsp_server::ErrorCode::InvalidParams as i32,
+        format!("couldn't parse `{}` as a type", params.query),
+      ),
+    };
+    st.cx.send_response(res);
+    Ok(())
+  })?;
+  r = helpers::try_req::<ext::ExplainTy, _>(r, |id, params| {
+    let pos = convert::text_doc_pos_params(&st.cx.fs, &mut st.cx.paths, &params)?;
+    let res: Option<Vec<_>> = st.analysis.explain_ty(pos).map(|xs| {
+      xs.into_iter()
+        .filter_map(|x| {
+          let location = convert::lsp_location(&st.cx.paths, x.range)?;
+          Some(ext::ExplainTyItem { location, code: x.code, ty: x.ty })
+        })
+        .collect()
+    });
+    st.cx.send_response(Response::new_ok(id, res));
+    Ok(())
+  })?;
   ControlFlow::Continue(r)
 }
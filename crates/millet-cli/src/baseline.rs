@@ -0,0 +1,41 @@
+//! Writing a baseline file of known diagnostics.
+
+use crate::report::{self, Item};
+use std::collections::BTreeMap;
+
+/// Writes the diagnostics in source files among `items` to a baseline file at `path`, and removes
+/// them from `items`, since they are now known.
+pub(crate) fn write(
+  cx: &report::Cx<'_>,
+  path: &std::path::Path,
+  items: &mut Vec<Item<'_>>,
+) -> std::io::Result<()> {
+  let mut counts = BTreeMap::<(String, String, str_util::SmolStr), usize>::new();
+  items.retain(|item| {
+    let (id, d) = match item {
+      Item::Input(_) => return true,
+      Item::Analysis(id, d) => (*id, d),
+    };
+    // diagnostics in group files have no fingerprint.
+    let fingerprint = match cx.analysis.fingerprint(id, d.code, d.range) {
+      Some(x) => x,
+      None => return true,
+    };
+    let file = report::uri_path(report::rel_path(cx, id));
+    *counts.entry((file, d.code.to_string(), fingerprint)).or_default() += 1;
+    false
+  });
+  let diagnostics: Vec<_> = counts
+    .into_iter()
+    .map(|((path, code, fingerprint), count)| config::baseline::Entry {
+      path: path.into(),
+      code: code.into(),
+      fingerprint,
+      count,
+    })
+    .collect();
+  let root = config::baseline::Root { version: 1, diagnostics };
+  let mut contents = serde_json::to_string_pretty(&root).expect("should serialize");
+  contents.push('\n');
+  std::fs::write(path, contents)
+}
@@ -0,0 +1,58 @@
+//! Unified diffs of file contents.
+
+use std::fmt::Write as _;
+
+/// How many unchanged lines to show around changed lines.
+const CONTEXT: usize = 3;
+
+/// Returns a unified diff from `old` to `new`, both named by `path`, or `None` if there are no
+/// changes.
+pub(crate) fn unified(path: &str, old: &str, new: &str) -> Option<String> {
+  let lines = diff::lines(old, new);
+  let changed: Vec<_> = lines
+    .iter()
+    .enumerate()
+    .filter_map(|(idx, line)| (!matches!(line, diff::Result::Both(_, _))).then_some(idx))
+    .collect();
+  let first = *changed.first()?;
+  let mut ret = format!("--- a/{path}\n+++ b/{path}\n");
+  let mut hunk_start = first;
+  let mut hunk_end = first;
+  for &idx in &changed[1..] {
+    if idx - hunk_end > 2 * CONTEXT {
+      hunk(&mut ret, &lines, hunk_start, hunk_end);
+      hunk_start = idx;
+    }
+    hunk_end = idx;
+  }
+  hunk(&mut ret, &lines, hunk_start, hunk_end);
+  Some(ret)
+}
+
+/// Writes the hunk for the changed lines from `first` to `last`, inclusive, with context.
+fn hunk(buf: &mut String, lines: &[diff::Result<&str>], first: usize, last: usize) {
+  let start = first.saturating_sub(CONTEXT);
+  let end = (last + CONTEXT + 1).min(lines.len());
+  let (old_before, new_before) = counts(&lines[..start]);
+  let (old_len, new_len) = counts(&lines[start..end]);
+  let old_start = if old_len == 0 { old_before } else { old_before + 1 };
+  let new_start = if new_len == 0 { new_before } else { new_before + 1 };
+  writeln!(buf, "@@ -{old_start},{old_len} +{new_start},{new_len} @@").expect("can't fail");
+  for line in &lines[start..end] {
+    let (prefix, text) = match line {
+      diff::Result::Left(s) => ('-', s),
+      diff::Result::Both(s, _) => (' ', s),
+      diff::Result::Right(s) => ('+', s),
+    };
+    writeln!(buf, "{prefix}{text}").expect("can't fail");
+  }
+}
+
+/// Returns how many of the lines are in the old and new contents.
+fn counts(lines: &[diff::Result<&str>]) -> (usize, usize) {
+  lines.iter().fold((0, 0), |(old, new), line| match line {
+    diff::Result::Left(_) => (old + 1, new),
+    diff::Result::Both(_, _) => (old + 1, new + 1),
+    diff::Result::Right(_) => (old, new + 1),
+  })
+}
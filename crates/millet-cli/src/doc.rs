@@ -0,0 +1,294 @@
+//! Generating API documentation for the signatures, structures, and functors in a project.
+
+use crate::{query, report, EXIT_FAILED, EXIT_OK, EXIT_USAGE};
+use paths::FileSystem as _;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+/// Returns the exit code.
+pub(crate) fn run(mut args: pico_args::Arguments) -> i32 {
+  let out: std::path::PathBuf = match args.value_from_str("--out") {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  let root: Option<std::path::PathBuf> = match args.opt_free_from_str() {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  let fs = paths::RealFileSystem::default();
+  let root = root.unwrap_or_else(|| std::path::PathBuf::from("."));
+  let root = match fs.canonicalize(root.as_path()) {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_input_error(root.as_path(), &input::Error::from_io(root.clone(), e));
+      return EXIT_USAGE;
+    }
+  };
+  let mut store = paths::Store::new();
+  let inp = input::Input::new(&fs, &mut store, &root);
+  for err in &inp.errors {
+    crate::show_input_error(root.as_path(), err);
+  }
+  let options = analysis::Options {
+    lines: config::DiagnosticLines::One,
+    ignore: config::init::DiagnosticsIgnore::All,
+    format: config::init::FormatEngine::None,
+  };
+  let mut an = analysis::Analysis::new(analysis::StdBasis::full(), options);
+  an.get_many(&inp);
+  let cx = report::Cx { root: root.as_path(), store: &store, input: &inp, analysis: &an };
+  let pages = get(&cx);
+  let anchors = anchors(&pages);
+  let mut files =
+    vec![("index.md".to_owned(), index_md(&pages)), ("index.html".to_owned(), index_html(&pages))];
+  for page in &pages {
+    files.push((format!("{}.md", page.stem), page_md(&anchors, page)));
+    files.push((format!("{}.html", page.stem), page_html(&anchors, page)));
+  }
+  if let Err(e) = std::fs::create_dir_all(out.as_path()) {
+    crate::show_input_error(root.as_path(), &input::Error::from_io(out.clone(), e));
+    return EXIT_FAILED;
+  }
+  let mut failed = false;
+  for (name, contents) in files {
+    let path = out.join(name);
+    if let Err(e) = std::fs::write(path.as_path(), contents) {
+      crate::show_input_error(root.as_path(), &input::Error::from_io(path, e));
+      failed = true;
+    }
+  }
+  let suffix = if pages.len() == 1 { "" } else { "s" };
+  println!("documented {} file{suffix} in {}", pages.len(), out.display());
+  if failed {
+    EXIT_FAILED
+  } else {
+    EXIT_OK
+  }
+}
+
+/// The documentation for one source file.
+struct Page {
+  path: paths::PathId,
+  /// The path of the source file relative to the root, `/`-separated.
+  name: String,
+  /// The name of the output files for this, without extension. Output files are all in the same
+  /// directory, so this is `name` with `/` replaced by `.`.
+  stem: String,
+  /// The top-level signatures, structures, and functors.
+  items: Vec<analysis::DocItem>,
+}
+
+/// A place an item is documented, which other items can link to.
+struct Anchor {
+  stem: String,
+  id: String,
+  title: String,
+}
+
+/// The anchors, keyed by the path and the start line and column of the item.
+type Anchors = BTreeMap<(paths::PathId, u32, u32), Anchor>;
+
+fn get(cx: &report::Cx<'_>) -> Vec<Page> {
+  let mut ret: Vec<_> = cx
+    .input
+    .sources
+    .keys()
+    .filter_map(|&path| {
+      let items: Vec<_> = cx
+        .analysis
+        .doc_items(path)?
+        .into_iter()
+        .filter(|item| {
+          matches!(
+            item.kind,
+            sml_namespace::SymbolKind::Structure
+              | sml_namespace::SymbolKind::Signature
+              | sml_namespace::SymbolKind::Functor
+          )
+        })
+        .collect();
+      if items.is_empty() {
+        return None;
+      }
+      let name = report::uri_path(report::rel_path(cx, path));
+      let stem = name.replace('/', ".");
+      Some(Page { path, name, stem, items })
+    })
+    .collect();
+  ret.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+  for page in &mut ret {
+    sort_items(&mut page.items);
+  }
+  ret
+}
+
+fn sort_items(items: &mut [analysis::DocItem]) {
+  items.sort_unstable_by_key(|item| (item.range.start.line, item.range.start.col));
+  for item in items {
+    sort_items(&mut item.children);
+  }
+}
+
+fn anchors(pages: &[Page]) -> Anchors {
+  let mut ret = Anchors::new();
+  for page in pages {
+    anchors_items(&mut ret, page, &page.items);
+  }
+  ret
+}
+
+fn anchors_items(ac: &mut Anchors, page: &Page, items: &[analysis::DocItem]) {
+  for item in items {
+    let anchor = Anchor { stem: page.stem.clone(), id: anchor_id(item), title: title(item) };
+    ac.insert((page.path, item.range.start.line, item.range.start.col), anchor);
+    anchors_items(ac, page, &item.children);
+  }
+}
+
+fn anchor_id(item: &analysis::DocItem) -> String {
+  format!("L{}C{}", item.range.start.line + 1, item.range.start.col + 1)
+}
+
+fn title(item: &analysis::DocItem) -> String {
+  format!("{} {}", query::symbol_kind_str(item.kind), item.name)
+}
+
+/// Returns the code to show for the item, if any.
+fn code(item: &analysis::DocItem) -> Option<String> {
+  let detail = item.detail.as_deref()?;
+  let ret = match item.kind {
+    sml_namespace::SymbolKind::Type => detail.to_owned(),
+    _ => format!("{} : {detail}", item.name),
+  };
+  Some(ret)
+}
+
+/// Returns the anchors the item links to.
+fn links<'a>(anchors: &'a Anchors, item: &analysis::DocItem) -> Vec<&'a Anchor> {
+  item
+    .links
+    .iter()
+    .filter_map(|link| anchors.get(&(link.path, link.val.start.line, link.val.start.col)))
+    .collect()
+}
+
+fn index_md(pages: &[Page]) -> String {
+  let mut ret = String::from("# API documentation\n");
+  for page in pages {
+    writeln!(ret, "\n## [{}]({}.md)\n", page.name, page.stem).expect("can't fail");
+    for item in &page.items {
+      let (stem, id, title) = (&page.stem, anchor_id(item), title(item));
+      writeln!(ret, "- [`{title}`]({stem}.md#{id})").expect("can't fail");
+    }
+  }
+  ret
+}
+
+fn page_md(anchors: &Anchors, page: &Page) -> String {
+  let mut ret = format!("# {}\n\n[Index](index.md)\n", page.name);
+  for item in &page.items {
+    item_md(&mut ret, anchors, 2, item);
+  }
+  ret
+}
+
+fn item_md(ac: &mut String, anchors: &Anchors, level: usize, item: &analysis::DocItem) {
+  let hashes = "#".repeat(level.min(6));
+  writeln!(ac, "\n<a id=\"{}\"></a>\n", anchor_id(item)).expect("can't fail");
+  writeln!(ac, "{hashes} `{}`", title(item)).expect("can't fail");
+  if let Some(code) = code(item) {
+    writeln!(ac, "\n```sml\n{code}\n```").expect("can't fail");
+  }
+  if let Some(doc) = &item.doc {
+    writeln!(ac, "\n{}", doc.trim()).expect("can't fail");
+  }
+  let links = links(anchors, item);
+  if !links.is_empty() {
+    let links: Vec<_> =
+      links.iter().map(|a| format!("[`{}`]({}.md#{})", a.title, a.stem, a.id)).collect();
+    writeln!(ac, "\nUses: {}", links.join(", ")).expect("can't fail");
+  }
+  for child in &item.children {
+    item_md(ac, anchors, level + 1, child);
+  }
+}
+
+fn index_html(pages: &[Page]) -> String {
+  let mut body = String::from("<h1>API documentation</h1>\n");
+  for page in pages {
+    let (name, stem) = (escape(page.name.as_str()), escape(page.stem.as_str()));
+    writeln!(body, "<h2><a href=\"{stem}.html\">{name}</a></h2>\n<ul>").expect("can't fail");
+    for item in &page.items {
+      let (id, title) = (anchor_id(item), escape(title(item).as_str()));
+      writeln!(body, "<li><a href=\"{stem}.html#{id}\"><code>{title}</code></a></li>")
+        .expect("can't fail");
+    }
+    body.push_str("</ul>\n");
+  }
+  html("API documentation", body.as_str())
+}
+
+fn page_html(anchors: &Anchors, page: &Page) -> String {
+  let name = escape(page.name.as_str());
+  let mut body = format!("<h1>{name}</h1>\n<p><a href=\"index.html\">Index</a></p>\n");
+  for item in &page.items {
+    item_html(&mut body, anchors, 2, item);
+  }
+  html(page.name.as_str(), body.as_str())
+}
+
+fn item_html(ac: &mut String, anchors: &Anchors, level: usize, item: &analysis::DocItem) {
+  let level = level.min(6);
+  let (id, title) = (anchor_id(item), escape(title(item).as_str()));
+  writeln!(ac, "<section>\n<h{level} id=\"{id}\"><code>{title}</code></h{level}>")
+    .expect("can't fail");
+  if let Some(code) = code(item) {
+    writeln!(ac, "<pre><code>{}</code></pre>", escape(code.as_str())).expect("can't fail");
+  }
+  if let Some(doc) = &item.doc {
+    pulldown_cmark::html::push_html(ac, pulldown_cmark::Parser::new(doc.as_str()));
+  }
+  let links = links(anchors, item);
+  if !links.is_empty() {
+    let links: Vec<_> = links
+      .iter()
+      .map(|a| {
+        let (stem, title) = (escape(a.stem.as_str()), escape(a.title.as_str()));
+        format!("<a href=\"{stem}.html#{}\"><code>{title}</code></a>", a.id)
+      })
+      .collect();
+    writeln!(ac, "<p>Uses: {}</p>", links.join(", ")).expect("can't fail");
+  }
+  for child in &item.children {
+    item_html(ac, anchors, level + 1, child);
+  }
+  ac.push_str("</section>\n");
+}
+
+fn html(title: &str, body: &str) -> String {
+  let title = escape(title);
+  format!(
+    "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n\
+     <body>\n{body}</body>\n</html>\n"
+  )
+}
+
+fn escape(s: &str) -> String {
+  let mut ret = String::with_capacity(s.len());
+  for c in s.chars() {
+    match c {
+      '&' => ret.push_str("&amp;"),
+      '<' => ret.push_str("&lt;"),
+      '>' => ret.push_str("&gt;"),
+      '"' => ret.push_str("&quot;"),
+      _ => ret.push(c),
+    }
+  }
+  ret
+}
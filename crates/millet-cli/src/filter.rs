@@ -0,0 +1,79 @@
+//! Choosing which diagnostics to report, and which of those make the run fail.
+
+use crate::report::Item;
+use diagnostic::{Code, Severity};
+
+/// Options for filtering and gating diagnostics.
+#[derive(Debug, Default)]
+pub(crate) struct Filter {
+  /// Whether warnings make the run fail, like errors do.
+  pub(crate) deny_warnings: bool,
+  /// Whether to hide warnings.
+  pub(crate) errors_only: bool,
+  /// If non-empty, only these codes are reported.
+  pub(crate) only: Vec<Code>,
+  /// These codes are never reported.
+  pub(crate) exclude: Vec<Code>,
+  /// The maximum number of diagnostics to show.
+  pub(crate) max: Option<usize>,
+}
+
+impl Filter {
+  /// Parses the options from the args.
+  pub(crate) fn from_args(args: &mut pico_args::Arguments) -> Result<Self, pico_args::Error> {
+    let deny_warnings = !args.values_from_fn("--deny", parse_deny)?.is_empty();
+    let min_severity = args.opt_value_from_fn("--min-severity", parse_severity)?;
+    let only = args.values_from_fn("--only", parse_codes)?.into_iter().flatten().collect();
+    let exclude =
+      args.values_from_fn("--exclude-code", parse_codes)?.into_iter().flatten().collect();
+    let max = args.opt_value_from_str("--max-diagnostics")?;
+    Ok(Filter {
+      deny_warnings,
+      errors_only: matches!(min_severity, Some(Severity::Error)),
+      only,
+      exclude,
+      max,
+    })
+  }
+
+  /// Returns whether to report the item at all.
+  pub(crate) fn keep(&self, item: &Item<'_>) -> bool {
+    let code = item.code();
+    if self.errors_only && matches!(item.severity(), Severity::Warning) {
+      return false;
+    }
+    if !self.only.is_empty() && !self.only.contains(&code) {
+      return false;
+    }
+    !self.exclude.contains(&code)
+  }
+
+  /// Returns whether the item, which should be kept, makes the run fail.
+  pub(crate) fn fails(&self, item: &Item<'_>) -> bool {
+    match item.severity() {
+      Severity::Error => true,
+      Severity::Warning => self.deny_warnings,
+    }
+  }
+}
+
+fn parse_deny(s: &str) -> Result<(), String> {
+  match s {
+    "warnings" => Ok(()),
+    _ => Err(format!("unknown lint level `{s}`, expected: warnings")),
+  }
+}
+
+fn parse_severity(s: &str) -> Result<Severity, String> {
+  match s {
+    "warning" => Ok(Severity::Warning),
+    "error" => Ok(Severity::Error),
+    _ => Err(format!("unknown severity `{s}`, expected one of: warning, error")),
+  }
+}
+
+fn parse_codes(s: &str) -> Result<Vec<Code>, String> {
+  s.split(',')
+    .map(|code| code.trim().parse::<Code>().map_err(|e| format!("invalid code `{code}`: {e}")))
+    .collect()
+}
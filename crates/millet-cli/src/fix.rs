@@ -0,0 +1,67 @@
+//! Applying quick fixes to source files.
+
+use paths::{PathId, PathMap};
+use text_size_util::TextRange;
+
+/// The most times to analyze and then fix. Fixing may reveal more things to fix, and we skip fixes
+/// that overlap other fixes until the next time.
+const MAX_ROUNDS: usize = 10;
+
+/// Applies every quick fix that needs no choices to the sources in `inp`, re-analyzing after each
+/// round of fixes. Returns the original contents of the sources that changed.
+pub(crate) fn apply(an: &mut analysis::Analysis, inp: &mut input::Input) -> PathMap<String> {
+  let mut original = PathMap::<String>::default();
+  let paths: Vec<PathId> = inp.sources.keys().copied().collect();
+  for _ in 0..MAX_ROUNDS {
+    an.get_many_text_range(inp);
+    let mut changed = false;
+    for &path in &paths {
+      let edits = match an.source_pos_db(path) {
+        Some(pos_db) => non_overlapping(pos_db, an.fixes(path)),
+        None => continue,
+      };
+      if edits.is_empty() {
+        continue;
+      }
+      let contents = inp.sources.get_mut(&path).expect("path should be a source");
+      original.entry(path).or_insert_with(|| contents.clone());
+      for (range, new_text) in edits.into_iter().rev() {
+        contents.replace_range(std::ops::Range::<usize>::from(range), new_text.as_str());
+      }
+      changed = true;
+    }
+    if !changed {
+      break;
+    }
+  }
+  original
+}
+
+/// Returns the edits of the actions whose edits do not overlap the edits of earlier actions, sorted
+/// by start.
+fn non_overlapping(
+  pos_db: &text_pos::PositionDb,
+  actions: Vec<analysis::CodeAction>,
+) -> Vec<(TextRange, String)> {
+  let mut actions: Vec<Vec<(TextRange, String)>> = actions
+    .into_iter()
+    .filter_map(|action| {
+      action
+        .edits
+        .into_iter()
+        .map(|edit| Some((pos_db.text_range_utf16(edit.range)?, edit.new_text)))
+        .collect()
+    })
+    .collect();
+  actions.sort_by_key(|edits| edits.iter().map(|(range, _)| range.start()).min());
+  let mut ret = Vec::<(TextRange, String)>::new();
+  for edits in actions {
+    let overlaps =
+      edits.iter().any(|(range, _)| ret.iter().any(|(other, _)| other.intersect(*range).is_some()));
+    if !overlaps {
+      ret.extend(edits);
+    }
+  }
+  ret.sort_by_key(|(range, _)| range.start());
+  ret
+}
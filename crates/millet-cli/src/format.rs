@@ -0,0 +1,111 @@
+//! Formatting files, or checking that they are formatted.
+
+use crate::{diff, report, EXIT_FAILED, EXIT_OK, EXIT_USAGE};
+use paths::FileSystem as _;
+
+/// The tab size to format with.
+const TAB_SIZE: u32 = 2;
+
+/// Returns the exit code.
+pub(crate) fn run(mut args: pico_args::Arguments) -> i32 {
+  let check = args.contains("--check");
+  let stdin = args.contains("--stdin");
+  let engine = match args.opt_value_from_fn("--engine", parse_engine) {
+    Ok(x) => x.unwrap_or(config::init::FormatEngine::Naive),
+    Err(e) => {
+      crate::show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  let options = analysis::Options {
+    lines: config::DiagnosticLines::One,
+    ignore: config::init::DiagnosticsIgnore::All,
+    format: engine,
+  };
+  let mut an = analysis::Analysis::new(analysis::StdBasis::full(), options);
+  if stdin {
+    let mut contents = String::new();
+    if let Err(e) = std::io::Read::read_to_string(&mut std::io::stdin(), &mut contents) {
+      println!("error: couldn't read stdin: {e}");
+      return EXIT_USAGE;
+    }
+    return match an.format_str(contents.as_str(), TAB_SIZE) {
+      Ok(formatted) => {
+        print!("{formatted}");
+        EXIT_OK
+      }
+      Err(e) => {
+        eprintln!("error: {e:#}");
+        EXIT_FAILED
+      }
+    };
+  }
+  let root: std::path::PathBuf = match args.free_from_str() {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  let fs = paths::RealFileSystem::default();
+  let root = match fs.canonicalize(root.as_path()) {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_input_error(root.as_path(), &input::Error::from_io(root.clone(), e));
+      return EXIT_USAGE;
+    }
+  };
+  let mut store = paths::Store::new();
+  let inp = input::Input::new(&fs, &mut store, &root);
+  for err in &inp.errors {
+    crate::show_input_error(root.as_path(), err);
+  }
+  an.get_many_text_range(&inp);
+  let mut sources: Vec<_> = inp.sources.iter().collect();
+  sources.sort_unstable_by_key(|&(&id, _)| store.get_path(id).as_path());
+  let mut changed = 0usize;
+  let mut failed = !inp.errors.is_empty();
+  for (&id, old) in sources {
+    let path = store.get_path(id).as_path();
+    let rel = path.strip_prefix(root.as_path()).unwrap_or(path);
+    let new = match an.format(id, TAB_SIZE) {
+      Ok((x, _)) => x,
+      Err(e) => {
+        eprintln!("{}: error: {e:#}", rel.display());
+        failed = true;
+        continue;
+      }
+    };
+    if new == *old {
+      continue;
+    }
+    changed += 1;
+    if check {
+      if let Some(diff) = diff::unified(&report::uri_path(rel), old, &new) {
+        print!("{diff}");
+      }
+    } else if let Err(e) = std::fs::write(path, new.as_str()) {
+      crate::show_input_error(root.as_path(), &input::Error::from_io(path.to_owned(), e));
+      failed = true;
+    }
+  }
+  let suffix = if changed == 1 { "" } else { "s" };
+  if check {
+    println!("{changed} file{suffix} would be reformatted");
+  } else {
+    println!("reformatted {changed} file{suffix}");
+  }
+  if failed || (check && changed != 0) {
+    EXIT_FAILED
+  } else {
+    EXIT_OK
+  }
+}
+
+fn parse_engine(s: &str) -> Result<config::init::FormatEngine, String> {
+  match s {
+    "naive" => Ok(config::init::FormatEngine::Naive),
+    "smlfmt" => Ok(config::init::FormatEngine::Smlfmt),
+    _ => Err(format!("unknown format engine `{s}`, expected one of: naive, smlfmt")),
+  }
+}
@@ -0,0 +1,315 @@
+//! Printing the dependency graph of the files and groups in a project.
+
+use crate::{report, EXIT_OK, EXIT_USAGE};
+use paths::FileSystem as _;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+
+/// Returns the exit code.
+pub(crate) fn run(mut args: pico_args::Arguments) -> i32 {
+  let groups = args.contains("--groups");
+  let format = match args.opt_value_from_str::<_, Format>("--format") {
+    Ok(x) => x.unwrap_or_default(),
+    Err(e) => {
+      crate::show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  let root: Option<std::path::PathBuf> = match args.opt_free_from_str() {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  let fs = paths::RealFileSystem::default();
+  let root = root.unwrap_or_else(|| std::path::PathBuf::from("."));
+  let root = match fs.canonicalize(root.as_path()) {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_input_error(root.as_path(), &input::Error::from_io(root.clone(), e));
+      return EXIT_USAGE;
+    }
+  };
+  let mut store = paths::Store::new();
+  let inp = input::Input::new(&fs, &mut store, &root);
+  for err in &inp.errors {
+    crate::show_input_error(root.as_path(), err);
+  }
+  let options = analysis::Options {
+    lines: config::DiagnosticLines::One,
+    ignore: config::init::DiagnosticsIgnore::All,
+    format: config::init::FormatEngine::None,
+  };
+  let mut an = analysis::Analysis::new(analysis::StdBasis::full(), options);
+  an.get_many(&inp);
+  let cx = report::Cx { root: root.as_path(), store: &store, input: &inp, analysis: &an };
+  let graph = get(&cx, groups);
+  for cycle in graph.cycles() {
+    eprintln!("warning: dependency cycle: {}", cycle.join(", "));
+  }
+  match format {
+    Format::Dot => print!("{}", graph.dot()),
+    Format::Json => {
+      println!("{}", serde_json::to_string_pretty(&graph.json()).expect("should serialize"));
+    }
+  }
+  EXIT_OK
+}
+
+/// How to print the graph.
+#[derive(Debug, Default, Clone, Copy)]
+enum Format {
+  /// A Graphviz DOT digraph.
+  #[default]
+  Dot,
+  /// A JSON object with the nodes and edges.
+  Json,
+}
+
+impl std::str::FromStr for Format {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "dot" => Ok(Format::Dot),
+      "json" => Ok(Format::Json),
+      _ => Err(format!("unknown graph format `{s}`, expected one of: dot, json")),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum NodeKind {
+  Group,
+  Source,
+}
+
+impl NodeKind {
+  fn as_str(self) -> &'static str {
+    match self {
+      NodeKind::Group => "group",
+      NodeKind::Source => "source",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum EdgeKind {
+  /// A group lists a source file.
+  Contains,
+  /// A group lists another group.
+  Includes,
+  /// A source file uses a definition from another source file. When collapsed to groups, a file
+  /// in one group uses a definition from a file in another group.
+  Uses,
+}
+
+impl EdgeKind {
+  fn as_str(self) -> &'static str {
+    match self {
+      EdgeKind::Contains => "contains",
+      EdgeKind::Includes => "includes",
+      EdgeKind::Uses => "uses",
+    }
+  }
+}
+
+/// The nodes and edges, keyed by path relative to the root, so output is sorted and stable.
+#[derive(Debug, Default)]
+struct Graph {
+  nodes: BTreeMap<String, NodeKind>,
+  edges: BTreeSet<(String, String, EdgeKind)>,
+}
+
+impl Graph {
+  /// Returns the cycles, each as the sorted names of the nodes in it. Each node is in at most one
+  /// cycle.
+  fn cycles(&self) -> Vec<Vec<&str>> {
+    let mut fwd = BTreeMap::<&str, Vec<&str>>::new();
+    let mut rev = BTreeMap::<&str, Vec<&str>>::new();
+    for (from, to, _) in &self.edges {
+      fwd.entry(from.as_str()).or_default().push(to.as_str());
+      rev.entry(to.as_str()).or_default().push(from.as_str());
+    }
+    // Kosaraju's algorithm for the strongly connected components. First, order the nodes by when
+    // a depth-first search of the edges finishes with them.
+    let mut done = BTreeSet::<&str>::new();
+    let mut order = Vec::<&str>::with_capacity(self.nodes.len());
+    for name in self.nodes.keys() {
+      if !done.insert(name.as_str()) {
+        continue;
+      }
+      let mut stack = vec![(name.as_str(), 0usize)];
+      while let Some((node, idx)) = stack.pop() {
+        match fwd.get(node).and_then(|xs| xs.get(idx)) {
+          Some(&next) => {
+            stack.push((node, idx + 1));
+            if done.insert(next) {
+              stack.push((next, 0));
+            }
+          }
+          None => order.push(node),
+        }
+      }
+    }
+    // then, in reverse of that order, search the reversed edges for each component.
+    let mut seen = BTreeSet::<&str>::new();
+    let mut ret = Vec::<Vec<&str>>::new();
+    for &name in order.iter().rev() {
+      if !seen.insert(name) {
+        continue;
+      }
+      let mut component = Vec::<&str>::new();
+      let mut stack = vec![name];
+      while let Some(node) = stack.pop() {
+        component.push(node);
+        for &prev in rev.get(node).into_iter().flatten() {
+          if seen.insert(prev) {
+            stack.push(prev);
+          }
+        }
+      }
+      if component.len() > 1 {
+        component.sort_unstable();
+        ret.push(component);
+      }
+    }
+    ret.sort_unstable();
+    ret
+  }
+
+  fn dot(&self) -> String {
+    let cycles = self.cycles();
+    let mut ret = String::from("digraph millet {\n");
+    for (name, kind) in &self.nodes {
+      let shape = match kind {
+        NodeKind::Group => "box",
+        NodeKind::Source => "ellipse",
+      };
+      writeln!(ret, "  {} [shape={shape}];", dot_id(name)).expect("can't fail");
+    }
+    for (from, to, kind) in &self.edges {
+      let style = match kind {
+        EdgeKind::Contains => "solid",
+        EdgeKind::Includes => "bold",
+        EdgeKind::Uses => "dashed",
+      };
+      let color = if in_cycle(&cycles, from, to) { ", color=red" } else { "" };
+      let (from, to) = (dot_id(from), dot_id(to));
+      writeln!(ret, "  {from} -> {to} [style={style}, label={}{color}];", kind.as_str())
+        .expect("can't fail");
+    }
+    ret.push_str("}\n");
+    ret
+  }
+
+  fn json(&self) -> serde_json::Value {
+    let cycles = self.cycles();
+    let nodes: Vec<_> = self
+      .nodes
+      .iter()
+      .map(|(name, kind)| json!({ "name": name, "kind": kind.as_str() }))
+      .collect();
+    let edges: Vec<_> = self
+      .edges
+      .iter()
+      .map(|(from, to, kind)| {
+        let cycle = in_cycle(&cycles, from, to);
+        json!({ "from": from, "to": to, "kind": kind.as_str(), "cycle": cycle })
+      })
+      .collect();
+    json!({ "nodes": nodes, "edges": edges, "cycles": cycles })
+  }
+}
+
+/// Returns whether the edge is in a cycle, i.e. both of its ends are in the same cycle.
+fn in_cycle(cycles: &[Vec<&str>], from: &str, to: &str) -> bool {
+  cycles.iter().any(|c| c.binary_search(&from).is_ok() && c.binary_search(&to).is_ok())
+}
+
+fn dot_id(s: &str) -> String {
+  format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Gets the graph. If `groups`, collapses it to only the groups.
+fn get(cx: &report::Cx<'_>, groups: bool) -> Graph {
+  let name = |path: paths::PathId| report::uri_path(report::rel_path(cx, path));
+  let mut ret = Graph::default();
+  // the groups that directly list each source file.
+  let mut owners = paths::PathMap::<Vec<paths::PathId>>::default();
+  for (&group, g) in &cx.input.groups {
+    ret.nodes.insert(name(group), NodeKind::Group);
+    let mut listed = Vec::<(paths::PathId, mlb_hir::PathKind)>::new();
+    bas_dec_paths(&mut listed, &g.bas_dec);
+    for (path, kind) in listed {
+      match kind {
+        mlb_hir::PathKind::Source => {
+          owners.entry(path).or_default().push(group);
+          if !groups {
+            ret.edges.insert((name(group), name(path), EdgeKind::Contains));
+          }
+        }
+        mlb_hir::PathKind::Group => {
+          ret.edges.insert((name(group), name(path), EdgeKind::Includes));
+        }
+      }
+    }
+  }
+  for &path in cx.input.sources.keys() {
+    if !groups {
+      ret.nodes.insert(name(path), NodeKind::Source);
+    }
+    for dep in cx.analysis.source_deps(path).unwrap_or_default() {
+      if !groups {
+        ret.edges.insert((name(path), name(dep), EdgeKind::Uses));
+        continue;
+      }
+      let (from, to) = match (owners.get(&path), owners.get(&dep)) {
+        (Some(from), Some(to)) => (from, to),
+        _ => continue,
+      };
+      for &from in from {
+        for &to in to {
+          if from != to {
+            ret.edges.insert((name(from), name(to), EdgeKind::Uses));
+          }
+        }
+      }
+    }
+  }
+  ret
+}
+
+fn bas_dec_paths(ac: &mut Vec<(paths::PathId, mlb_hir::PathKind)>, dec: &mlb_hir::BasDec) {
+  match dec {
+    mlb_hir::BasDec::Basis(_, exp) => bas_exp_paths(ac, exp),
+    mlb_hir::BasDec::Open(_) | mlb_hir::BasDec::Export(..) => {}
+    mlb_hir::BasDec::Local(local_dec, in_dec) => {
+      bas_dec_paths(ac, local_dec);
+      bas_dec_paths(ac, in_dec);
+    }
+    mlb_hir::BasDec::Ann(_, dec) => bas_dec_paths(ac, dec),
+    mlb_hir::BasDec::Path(path, kind) => ac.push((*path, *kind)),
+    mlb_hir::BasDec::SourcePathSet(paths) => {
+      ac.extend(paths.iter().map(|&path| (path, mlb_hir::PathKind::Source)));
+    }
+    mlb_hir::BasDec::Seq(decs) => {
+      for dec in decs {
+        bas_dec_paths(ac, dec);
+      }
+    }
+  }
+}
+
+fn bas_exp_paths(ac: &mut Vec<(paths::PathId, mlb_hir::PathKind)>, exp: &mlb_hir::BasExp) {
+  match exp {
+    mlb_hir::BasExp::Bas(dec) => bas_dec_paths(ac, dec),
+    mlb_hir::BasExp::Name(_) => {}
+    mlb_hir::BasExp::Let(dec, exp) => {
+      bas_dec_paths(ac, dec);
+      bas_exp_paths(ac, exp);
+    }
+  }
+}
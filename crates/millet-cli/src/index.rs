@@ -0,0 +1,554 @@
+//! Exporting an index of definitions, references, hover text, and document symbols for code
+//! navigation services, as SCIP or LSIF.
+
+use crate::{report, EXIT_FAILED, EXIT_OK, EXIT_USAGE};
+use paths::FileSystem as _;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Returns the exit code.
+pub(crate) fn run(mut args: pico_args::Arguments) -> i32 {
+  let format = match args.opt_value_from_str::<_, Format>("--format") {
+    Ok(x) => x.unwrap_or_default(),
+    Err(e) => {
+      crate::show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  let out: Option<std::path::PathBuf> = match args.opt_value_from_str("--out") {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  let root: Option<std::path::PathBuf> = match args.opt_free_from_str() {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  let fs = paths::RealFileSystem::default();
+  let root = root.unwrap_or_else(|| std::path::PathBuf::from("."));
+  let root = match fs.canonicalize(root.as_path()) {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_input_error(root.as_path(), &input::Error::from_io(root.clone(), e));
+      return EXIT_USAGE;
+    }
+  };
+  let mut store = paths::Store::new();
+  let inp = input::Input::new(&fs, &mut store, &root);
+  for err in &inp.errors {
+    crate::show_input_error(root.as_path(), err);
+  }
+  let options = analysis::Options {
+    lines: config::DiagnosticLines::One,
+    ignore: config::init::DiagnosticsIgnore::All,
+    format: config::init::FormatEngine::None,
+  };
+  let mut an = analysis::Analysis::new(analysis::StdBasis::full(), options);
+  an.get_many(&inp);
+  let cx = report::Cx { root: root.as_path(), store: &store, input: &inp, analysis: &an };
+  let index = get(&cx);
+  let (contents, default_out) = match format {
+    Format::Scip => (scip(&cx, &index), "index.scip"),
+    Format::Lsif => (lsif(&cx, &index).into_bytes(), "dump.lsif"),
+  };
+  let out = out.unwrap_or_else(|| std::path::PathBuf::from(default_out));
+  if let Err(e) = std::fs::write(out.as_path(), contents) {
+    crate::show_input_error(root.as_path(), &input::Error::from_io(out, e));
+    return EXIT_FAILED;
+  }
+  let suffix = if index.docs.len() == 1 { "" } else { "s" };
+  println!("indexed {} file{suffix} to {}", index.docs.len(), out.display());
+  EXIT_OK
+}
+
+/// The format of the index.
+#[derive(Debug, Default, Clone, Copy)]
+enum Format {
+  /// The SCIP protobuf format.
+  #[default]
+  Scip,
+  /// The LSIF JSON lines format.
+  Lsif,
+}
+
+impl std::str::FromStr for Format {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "scip" => Ok(Format::Scip),
+      "lsif" => Ok(Format::Lsif),
+      _ => Err(format!("unknown index format `{s}`, expected one of: scip, lsif")),
+    }
+  }
+}
+
+/// The index, independent of format.
+#[derive(Debug, Default)]
+struct Index {
+  /// The documents, sorted by name.
+  docs: Vec<Doc>,
+  /// The symbols defined in all the documents, and the symbols from the std basis they use.
+  symbols: Vec<Symbol>,
+}
+
+/// A source file.
+#[derive(Debug)]
+struct Doc {
+  path: paths::PathId,
+  /// The path relative to the root, `/`-separated.
+  name: String,
+  /// The top-level symbols, as indices into the symbols of the index.
+  symbols: Vec<usize>,
+  /// The definitions and references in this, sorted by start.
+  occurrences: Vec<Occurrence>,
+}
+
+/// A document symbol, local binding, or std basis definition.
+#[derive(Debug)]
+struct Symbol {
+  /// The SCIP symbol. This is globally unique, except for local bindings, which are unique in their
+  /// document.
+  scip: String,
+  name: String,
+  kind: sml_namespace::SymbolKind,
+  /// The index of the document that defines this and the range of the whole definition, or `None`
+  /// if this is from the std basis.
+  def: Option<(usize, text_pos::RangeUtf16)>,
+  hover: Option<String>,
+  parent: Option<usize>,
+  children: Vec<usize>,
+}
+
+#[derive(Debug)]
+struct Occurrence {
+  range: text_pos::RangeUtf16,
+  /// The index of the symbol.
+  symbol: usize,
+  definition: bool,
+}
+
+const SCIP_PREFIX: &str = "millet . . . ";
+
+/// A location, as a key in an ordered map.
+type LocKey = (paths::PathId, u32, u32, u32, u32);
+
+fn loc_key(path: paths::PathId, r: text_pos::RangeUtf16) -> LocKey {
+  (path, r.start.line, r.start.col, r.end.line, r.end.col)
+}
+
+fn get(cx: &report::Cx<'_>) -> Index {
+  let mut docs: Vec<_> = cx
+    .input
+    .sources
+    .keys()
+    .map(|&path| Doc {
+      path,
+      name: report::uri_path(report::rel_path(cx, path)),
+      symbols: Vec::new(),
+      occurrences: Vec::new(),
+    })
+    .collect();
+  docs.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+  let doc_indices: BTreeMap<_, _> = docs.iter().enumerate().map(|(i, d)| (d.path, i)).collect();
+  let mut ret = Index { docs, symbols: Vec::new() };
+  let mut cx =
+    SymbolCx { cx, doc_indices: &doc_indices, seen: BTreeSet::new(), defined: BTreeSet::new() };
+  for doc in 0..ret.docs.len() {
+    let path = ret.docs[doc].path;
+    let prefix = format!("{SCIP_PREFIX}{}/", descriptor_name(ret.docs[doc].name.as_str()));
+    for sym in cx.cx.analysis.document_symbols(path).unwrap_or_default() {
+      let id = add_symbol(&mut cx, &mut ret, doc, None, prefix.as_str(), sym);
+      ret.docs[doc].symbols.push(id);
+    }
+  }
+  add_uses(&mut cx, &mut ret);
+  for doc in &mut ret.docs {
+    doc.occurrences.sort_by_key(|x| (x.range.start.line, x.range.start.col, !x.definition));
+  }
+  ret
+}
+
+/// The state for adding symbols.
+struct SymbolCx<'a> {
+  cx: &'a report::Cx<'a>,
+  doc_indices: &'a BTreeMap<paths::PathId, usize>,
+  /// The SCIP symbols so far.
+  seen: BTreeSet<String>,
+  /// The locations of the names of the document symbols.
+  defined: BTreeSet<LocKey>,
+}
+
+fn add_symbol(
+  cx: &mut SymbolCx<'_>,
+  index: &mut Index,
+  doc: usize,
+  parent: Option<usize>,
+  prefix: &str,
+  sym: analysis::DocumentSymbol,
+) -> usize {
+  let suffix = match sym.kind {
+    sml_namespace::SymbolKind::Structure
+    | sml_namespace::SymbolKind::Signature
+    | sml_namespace::SymbolKind::Functor => '/',
+    sml_namespace::SymbolKind::Type => '#',
+    sml_namespace::SymbolKind::Constructor
+    | sml_namespace::SymbolKind::Exception
+    | sml_namespace::SymbolKind::Function
+    | sml_namespace::SymbolKind::Value => '.',
+  };
+  let mut scip = format!("{prefix}{}{suffix}", descriptor_name(sym.name.as_str()));
+  if cx.seen.contains(&scip) {
+    // e.g. a name defined twice in the same structure. disambiguate by the position.
+    let start = sym.selection_range.start;
+    let name = format!("{}@{}:{}", sym.name, start.line + 1, start.col + 1);
+    scip = format!("{prefix}{}{suffix}", descriptor_name(name.as_str()));
+  }
+  cx.seen.insert(scip.clone());
+  let path = index.docs[doc].path;
+  let name_pos = path.wrap(sym.selection_range.start);
+  cx.defined.insert(loc_key(path, sym.selection_range));
+  let hover = cx.cx.analysis.get_md(name_pos, false).map(|(md, _)| md);
+  let id = index.symbols.len();
+  let range = sym.selection_range;
+  index.docs[doc].occurrences.push(Occurrence { range, symbol: id, definition: true });
+  for loc in cx.cx.analysis.find_all_references(name_pos).unwrap_or_default() {
+    let ref_doc = match cx.doc_indices.get(&loc.path) {
+      Some(&x) => x,
+      None => continue,
+    };
+    if ref_doc == doc && (loc.val == sym.range || loc.val == sym.selection_range) {
+      continue;
+    }
+    let occ = Occurrence { range: loc.val, symbol: id, definition: false };
+    index.docs[ref_doc].occurrences.push(occ);
+  }
+  let child_prefix = if suffix == '/' { scip.clone() } else { prefix.to_owned() };
+  index.symbols.push(Symbol {
+    scip,
+    name: sym.name,
+    kind: sym.kind,
+    def: Some((doc, sym.range)),
+    hover,
+    parent,
+    children: Vec::new(),
+  });
+  for child in sym.children {
+    let child = add_symbol(cx, index, doc, Some(id), child_prefix.as_str(), child);
+    index.symbols[id].children.push(child);
+  }
+  id
+}
+
+/// Adds the uses of local bindings and the std basis, and their definitions. Uses of document
+/// symbols were already added from their references.
+fn add_uses(cx: &mut SymbolCx<'_>, index: &mut Index) {
+  let mut locals = BTreeMap::<LocKey, usize>::new();
+  let mut std_basis = HashMap::<analysis::StdBasisDef, usize>::new();
+  for doc in 0..index.docs.len() {
+    let path = index.docs[doc].path;
+    for u in cx.cx.analysis.uses(path).unwrap_or_default() {
+      let id = match u.def {
+        analysis::UseDef::Source(def) => {
+          let key = loc_key(def.path, def.val);
+          if cx.defined.contains(&key) {
+            continue;
+          }
+          let def_doc = match cx.doc_indices.get(&def.path) {
+            Some(&x) => x,
+            None => continue,
+          };
+          let id = *locals.entry(key).or_insert_with(|| {
+            let id = index.symbols.len();
+            let hover = cx.cx.analysis.get_md(def.path.wrap(def.val.start), false);
+            index.symbols.push(Symbol {
+              scip: format!("local {id}"),
+              name: u.name.clone(),
+              kind: sml_namespace::SymbolKind::Value,
+              def: Some((def_doc, def.val)),
+              hover: hover.map(|(md, _)| md),
+              parent: None,
+              children: Vec::new(),
+            });
+            let occ = Occurrence { range: def.val, symbol: id, definition: true };
+            index.docs[def_doc].occurrences.push(occ);
+            id
+          });
+          if def.path == path && def.val == u.range {
+            continue;
+          }
+          id
+        }
+        analysis::UseDef::StdBasis(def) => *std_basis.entry(def).or_insert_with(|| {
+          let id = index.symbols.len();
+          let prefix = format!("{SCIP_PREFIX}std/");
+          let mut scip = format!("{prefix}{}.", descriptor_name(u.name.as_str()));
+          if cx.seen.contains(&scip) {
+            // different definitions with the same name, e.g. `List.map` and `Option.map`.
+            let name = format!("{}@{id}", u.name);
+            scip = format!("{prefix}{}.", descriptor_name(name.as_str()));
+          }
+          cx.seen.insert(scip.clone());
+          let hover = cx.cx.analysis.get_md(path.wrap(u.range.start), false);
+          index.symbols.push(Symbol {
+            scip,
+            name: u.name.clone(),
+            kind: sml_namespace::SymbolKind::Value,
+            def: None,
+            hover: hover.map(|(md, _)| md),
+            parent: None,
+            children: Vec::new(),
+          });
+          id
+        }),
+      };
+      let occ = Occurrence { range: u.range, symbol: id, definition: false };
+      index.docs[doc].occurrences.push(occ);
+    }
+  }
+}
+
+/// Returns the name as a SCIP descriptor name, escaping it with backticks if needed.
+fn descriptor_name(s: &str) -> String {
+  if !s.is_empty()
+    && s.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-' | '$'))
+  {
+    s.to_owned()
+  } else {
+    format!("`{}`", s.replace('`', "``"))
+  }
+}
+
+fn scip(cx: &report::Cx<'_>, index: &Index) -> Vec<u8> {
+  let metadata = scip::Metadata {
+    tool_name: "millet".to_owned(),
+    tool_version: env!("CARGO_PKG_VERSION").to_owned(),
+    project_root: format!("{}/", report::file_uri(cx.root)),
+    text_document_encoding: scip::TEXT_ENCODING_UTF8,
+  };
+  let documents: Vec<_> = index
+    .docs
+    .iter()
+    .enumerate()
+    .map(|(doc_idx, doc)| {
+      let occurrences: Vec<_> = doc
+        .occurrences
+        .iter()
+        .map(|occ| {
+          let sym = &index.symbols[occ.symbol];
+          let (symbol_roles, enclosing_range) = match sym.def {
+            Some((_, range)) if occ.definition => (scip::ROLE_DEFINITION, scip_range(range)),
+            _ => (0, Vec::new()),
+          };
+          scip::Occurrence {
+            range: scip_range(occ.range),
+            symbol: sym.scip.clone(),
+            symbol_roles,
+            enclosing_range,
+          }
+        })
+        .collect();
+      let symbols: Vec<_> = index
+        .symbols
+        .iter()
+        .filter(|sym| sym.def.map_or(false, |(doc, _)| doc == doc_idx))
+        .map(|sym| scip_symbol(index, sym))
+        .collect();
+      scip::Document {
+        relative_path: doc.name.clone(),
+        occurrences,
+        symbols,
+        language: "sml".to_owned(),
+        position_encoding: scip::POSITION_ENCODING_UTF16,
+      }
+    })
+    .collect();
+  let external_symbols: Vec<_> = index
+    .symbols
+    .iter()
+    .filter(|sym| sym.def.is_none())
+    .map(|sym| scip_symbol(index, sym))
+    .collect();
+  scip::encode(&scip::Index { metadata, documents, external_symbols })
+}
+
+fn scip_symbol(index: &Index, sym: &Symbol) -> scip::SymbolInformation {
+  scip::SymbolInformation {
+    symbol: sym.scip.clone(),
+    documentation: sym.hover.iter().cloned().collect(),
+    display_name: sym.name.clone(),
+    enclosing_symbol: sym.parent.map(|p| index.symbols[p].scip.clone()).unwrap_or_default(),
+  }
+}
+
+/// A SCIP range: the start line, start column, end line (if different from the start line), and end
+/// column.
+fn scip_range(r: text_pos::RangeUtf16) -> Vec<u32> {
+  if r.start.line == r.end.line {
+    vec![r.start.line, r.start.col, r.end.col]
+  } else {
+    vec![r.start.line, r.start.col, r.end.line, r.end.col]
+  }
+}
+
+fn lsif(cx: &report::Cx<'_>, index: &Index) -> String {
+  let mut w = Lsif::default();
+  let root_uri = format!("{}/", report::file_uri(cx.root));
+  w.vertex(
+    "metaData",
+    json!({
+      "version": "0.5.0",
+      "projectRoot": root_uri,
+      "positionEncoding": "utf-16",
+      "toolInfo": { "name": "millet", "version": env!("CARGO_PKG_VERSION") },
+    }),
+  );
+  let project = w.vertex("project", json!({ "kind": "sml" }));
+  let doc_ids: Vec<_> = index
+    .docs
+    .iter()
+    .map(|doc| {
+      let uri = report::file_uri(cx.store.get_path(doc.path).as_path());
+      w.vertex("document", json!({ "uri": uri, "languageId": "sml" }))
+    })
+    .collect();
+  if !doc_ids.is_empty() {
+    w.edge("contains", json!({ "outV": project, "inVs": doc_ids }));
+  }
+  let result_sets: Vec<_> =
+    index.symbols.iter().map(|_| w.vertex("resultSet", json!({}))).collect();
+  // 0 if there is no definition, i.e. the symbol is from the std basis.
+  let mut definition_ranges = vec![0u64; index.symbols.len()];
+  let mut reference_ranges = vec![BTreeMap::<usize, Vec<u64>>::new(); index.symbols.len()];
+  for (doc_idx, doc) in index.docs.iter().enumerate() {
+    let mut ranges = Vec::<u64>::with_capacity(doc.occurrences.len());
+    for occ in &doc.occurrences {
+      let sym = &index.symbols[occ.symbol];
+      let mut range = lsif_range(occ.range);
+      if let (true, Some((_, full_range))) = (occ.definition, sym.def) {
+        range["tag"] = json!({
+          "type": "definition",
+          "text": sym.name,
+          "kind": lsp_symbol_kind(sym.kind),
+          "fullRange": lsif_range(full_range),
+        });
+      }
+      let id = w.vertex("range", range);
+      w.edge("next", json!({ "outV": id, "inV": result_sets[occ.symbol] }));
+      if occ.definition {
+        definition_ranges[occ.symbol] = id;
+      } else {
+        reference_ranges[occ.symbol].entry(doc_idx).or_default().push(id);
+      }
+      ranges.push(id);
+    }
+    if !ranges.is_empty() {
+      w.edge("contains", json!({ "outV": doc_ids[doc_idx], "inVs": ranges }));
+    }
+  }
+  for (id, sym) in index.symbols.iter().enumerate() {
+    let result_set = result_sets[id];
+    if let Some(hover) = &sym.hover {
+      let contents = json!({ "kind": "markdown", "value": hover });
+      let hover = w.vertex("hoverResult", json!({ "result": { "contents": contents } }));
+      w.edge("textDocument/hover", json!({ "outV": result_set, "inV": hover }));
+    }
+    let references = w.vertex("referenceResult", json!({}));
+    w.edge("textDocument/references", json!({ "outV": result_set, "inV": references }));
+    if let Some((doc_idx, _)) = sym.def {
+      let (def, document) = (definition_ranges[id], doc_ids[doc_idx]);
+      let definitions = w.vertex("definitionResult", json!({}));
+      w.edge("textDocument/definition", json!({ "outV": result_set, "inV": definitions }));
+      w.edge("item", json!({ "outV": definitions, "inVs": [def], "document": document }));
+      w.edge(
+        "item",
+        json!({
+          "outV": references,
+          "inVs": [def],
+          "document": document,
+          "property": "definitions",
+        }),
+      );
+    }
+    for (&doc_idx, ranges) in &reference_ranges[id] {
+      w.edge(
+        "item",
+        json!({
+          "outV": references,
+          "inVs": ranges,
+          "document": doc_ids[doc_idx],
+          "property": "references",
+        }),
+      );
+    }
+  }
+  for (doc_idx, doc) in index.docs.iter().enumerate() {
+    let result: Vec<_> =
+      doc.symbols.iter().map(|&id| lsif_document_symbol(index, &definition_ranges, id)).collect();
+    let symbols = w.vertex("documentSymbolResult", json!({ "result": result }));
+    w.edge("textDocument/documentSymbol", json!({ "outV": doc_ids[doc_idx], "inV": symbols }));
+  }
+  w.out
+}
+
+/// The LSIF output being written, one JSON object per line.
+#[derive(Debug, Default)]
+struct Lsif {
+  next_id: u64,
+  out: String,
+}
+
+impl Lsif {
+  fn vertex(&mut self, label: &str, rest: Value) -> u64 {
+    self.element("vertex", label, rest)
+  }
+
+  fn edge(&mut self, label: &str, rest: Value) -> u64 {
+    self.element("edge", label, rest)
+  }
+
+  fn element(&mut self, type_: &str, label: &str, mut rest: Value) -> u64 {
+    self.next_id += 1;
+    let id = self.next_id;
+    rest["id"] = json!(id);
+    rest["type"] = json!(type_);
+    rest["label"] = json!(label);
+    self.out.push_str(serde_json::to_string(&rest).expect("should serialize").as_str());
+    self.out.push('\n');
+    id
+  }
+}
+
+fn lsif_range(r: text_pos::RangeUtf16) -> Value {
+  json!({
+    "start": { "line": r.start.line, "character": r.start.col },
+    "end": { "line": r.end.line, "character": r.end.col },
+  })
+}
+
+fn lsif_document_symbol(index: &Index, definition_ranges: &[u64], id: usize) -> Value {
+  let children: Vec<_> = index.symbols[id]
+    .children
+    .iter()
+    .map(|&child| lsif_document_symbol(index, definition_ranges, child))
+    .collect();
+  json!({ "id": definition_ranges[id], "children": children })
+}
+
+/// The LSP symbol kind number, like the language server uses.
+fn lsp_symbol_kind(kind: sml_namespace::SymbolKind) -> u32 {
+  match kind {
+    sml_namespace::SymbolKind::Signature => 11,
+    sml_namespace::SymbolKind::Structure => 2,
+    sml_namespace::SymbolKind::Functor | sml_namespace::SymbolKind::Function => 12,
+    sml_namespace::SymbolKind::Value => 13,
+    sml_namespace::SymbolKind::Type => 5,
+    sml_namespace::SymbolKind::Constructor => 9,
+    sml_namespace::SymbolKind::Exception => 24,
+  }
+}
@@ -2,6 +2,19 @@
 
 #![deny(clippy::pedantic, missing_debug_implementations, missing_docs, rust_2018_idioms)]
 
+mod baseline;
+mod diff;
+mod doc;
+mod filter;
+mod fix;
+mod format;
+mod graph;
+mod index;
+mod query;
+mod report;
+mod search_type;
+mod watch;
+
 use paths::FileSystem as _;
 
 fn usage() {
@@ -11,10 +24,94 @@ fn usage() {
     .unwrap_or_else(|| "<unknown>".to_owned());
   println!("usage:");
   println!("  {current_exe_name} [options] <path>");
+  println!("  {current_exe_name} search-type <type> [<path>]");
+  println!("  {current_exe_name} format [--engine <engine>] [--check] <path>");
+  println!("  {current_exe_name} format [--engine <engine>] --stdin");
+  println!("  {current_exe_name} doc --out <dir> [<path>]");
+  println!("  {current_exe_name} graph [--format <format>] [--groups] [<path>]");
+  println!("  {current_exe_name} index [--format <format>] [--out <file>] [<path>]");
+  println!("  {current_exe_name} query <query> <file>:<line>:<col> [<path>]");
+  println!("  {current_exe_name} query symbols <file> [<path>]");
   let rest_of_usage = r#"
+commands:
+  search-type <type> [<path>]
+    search for values whose type matches <type>, like "'a list -> int", in the
+    std basis and the project at <path>, if given. the order of parameters and
+    the names of type variables do not matter.
+  format [--engine <engine>] [--check] <path>
+    WARNING: THE FORMATTERS ARE HIGHLY EXPERIMENTAL.
+    format all of the SML files in the project at <path>. <engine> is one of:
+    - naive (the default): Millet's own formatter
+    - smlfmt: run the external `smlfmt` program
+    with --check, instead of changing files, print a unified diff for each file
+    that would change, and exit with failure if any would change
+  format [--engine <engine>] --stdin
+    format SML from stdin, and print the result to stdout
+  doc --out <dir> [<path>]
+    write API documentation for the signatures, structures, and functors in
+    the project at <path>, or the current directory if not given, to <dir>,
+    as both Markdown and HTML. this includes their specs, types, and doc
+    comments, and links to the other definitions they use
+  graph [--format <format>] [--groups] [<path>]
+    print the dependency graph of the project at <path>, or the current
+    directory if not given. groups contain source files and include other
+    groups, and source files use definitions, like structures, from other
+    source files. <format> is one of:
+    - dot (the default): a Graphviz digraph
+    - json: a JSON object with "nodes" and "edges"
+    with --groups, show only the groups, with a group using another group if
+    any of its source files use any of the other's
+  index [--format <format>] [--out <file>] [<path>]
+    write an index of the definitions, references, hover information, and
+    symbols in the project at <path>, or the current directory if not given,
+    for code navigation services. <format> is one of:
+    - scip (the default): SCIP, written to index.scip by default
+    - lsif: LSIF, written to dump.lsif by default
+  query <query> <file>:<line>:<col> [<path>]
+    print the answer to a query about the position in <file>, which is in the
+    project at <path>, or the current directory if not given, as JSON. the
+    line and column are 1-based. <query> is one of:
+    - type-at: the type of the expression or pattern
+    - hover: the hover information, as Markdown
+    - definition: the locations of the definitions
+    - references: the locations of all references to the definition
+  query symbols <file> [<path>]
+    print the symbols, like structures and values, in <file> as JSON
+
 options:
   -h, --help
     show this help
+  --format <format>
+    how to show diagnostics. one of:
+    - human (the default): rich output to stderr
+    - short: one line per diagnostic to stdout, like
+      `file:line:col: error[5006]: message`
+    - json: a JSON array of diagnostics to stdout
+    - sarif: a SARIF log to stdout
+  --deny warnings
+    exit with failure if there are any warnings, not just errors
+  --min-severity <severity>
+    only show diagnostics at least this severe. one of: warning (the default), error
+  --only <codes>
+    only show diagnostics with these comma-separated codes, like 5006,5011
+  --exclude-code <codes>
+    never show diagnostics with these comma-separated codes
+  --max-diagnostics <n>
+    show at most <n> diagnostics. the exit code still accounts for all of them
+  --baseline <file>
+    do not report the known diagnostics in the baseline <file>, instead of the
+    one from the config, if any
+  --write-baseline <file>
+    write all current diagnostics in source files to the baseline <file>, and
+    do not report them
+  --fix
+    apply every quick fix that needs no choices, like those for lints, to the
+    files on disk. then report the remaining diagnostics
+  --dry-run
+    with --fix, print a unified diff of the fixes instead of applying them
+  --watch
+    keep running, and report the diagnostics again whenever an input file
+    changes. cannot be used with --fix, --write-baseline, or --unsafe-format
   --unsafe-format
     WARNING: THE FORMATTER IS HIGHLY EXPERIMENTAL.
     IT MAY IRREVOCABLY DESTROY SOME OR ALL OF YOUR CODE.
@@ -25,78 +122,207 @@ arguments:
     path of the project to analyze. the path is a directory containing either:
     - a single .cm or .mlb file
     - a millet.toml config file
+
+exit codes:
+  0: there were no errors (and no warnings, with --deny warnings)
+  1: there were errors (or warnings, with --deny warnings)
+  2: could not run, e.g. because of invalid arguments
+  3: could not finish, e.g. because search-type could not analyze the project
+
+  diagnostics hidden by --min-severity, --only, or --exclude-code do not affect
+  the exit code.
 "#;
   print!("{rest_of_usage}");
 }
 
-fn run() -> usize {
+/// There were no failing diagnostics.
+const EXIT_OK: i32 = 0;
+/// There were failing diagnostics.
+const EXIT_FAILED: i32 = 1;
+/// We could not run, e.g. because of invalid arguments.
+const EXIT_USAGE: i32 = 2;
+/// We started running, but then could not finish, e.g. because the project could not be analyzed.
+const EXIT_INTERNAL: i32 = 3;
+
+/// Returns the exit code.
+fn run() -> i32 {
   match env_logger::try_init_from_env(env_logger::Env::default().default_filter_or("error")) {
     Ok(()) => {}
     Err(e) => {
       println!("could not start env logger: {e}");
-      return 1;
+      return EXIT_USAGE;
     }
   }
   let mut args = pico_args::Arguments::from_env();
   if args.contains(["-h", "--help"]) {
     usage();
-    return 0;
+    return EXIT_OK;
+  }
+  let subcommand = match args.subcommand() {
+    Ok(x) => x,
+    Err(e) => {
+      show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  match subcommand.as_deref() {
+    Some("search-type") => return search_type::run(args),
+    Some("doc") => return doc::run(args),
+    Some("format") => return format::run(args),
+    Some("graph") => return graph::run(args),
+    Some("index") => return index::run(args),
+    Some("query") => return query::run(args),
+    _ => {}
   }
-  let format = args.contains("--unsafe-format");
-  let root: std::path::PathBuf = match args.free_from_str() {
+  let opts = match Opts::from_args(&mut args) {
     Ok(x) => x,
     Err(e) => {
-      println!("error[{}]: {}", diagnostic::Code::n(1019), e);
-      return 1;
+      show_args_error(&e);
+      return EXIT_USAGE;
     }
   };
+  // if there was no subcommand, the first free argument was the root.
+  let root = match subcommand {
+    Some(x) => Ok(std::path::PathBuf::from(x)),
+    None => args.free_from_str(),
+  };
+  let root: std::path::PathBuf = match root {
+    Ok(x) => x,
+    Err(e) => {
+      show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  if opts.watch {
+    if opts.fix || opts.write_baseline.is_some() || opts.format {
+      println!("error: --watch cannot be used with --fix, --write-baseline, or --unsafe-format");
+      return EXIT_USAGE;
+    }
+    return watch::run(root, &opts);
+  }
+  let summary = match check(root, &opts) {
+    Some(x) => x,
+    None => return EXIT_USAGE,
+  };
+  if opts.report_format.is_human() {
+    summary.show();
+  }
+  if summary.failed == 0 {
+    EXIT_OK
+  } else {
+    EXIT_FAILED
+  }
+}
+
+/// Options for checking.
+#[derive(Debug)]
+struct Opts {
+  /// Whether to format the files.
+  format: bool,
+  report_format: report::Format,
+  filter: filter::Filter,
+  /// A baseline file to use instead of the one from the config.
+  baseline: Option<std::path::PathBuf>,
+  /// Where to write a baseline file.
+  write_baseline: Option<std::path::PathBuf>,
+  /// Whether to apply quick fixes.
+  fix: bool,
+  /// Whether to only show what would change, instead of changing files.
+  dry_run: bool,
+  /// Whether to keep checking when files change.
+  watch: bool,
+}
+
+impl Opts {
+  fn analysis_options(&self) -> analysis::Options {
+    analysis::Options {
+      lines: config::DiagnosticLines::One,
+      ignore: config::init::DiagnosticsIgnore::AfterSyntax,
+      format: if self.format {
+        config::init::FormatEngine::Naive
+      } else {
+        config::init::FormatEngine::None
+      },
+    }
+  }
+
+  fn from_args(args: &mut pico_args::Arguments) -> Result<Self, pico_args::Error> {
+    Ok(Opts {
+      format: args.contains("--unsafe-format"),
+      report_format: args.opt_value_from_str("--format")?.unwrap_or_default(),
+      filter: filter::Filter::from_args(args)?,
+      baseline: args.opt_value_from_str("--baseline")?,
+      write_baseline: args.opt_value_from_str("--write-baseline")?,
+      fix: args.contains("--fix"),
+      dry_run: args.contains("--dry-run"),
+      watch: args.contains("--watch"),
+    })
+  }
+}
+
+/// Counts of the reported diagnostics.
+#[derive(Debug, Default)]
+struct Summary {
+  errors: usize,
+  warnings: usize,
+  /// How many made the run fail.
+  failed: usize,
+  /// How many were not shown because of `--max-diagnostics`.
+  not_shown: usize,
+}
+
+impl Summary {
+  fn show(&self) {
+    if self.not_shown != 0 {
+      println!("{} more not shown", self.not_shown);
+    }
+    if self.errors == 0 && self.warnings == 0 {
+      println!("no errors!");
+      return;
+    }
+    let mut parts = Vec::<String>::new();
+    for (n, name) in [(self.errors, "error"), (self.warnings, "warning")] {
+      if n != 0 {
+        let suffix = if n == 1 { "" } else { "s" };
+        parts.push(format!("{n} {name}{suffix}"));
+      }
+    }
+    println!("{}. see {} for more information", parts.join(", "), analysis::URL);
+  }
+}
+
+/// Returns a summary of the diagnostics, or `None` if we could not analyze at all.
+fn check(root: std::path::PathBuf, opts: &Opts) -> Option<Summary> {
   let fs = paths::RealFileSystem::default();
   let root = match fs.canonicalize(root.as_path()) {
     Ok(x) => x,
     Err(e) => {
       show_input_error(root.as_path(), &input::Error::from_io(root.clone(), e));
-      return 1;
+      return None;
     }
   };
   let mut store = paths::Store::new();
-  let inp = input::Input::new(&fs, &mut store, &root);
-  let options = analysis::Options {
-    lines: config::DiagnosticLines::One,
-    ignore: config::init::DiagnosticsIgnore::AfterSyntax,
-    format: if format {
-      config::init::FormatEngine::Naive
-    } else {
-      config::init::FormatEngine::None
-    },
-  };
-  let mut an = analysis::Analysis::new(analysis::StdBasis::full(), options);
+  let mut inp = input::Input::new(&fs, &mut store, &root);
+  if opts.write_baseline.is_some() {
+    // we want all the diagnostics, to write them.
+    inp.baseline = input::Baseline::default();
+  } else if let Some(path) = &opts.baseline {
+    inp.read_baseline(&fs, &mut store, &root, path);
+  }
+  let mut an = analysis::Analysis::new(analysis::StdBasis::full(), opts.analysis_options());
+  let mut write_errors = Vec::<input::Error>::new();
+  if opts.fix {
+    fix(&mut an, &mut inp, &store, root.as_path(), opts, &mut write_errors);
+  }
   let got = an.get_many_text_range(&inp);
-  for err in &inp.errors {
-    show_input_error(root.as_path(), err);
-  }
-  let mut stderr = codespan_reporting::term::termcolor::StandardStream::stderr(
-    codespan_reporting::term::termcolor::ColorChoice::Auto,
-  );
-  let config = codespan_reporting::term::Config::default();
-  let files = Files { store: &store, input: &inp, analysis: &an };
-  for (&path, ds) in &got {
-    for d in ds {
-      let d = mk_diagnostic(path, d);
-      codespan_reporting::term::emit(&mut stderr, &config, &files, &d).unwrap();
-    }
-  }
-  let mut format_errors = 0usize;
-  if format {
+  let mut items = items(&inp, got);
+  if opts.format {
     for &id in inp.sources.keys() {
       let path = store.get_path(id);
       match an.format(id, 2) {
         Ok((contents, _)) => match std::fs::write(path.as_path(), contents.as_str()) {
           Ok(()) => {}
-          Err(e) => {
-            let e = input::Error::from_io(path.as_path().to_owned(), e);
-            show_input_error(path.as_path(), &e);
-            format_errors += 1;
-          }
+          Err(e) => write_errors.push(input::Error::from_io(path.as_path().to_owned(), e)),
         },
         Err(e) => match e {
           analysis::FormatError::NaiveFmt(e) => match e {
@@ -105,9 +331,7 @@ fn run() -> usize {
             sml_naive_fmt::Error::Comments(ranges) => {
               for range in ranges {
                 let d = analysis::Diagnostic::naive_fmt_comment(range);
-                let d = mk_diagnostic(id, &d);
-                codespan_reporting::term::emit(&mut stderr, &config, &files, &d).unwrap();
-                format_errors += 1;
+                items.push(report::Item::Analysis(id, d));
               }
             }
           },
@@ -122,103 +346,101 @@ fn run() -> usize {
       }
     }
   }
-  format_errors + inp.errors.len() + got.values().map(Vec::len).sum::<usize>()
+  items.retain(|item| opts.filter.keep(item));
+  let cx = report::Cx { root: root.as_path(), store: &store, input: &inp, analysis: &an };
+  if let Some(path) = &opts.write_baseline {
+    if let Err(e) = baseline::write(&cx, path, &mut items) {
+      write_errors.push(input::Error::from_io(path.clone(), e));
+    }
+  }
+  items.extend(write_errors.iter().map(report::Item::Input));
+  Some(finish(&cx, opts, items))
 }
 
-fn show_input_error(root: &std::path::Path, e: &input::Error) {
-  print!("{}", e.maybe_rel_path(root).display());
-  if let Some(r) = e.range() {
-    print!(":{}", r.start);
+/// Returns the items to report for the input and the diagnostics from analysis.
+fn items(
+  inp: &input::Input,
+  got: paths::PathMap<Vec<analysis::Diagnostic<text_size_util::TextRange>>>,
+) -> Vec<report::Item<'_>> {
+  let mut ret: Vec<_> = inp.errors.iter().map(report::Item::Input).collect();
+  for (path, ds) in got {
+    ret.extend(ds.into_iter().map(|d| report::Item::Analysis(path, d)));
   }
-  let code = e.code();
-  println!(": error[{code}]: {}", e.display(root));
+  ret
 }
 
-fn mk_diagnostic<R>(
-  path: paths::PathId,
-  d: &analysis::Diagnostic<R>,
-) -> codespan_reporting::diagnostic::Diagnostic<paths::PathId>
-where
-  R: Copy + Into<std::ops::Range<usize>>,
-{
-  let sev = match d.severity {
-    diagnostic::Severity::Warning => codespan_reporting::diagnostic::Severity::Warning,
-    diagnostic::Severity::Error => codespan_reporting::diagnostic::Severity::Error,
-  };
-  let lab = codespan_reporting::diagnostic::Label::new(
-    codespan_reporting::diagnostic::LabelStyle::Primary,
-    path,
-    d.range,
-  );
-  codespan_reporting::diagnostic::Diagnostic::new(sev)
-    .with_code(d.code.to_string())
-    .with_message(d.message.clone())
-    .with_labels(vec![lab])
+/// Reports the items, which should already be filtered, and returns a summary of them.
+fn finish(cx: &report::Cx<'_>, opts: &Opts, mut items: Vec<report::Item<'_>>) -> Summary {
+  let mut summary = Summary::default();
+  for item in &items {
+    match item.severity() {
+      diagnostic::Severity::Error => summary.errors += 1,
+      diagnostic::Severity::Warning => summary.warnings += 1,
+    }
+    if opts.filter.fails(item) {
+      summary.failed += 1;
+    }
+  }
+  if let Some(max) = opts.filter.max {
+    summary.not_shown = items.len().saturating_sub(max);
+    items.truncate(max);
+  }
+  report::emit(cx, opts.report_format, &items);
+  summary
 }
 
-fn main() {
-  panic_hook::install();
-  match run() {
-    0 => println!("no errors!"),
-    n => {
-      let suffix = if n == 1 { "" } else { "s" };
-      println!("{n} error{suffix}. see {} for more information", analysis::URL);
-      std::process::exit(1)
+/// Applies quick fixes to the files, or with `--dry-run`, shows a diff of what would change.
+fn fix(
+  an: &mut analysis::Analysis,
+  inp: &mut input::Input,
+  store: &paths::Store,
+  root: &std::path::Path,
+  opts: &Opts,
+  write_errors: &mut Vec<input::Error>,
+) {
+  let mut original: Vec<_> = fix::apply(an, inp).into_iter().collect();
+  original.sort_unstable_by_key(|&(path, _)| store.get_path(path).as_path());
+  let mut fixed = 0usize;
+  for (id, old) in original {
+    let path = store.get_path(id).as_path();
+    let new = inp.sources.get(&id).expect("fixed path should be a source");
+    if opts.dry_run {
+      let rel = path.strip_prefix(root).unwrap_or(path);
+      if let Some(diff) = diff::unified(&report::uri_path(rel), &old, new) {
+        print!("{diff}");
+      }
+      // report the diagnostics for the files as they are on disk.
+      inp.sources.insert(id, old);
+      continue;
+    }
+    match std::fs::write(path, new.as_str()) {
+      Ok(()) => fixed += 1,
+      Err(e) => write_errors.push(input::Error::from_io(path.to_owned(), e)),
     }
   }
+  if opts.report_format.is_human() && !opts.dry_run {
+    let suffix = if fixed == 1 { "" } else { "s" };
+    println!("fixed {fixed} file{suffix}");
+  }
 }
 
-struct Files<'a> {
-  store: &'a paths::Store,
-  input: &'a input::Input,
-  analysis: &'a analysis::Analysis,
+fn show_args_error(e: &pico_args::Error) {
+  println!("error[{}]: {}", diagnostic::Code::n(1019), e);
 }
 
-impl<'a> codespan_reporting::files::Files<'a> for Files<'a> {
-  type FileId = paths::PathId;
-
-  type Name = std::path::Display<'a>;
-
-  type Source = &'a str;
-
-  fn name(&'a self, id: Self::FileId) -> Result<Self::Name, codespan_reporting::files::Error> {
-    Ok(self.store.get_path(id).as_path().display())
-  }
-
-  fn source(&'a self, id: Self::FileId) -> Result<Self::Source, codespan_reporting::files::Error> {
-    match self.input.sources.get(&id) {
-      Some(x) => Ok(x.as_str()),
-      None => Err(codespan_reporting::files::Error::FileMissing),
-    }
-  }
-
-  fn line_index(
-    &'a self,
-    id: Self::FileId,
-    byte_index: usize,
-  ) -> Result<usize, codespan_reporting::files::Error> {
-    let db =
-      self.analysis.source_pos_db(id).ok_or(codespan_reporting::files::Error::FileMissing)?;
-    let ts = text_size_util::TextSize::try_from(byte_index).unwrap();
-    let pos = db.position_utf16(ts).unwrap_or(db.end_position_utf16());
-    Ok(pos.line.try_into().unwrap())
+fn show_input_error(root: &std::path::Path, e: &input::Error) {
+  print!("{}", e.maybe_rel_path(root).display());
+  if let Some(r) = e.range() {
+    print!(":{}", r.start);
   }
+  let code = e.code();
+  println!(": error[{code}]: {}", e.display(root));
+}
 
-  fn line_range(
-    &'a self,
-    id: Self::FileId,
-    line_index: usize,
-  ) -> Result<std::ops::Range<usize>, codespan_reporting::files::Error> {
-    let db =
-      self.analysis.source_pos_db(id).ok_or(codespan_reporting::files::Error::FileMissing)?;
-    let start = text_pos::PositionUtf16 { line: line_index.try_into().unwrap(), col: 0 };
-    let mut end = text_pos::PositionUtf16 { line: start.line + 1, col: 0 };
-    let file_end = db.end_position_utf16();
-    if end.line > file_end.line || end.line == file_end.line && end.col > file_end.col {
-      end = file_end;
-    }
-    let tr =
-      db.text_range_utf16(text_pos::RangeUtf16 { start, end }).expect("line range out of range");
-    Ok(tr.into())
+fn main() {
+  panic_hook::install();
+  let code = run();
+  if code != EXIT_OK {
+    std::process::exit(code)
   }
 }
@@ -0,0 +1,172 @@
+//! Answering queries about positions and files, like an editor would ask the language server.
+
+use crate::{report, EXIT_OK, EXIT_USAGE};
+use paths::FileSystem as _;
+use serde_json::{json, Value};
+
+/// Returns the exit code.
+pub(crate) fn run(mut args: pico_args::Arguments) -> i32 {
+  let kind: Kind = match args.free_from_str() {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  let target: String = match args.free_from_str() {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  let root: Option<std::path::PathBuf> = match args.opt_free_from_str() {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  let (file, pos) = if matches!(kind, Kind::Symbols) {
+    (target.as_str(), None)
+  } else {
+    match parse_position(target.as_str()) {
+      Some((file, pos)) => (file, Some(pos)),
+      None => {
+        println!("error: expected <file>:<line>:<col>, found `{target}`");
+        return EXIT_USAGE;
+      }
+    }
+  };
+  let fs = paths::RealFileSystem::default();
+  let root = root.unwrap_or_else(|| std::path::PathBuf::from("."));
+  let root = match fs.canonicalize(root.as_path()) {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_input_error(root.as_path(), &input::Error::from_io(root.clone(), e));
+      return EXIT_USAGE;
+    }
+  };
+  let mut store = paths::Store::new();
+  let inp = input::Input::new(&fs, &mut store, &root);
+  for err in &inp.errors {
+    crate::show_input_error(root.as_path(), err);
+  }
+  // relative to the root, like the paths in the output, not the current directory.
+  let path = match fs.canonicalize(root.as_path().join(file).as_path()) {
+    Ok(x) => store.get_id(&x),
+    Err(e) => {
+      println!("error: couldn't find `{file}`: {e}");
+      return EXIT_USAGE;
+    }
+  };
+  if !inp.sources.contains_key(&path) {
+    println!("error: `{file}` is not a source file in the project");
+    return EXIT_USAGE;
+  }
+  let options = analysis::Options {
+    lines: config::DiagnosticLines::One,
+    ignore: config::init::DiagnosticsIgnore::All,
+    format: config::init::FormatEngine::None,
+  };
+  let mut an = analysis::Analysis::new(analysis::StdBasis::full(), options);
+  an.get_many(&inp);
+  let cx = report::Cx { root: root.as_path(), store: &store, input: &inp, analysis: &an };
+  let res = match pos {
+    None => symbols(an.document_symbols(path).unwrap_or_default()),
+    Some(pos) => {
+      let pos = path.wrap(pos);
+      match kind {
+        Kind::TypeAt => match an.get_ty(pos) {
+          Some((ty, range)) => json!({ "type": ty, "range": report::json_range(range) }),
+          None => Value::Null,
+        },
+        Kind::Hover => match an.get_md(pos, false) {
+          Some((md, range)) => json!({ "contents": md, "range": report::json_range(range) }),
+          None => Value::Null,
+        },
+        Kind::Definition => locations(&cx, an.get_defs(pos).unwrap_or_default()),
+        Kind::References => locations(&cx, an.find_all_references(pos).unwrap_or_default()),
+        Kind::Symbols => unreachable!("handled symbols above"),
+      }
+    }
+  };
+  println!("{}", serde_json::to_string_pretty(&res).expect("should serialize"));
+  EXIT_OK
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+  TypeAt,
+  Definition,
+  References,
+  Hover,
+  Symbols,
+}
+
+impl std::str::FromStr for Kind {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "type-at" => Ok(Kind::TypeAt),
+      "definition" => Ok(Kind::Definition),
+      "references" => Ok(Kind::References),
+      "hover" => Ok(Kind::Hover),
+      "symbols" => Ok(Kind::Symbols),
+      _ => Err(format!(
+        "unknown query `{s}`, expected one of: type-at, definition, references, hover, symbols"
+      )),
+    }
+  }
+}
+
+/// Parses `<file>:<line>:<col>`, where the line and column are 1-based.
+fn parse_position(s: &str) -> Option<(&str, text_pos::PositionUtf16)> {
+  let mut iter = s.rsplitn(3, ':');
+  let col = iter.next()?.parse::<u32>().ok()?.checked_sub(1)?;
+  let line = iter.next()?.parse::<u32>().ok()?.checked_sub(1)?;
+  let file = iter.next()?;
+  Some((file, text_pos::PositionUtf16 { line, col }))
+}
+
+fn locations(cx: &report::Cx<'_>, locations: Vec<paths::WithPath<text_pos::RangeUtf16>>) -> Value {
+  let locations: Vec<_> = locations
+    .into_iter()
+    .map(|loc| {
+      let path = report::uri_path(report::rel_path(cx, loc.path));
+      json!({ "path": path, "range": report::json_range(loc.val) })
+    })
+    .collect();
+  Value::Array(locations)
+}
+
+fn symbols(symbols: Vec<analysis::DocumentSymbol>) -> Value {
+  let symbols: Vec<_> = symbols
+    .into_iter()
+    .map(|sym| {
+      json!({
+        "name": sym.name,
+        "kind": symbol_kind_str(sym.kind),
+        "detail": sym.detail,
+        "range": report::json_range(sym.range),
+        "selectionRange": report::json_range(sym.selection_range),
+        "children": symbols(sym.children),
+      })
+    })
+    .collect();
+  Value::Array(symbols)
+}
+
+pub(crate) fn symbol_kind_str(kind: sml_namespace::SymbolKind) -> &'static str {
+  match kind {
+    sml_namespace::SymbolKind::Structure => "structure",
+    sml_namespace::SymbolKind::Signature => "signature",
+    sml_namespace::SymbolKind::Functor => "functor",
+    sml_namespace::SymbolKind::Type => "type",
+    sml_namespace::SymbolKind::Constructor => "constructor",
+    sml_namespace::SymbolKind::Exception => "exception",
+    sml_namespace::SymbolKind::Function => "function",
+    sml_namespace::SymbolKind::Value => "value",
+  }
+}
@@ -0,0 +1,388 @@
+//! Reporting diagnostics in different formats.
+
+use serde_json::{json, Value};
+use text_size_util::TextRange;
+
+/// How to show diagnostics.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) enum Format {
+  /// Rich human-readable output, to stderr.
+  #[default]
+  Human,
+  /// A JSON array of diagnostics, to stdout.
+  Json,
+  /// A SARIF log, to stdout.
+  Sarif,
+  /// One line per diagnostic, like `file:line:col: error[5006]: message`, to stdout.
+  Short,
+}
+
+impl Format {
+  /// Returns whether this is meant to be read by a human, and thus whether to print other messages,
+  /// like summaries, to stdout.
+  pub(crate) fn is_human(self) -> bool {
+    matches!(self, Format::Human)
+  }
+}
+
+impl std::str::FromStr for Format {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "human" => Ok(Format::Human),
+      "json" => Ok(Format::Json),
+      "sarif" => Ok(Format::Sarif),
+      "short" => Ok(Format::Short),
+      _ => Err(format!("unknown format `{s}`, expected one of: human, json, sarif, short")),
+    }
+  }
+}
+
+/// Something to report.
+pub(crate) enum Item<'a> {
+  /// An error with the input.
+  Input(&'a input::Error),
+  /// A diagnostic in a source file.
+  Analysis(paths::PathId, analysis::Diagnostic<TextRange>),
+}
+
+impl Item<'_> {
+  /// Returns the code for this.
+  pub(crate) fn code(&self) -> diagnostic::Code {
+    match self {
+      Item::Input(e) => e.code(),
+      Item::Analysis(_, d) => d.code,
+    }
+  }
+
+  /// Returns the severity for this.
+  pub(crate) fn severity(&self) -> diagnostic::Severity {
+    match self {
+      Item::Input(e) => e.severity(),
+      Item::Analysis(_, d) => d.severity,
+    }
+  }
+}
+
+/// The context for reporting.
+pub(crate) struct Cx<'a> {
+  pub(crate) root: &'a std::path::Path,
+  pub(crate) store: &'a paths::Store,
+  pub(crate) input: &'a input::Input,
+  pub(crate) analysis: &'a analysis::Analysis,
+}
+
+/// Reports the items in the format.
+pub(crate) fn emit(cx: &Cx<'_>, format: Format, items: &[Item<'_>]) {
+  match format {
+    Format::Human => human(cx, items),
+    Format::Short => short(cx, items),
+    Format::Json => {
+      let items: Vec<_> = items.iter().map(|item| json_item(cx, item)).collect();
+      println!("{}", serde_json::to_string_pretty(&items).expect("should serialize"));
+    }
+    Format::Sarif => {
+      println!("{}", serde_json::to_string_pretty(&sarif(cx, items)).expect("should serialize"));
+    }
+  }
+}
+
+fn human(cx: &Cx<'_>, items: &[Item<'_>]) {
+  let mut stderr = codespan_reporting::term::termcolor::StandardStream::stderr(
+    codespan_reporting::term::termcolor::ColorChoice::Auto,
+  );
+  let config = codespan_reporting::term::Config::default();
+  let files = Files { store: cx.store, input: cx.input, analysis: cx.analysis };
+  for item in items {
+    match item {
+      Item::Input(e) => crate::show_input_error(cx.root, e),
+      Item::Analysis(path, d) => {
+        let d = mk_diagnostic(*path, d);
+        codespan_reporting::term::emit(&mut stderr, &config, &files, &d).unwrap();
+      }
+    }
+  }
+}
+
+fn short(cx: &Cx<'_>, items: &[Item<'_>]) {
+  for item in items {
+    match item {
+      Item::Input(e) => {
+        print!("{}", e.maybe_rel_path(cx.root).display());
+        if let Some(r) = e.range() {
+          print!(":{}:{}", r.start.line + 1, r.start.col + 1);
+        }
+        println!(": error[{}]: {}", e.code(), e.display(cx.root));
+      }
+      Item::Analysis(path, d) => {
+        let sev = severity_str(d.severity);
+        print!("{}", rel_path(cx, *path).display());
+        if let Some(r) = range_utf16(cx, *path, d.range) {
+          print!(":{}:{}", r.start.line + 1, r.start.col + 1);
+        }
+        println!(": {sev}[{}]: {}", d.code, d.message);
+        for related in &d.related {
+          print!("{}", rel_path(cx, related.range.path).display());
+          if let Some(r) = range_utf16(cx, related.range.path, related.range.val) {
+            print!(":{}:{}", r.start.line + 1, r.start.col + 1);
+          }
+          println!(": note: {}", related.message);
+        }
+      }
+    }
+  }
+}
+
+fn json_item(cx: &Cx<'_>, item: &Item<'_>) -> Value {
+  match item {
+    Item::Input(e) => json!({
+      "path": e.maybe_rel_path(cx.root).display().to_string(),
+      "range": e.range().map(json_range),
+      "code": e.code().to_string(),
+      "severity": severity_str(e.severity()),
+      "message": e.display(cx.root).to_string(),
+      "related": [],
+    }),
+    Item::Analysis(path, d) => {
+      let related: Vec<_> = d
+        .related
+        .iter()
+        .map(|related| {
+          json!({
+            "path": rel_path(cx, related.range.path).display().to_string(),
+            "range": range_utf16(cx, related.range.path, related.range.val).map(json_range),
+            "message": related.message,
+          })
+        })
+        .collect();
+      json!({
+        "path": rel_path(cx, *path).display().to_string(),
+        "range": range_utf16(cx, *path, d.range).map(json_range),
+        "code": d.code.to_string(),
+        "severity": severity_str(d.severity),
+        "message": d.message,
+        "related": related,
+      })
+    }
+  }
+}
+
+/// Lines and columns are 1-based, and columns count UTF-16 code units.
+pub(crate) fn json_range(r: text_pos::RangeUtf16) -> Value {
+  json!({
+    "start": { "line": r.start.line + 1, "col": r.start.col + 1 },
+    "end": { "line": r.end.line + 1, "col": r.end.col + 1 },
+  })
+}
+
+const SRCROOT: &str = "%SRCROOT%";
+
+fn sarif(cx: &Cx<'_>, items: &[Item<'_>]) -> Value {
+  let mut codes: Vec<diagnostic::Code> = items
+    .iter()
+    .map(|item| match item {
+      Item::Input(e) => e.code(),
+      Item::Analysis(_, d) => d.code,
+    })
+    .collect();
+  codes.sort_unstable_by_key(ToString::to_string);
+  codes.dedup();
+  let rules: Vec<_> = codes
+    .iter()
+    .map(
+      |code| json!({ "id": code.to_string(), "helpUri": format!("{}/{code}.md", analysis::URL) }),
+    )
+    .collect();
+  let results: Vec<_> = items
+    .iter()
+    .map(|item| match item {
+      Item::Input(e) => json!({
+        "ruleId": e.code().to_string(),
+        "level": severity_str(e.severity()),
+        "message": { "text": e.display(cx.root).to_string() },
+        "locations": [sarif_location(e.abs_path(), e.range(), cx)],
+      }),
+      Item::Analysis(path, d) => {
+        let related: Vec<_> = d
+          .related
+          .iter()
+          .enumerate()
+          .map(|(idx, related)| {
+            let p = related.range.path;
+            let r = range_utf16(cx, p, related.range.val);
+            let mut loc = sarif_location(cx.store.get_path(p).as_path(), r, cx);
+            loc["id"] = json!(idx);
+            loc["message"] = json!({ "text": related.message });
+            loc
+          })
+          .collect();
+        let r = range_utf16(cx, *path, d.range);
+        json!({
+          "ruleId": d.code.to_string(),
+          "level": severity_str(d.severity),
+          "message": { "text": d.message },
+          "locations": [sarif_location(cx.store.get_path(*path).as_path(), r, cx)],
+          "relatedLocations": related,
+        })
+      }
+    })
+    .collect();
+  json!({
+    "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+    "version": "2.1.0",
+    "runs": [{
+      "tool": {
+        "driver": {
+          "name": "millet",
+          "informationUri": "https://github.com/azdavis/millet",
+          "version": env!("CARGO_PKG_VERSION"),
+          "rules": rules,
+        },
+      },
+      "columnKind": "utf16CodeUnits",
+      "originalUriBaseIds": {
+        SRCROOT: { "uri": format!("{}/", file_uri(cx.root)) },
+      },
+      "results": results,
+    }],
+  })
+}
+
+/// Lines and columns are 1-based, and columns count UTF-16 code units, as the run's `columnKind`
+/// says.
+fn sarif_location(
+  path: &std::path::Path,
+  range: Option<text_pos::RangeUtf16>,
+  cx: &Cx<'_>,
+) -> Value {
+  let artifact_location = match path.strip_prefix(cx.root) {
+    Ok(rel) => json!({ "uri": uri_path(rel), "uriBaseId": SRCROOT }),
+    Err(_) => json!({ "uri": file_uri(path) }),
+  };
+  let mut physical_location = json!({ "artifactLocation": artifact_location });
+  if let Some(r) = range {
+    physical_location["region"] = json!({
+      "startLine": r.start.line + 1,
+      "startColumn": r.start.col + 1,
+      "endLine": r.end.line + 1,
+      "endColumn": r.end.col + 1,
+    });
+  }
+  json!({ "physicalLocation": physical_location })
+}
+
+pub(crate) fn uri_path(path: &std::path::Path) -> String {
+  let parts: Vec<_> = path.components().map(|x| x.as_os_str().to_string_lossy()).collect();
+  parts.join("/")
+}
+
+pub(crate) fn file_uri(path: &std::path::Path) -> String {
+  let s = uri_path(path);
+  if s.starts_with('/') {
+    format!("file://{s}")
+  } else {
+    format!("file:///{s}")
+  }
+}
+
+fn severity_str(severity: diagnostic::Severity) -> &'static str {
+  match severity {
+    diagnostic::Severity::Warning => "warning",
+    diagnostic::Severity::Error => "error",
+  }
+}
+
+pub(crate) fn rel_path<'a>(cx: &Cx<'a>, path: paths::PathId) -> &'a std::path::Path {
+  let path = cx.store.get_path(path).as_path();
+  path.strip_prefix(cx.root).unwrap_or(path)
+}
+
+fn range_utf16(cx: &Cx<'_>, path: paths::PathId, range: TextRange) -> Option<text_pos::RangeUtf16> {
+  cx.analysis.source_pos_db(path)?.range_utf16(range)
+}
+
+fn mk_diagnostic<R>(
+  path: paths::PathId,
+  d: &analysis::Diagnostic<R>,
+) -> codespan_reporting::diagnostic::Diagnostic<paths::PathId>
+where
+  R: Copy + Into<std::ops::Range<usize>>,
+{
+  let sev = match d.severity {
+    diagnostic::Severity::Warning => codespan_reporting::diagnostic::Severity::Warning,
+    diagnostic::Severity::Error => codespan_reporting::diagnostic::Severity::Error,
+  };
+  let lab = codespan_reporting::diagnostic::Label::new(
+    codespan_reporting::diagnostic::LabelStyle::Primary,
+    path,
+    d.range,
+  );
+  let labels = std::iter::once(lab).chain(d.related.iter().map(|r| {
+    codespan_reporting::diagnostic::Label::new(
+      codespan_reporting::diagnostic::LabelStyle::Secondary,
+      r.range.path,
+      r.range.val,
+    )
+    .with_message(r.message.clone())
+  }));
+  codespan_reporting::diagnostic::Diagnostic::new(sev)
+    .with_code(d.code.to_string())
+    .with_message(d.message.clone())
+    .with_labels(labels.collect())
+}
+
+struct Files<'a> {
+  store: &'a paths::Store,
+  input: &'a input::Input,
+  analysis: &'a analysis::Analysis,
+}
+
+impl<'a> codespan_reporting::files::Files<'a> for Files<'a> {
+  type FileId = paths::PathId;
+
+  type Name = std::path::Display<'a>;
+
+  type Source = &'a str;
+
+  fn name(&'a self, id: Self::FileId) -> Result<Self::Name, codespan_reporting::files::Error> {
+    Ok(self.store.get_path(id).as_path().display())
+  }
+
+  fn source(&'a self, id: Self::FileId) -> Result<Self::Source, codespan_reporting::files::Error> {
+    match self.input.sources.get(&id) {
+      Some(x) => Ok(x.as_str()),
+      None => Err(codespan_reporting::files::Error::FileMissing),
+    }
+  }
+
+  fn line_index(
+    &'a self,
+    id: Self::FileId,
+    byte_index: usize,
+  ) -> Result<usize, codespan_reporting::files::Error> {
+    let db =
+      self.analysis.source_pos_db(id).ok_or(codespan_reporting::files::Error::FileMissing)?;
+    let ts = text_size_util::TextSize::try_from(byte_index).unwrap();
+    let pos = db.position_utf16(ts).unwrap_or(db.end_position_utf16());
+    Ok(pos.line.try_into().unwrap())
+  }
+
+  fn line_range(
+    &'a self,
+    id: Self::FileId,
+    line_index: usize,
+  ) -> Result<std::ops::Range<usize>, codespan_reporting::files::Error> {
+    let db =
+      self.analysis.source_pos_db(id).ok_or(codespan_reporting::files::Error::FileMissing)?;
+    let start = text_pos::PositionUtf16 { line: line_index.try_into().unwrap(), col: 0 };
+    let mut end = text_pos::PositionUtf16 { line: start.line + 1, col: 0 };
+    let file_end = db.end_position_utf16();
+    if end.line > file_end.line || end.line == file_end.line && end.col > file_end.col {
+      end = file_end;
+    }
+    let tr =
+      db.text_range_utf16(text_pos::RangeUtf16 { start, end }).expect("line range out of range");
+    Ok(tr.into())
+  }
+}
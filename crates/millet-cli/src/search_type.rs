@@ -0,0 +1,66 @@
+//! Searching for values by their types.
+
+use crate::{EXIT_INTERNAL, EXIT_OK, EXIT_USAGE};
+use paths::FileSystem as _;
+
+/// Returns the exit code.
+pub(crate) fn run(mut args: pico_args::Arguments) -> i32 {
+  let query: String = match args.free_from_str() {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  let root: Option<std::path::PathBuf> = match args.opt_free_from_str() {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_args_error(&e);
+      return EXIT_USAGE;
+    }
+  };
+  let options = analysis::Options {
+    lines: config::DiagnosticLines::One,
+    ignore: config::init::DiagnosticsIgnore::All,
+    format: config::init::FormatEngine::None,
+  };
+  let mut an = analysis::Analysis::new(analysis::StdBasis::full(), options);
+  let mut store = paths::Store::new();
+  if let Some(root) = root {
+    let fs = paths::RealFileSystem::default();
+    let root = match fs.canonicalize(root.as_path()) {
+      Ok(x) => x,
+      Err(e) => {
+        crate::show_input_error(root.as_path(), &input::Error::from_io(root.clone(), e));
+        return EXIT_INTERNAL;
+      }
+    };
+    let inp = input::Input::new(&fs, &mut store, &root);
+    for err in &inp.errors {
+      crate::show_input_error(root.as_path(), err);
+    }
+    if !inp.errors.is_empty() {
+      return EXIT_INTERNAL;
+    }
+    an.get_many(&inp);
+  }
+  let results = match an.search_by_ty(query.as_str()) {
+    Some(x) => x,
+    None => {
+      println!("error: couldn't parse `{query}` as a type");
+      return EXIT_USAGE;
+    }
+  };
+  for result in &results {
+    print!("{} : {}", result.name, result.ty_scheme);
+    if let Some(def) = &result.def {
+      let path = store.get_path(def.path).as_path();
+      print!(" ({}:{}:{})", path.display(), def.val.start.line + 1, def.val.start.col + 1);
+    }
+    println!();
+  }
+  if results.is_empty() {
+    println!("no results");
+  }
+  EXIT_OK
+}
@@ -0,0 +1,157 @@
+//! Checking again whenever input files change.
+
+use crate::{report, Opts, EXIT_USAGE};
+use paths::FileSystem as _;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// How long to wait between looking for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The files to watch, with whether each is a source, and how each was when we last looked.
+type Stamps = BTreeMap<PathBuf, (Option<paths::PathId>, Option<(SystemTime, u64)>)>;
+
+/// Checks the project at `root`, and then checks it again whenever the input files change. Only
+/// returns if we could not analyze at all.
+pub(crate) fn run(root: PathBuf, opts: &Opts) -> i32 {
+  let fs = paths::RealFileSystem::default();
+  let root = match fs.canonicalize(root.as_path()) {
+    Ok(x) => x,
+    Err(e) => {
+      crate::show_input_error(root.as_path(), &input::Error::from_io(root.clone(), e));
+      return EXIT_USAGE;
+    }
+  };
+  let mut store = paths::Store::new();
+  let mut inp = get_input(&fs, &mut store, &root, opts);
+  let mut an = analysis::Analysis::new(analysis::StdBasis::full(), opts.analysis_options());
+  let got = an.get_many_text_range(&inp);
+  show(&root, &store, &inp, &an, opts, got);
+  let mut stamps = get_stamps(&store, &root, &inp);
+  loop {
+    std::thread::sleep(POLL_INTERVAL);
+    let new_stamps = get_stamps(&store, &root, &inp);
+    if new_stamps == stamps {
+      continue;
+    }
+    let changed: Vec<_> = new_stamps
+      .iter()
+      .filter(|&(path, stamp)| stamps.get(path) != Some(stamp))
+      .map(|(path, &(id, _))| (path.as_path(), id))
+      .collect();
+    let got = match update_sources(&mut an, &mut inp, &changed) {
+      Update::Done => an.diagnostics_text_range(&inp),
+      Update::Analyze => an.get_many_text_range(&inp),
+      Update::GetInput => {
+        inp = get_input(&fs, &mut store, &root, opts);
+        an.get_many_text_range(&inp)
+      }
+    };
+    show(&root, &store, &inp, &an, opts, got);
+    stamps = get_stamps(&store, &root, &inp);
+  }
+}
+
+fn get_input(
+  fs: &paths::RealFileSystem,
+  store: &mut paths::Store,
+  root: &paths::CanonicalPathBuf,
+  opts: &Opts,
+) -> input::Input {
+  let mut ret = input::Input::new(fs, store, root);
+  if let Some(path) = &opts.baseline {
+    ret.read_baseline(fs, store, root, path);
+  }
+  ret
+}
+
+/// What was done to update for changed files, and so what is left to do.
+enum Update {
+  /// The changed sources were updated one at a time.
+  Done,
+  /// The changed sources were updated in the input, but other sources depend on them, so the input
+  /// should be analyzed again.
+  Analyze,
+  /// The input couldn't be updated, e.g. because a group file or the config changed, so it should
+  /// be gotten and analyzed again.
+  GetInput,
+}
+
+/// Updates the changed sources, one at a time if no other sources depend on them.
+fn update_sources(
+  an: &mut analysis::Analysis,
+  inp: &mut input::Input,
+  changed: &[(&std::path::Path, Option<paths::PathId>)],
+) -> Update {
+  let mut new_sources = Vec::<(paths::PathId, String)>::with_capacity(changed.len());
+  for &(path, id) in changed {
+    let id = match id {
+      Some(x) => x,
+      None => return Update::GetInput,
+    };
+    if an.source_pos_db(id).is_none() {
+      return Update::GetInput;
+    }
+    match std::fs::read_to_string(path) {
+      Ok(contents) => new_sources.push((id, contents)),
+      Err(_) => return Update::GetInput,
+    }
+  }
+  // a source that uses a definition from a changed source must be checked again too, against the
+  // new definitions, which updating one at a time does not do.
+  let has_dependents = inp.sources.keys().any(|&other| {
+    an.source_deps(other)
+      .unwrap_or_default()
+      .iter()
+      .any(|dep| *dep != other && new_sources.iter().any(|(id, _)| id == dep))
+  });
+  let ret = if has_dependents { Update::Analyze } else { Update::Done };
+  for (id, contents) in new_sources {
+    inp.sources.insert(id, contents);
+    if !has_dependents {
+      an.update_one(inp, id);
+    }
+  }
+  ret
+}
+
+fn get_stamps(store: &paths::Store, root: &paths::CanonicalPathBuf, inp: &input::Input) -> Stamps {
+  let sources = inp.sources.keys().map(|&id| (store.get_path(id).as_path().to_owned(), Some(id)));
+  let groups = inp.groups.keys().map(|&id| (store.get_path(id).as_path().to_owned(), None));
+  let config = std::iter::once((root.as_path().join(config::file::PATH), None));
+  // either from the config or from the command line.
+  let baseline = inp.baseline.path().map(|path| (path.to_owned(), None));
+  sources
+    .chain(groups)
+    .chain(config)
+    .chain(baseline)
+    .map(|(path, id)| {
+      let stamp = stamp(path.as_path());
+      (path, (id, stamp))
+    })
+    .collect()
+}
+
+fn stamp(path: &std::path::Path) -> Option<(SystemTime, u64)> {
+  let metadata = std::fs::metadata(path).ok()?;
+  Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn show(
+  root: &paths::CanonicalPathBuf,
+  store: &paths::Store,
+  inp: &input::Input,
+  an: &analysis::Analysis,
+  opts: &Opts,
+  got: paths::PathMap<Vec<analysis::Diagnostic<text_size_util::TextRange>>>,
+) {
+  let mut items = crate::items(inp, got);
+  items.retain(|item| opts.filter.keep(item));
+  let cx = report::Cx { root: root.as_path(), store, input: inp, analysis: an };
+  let summary = crate::finish(&cx, opts, items);
+  if opts.report_format.is_human() {
+    summary.show();
+    println!("watching for changes...");
+  }
+}
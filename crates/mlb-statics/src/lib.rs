@@ -10,7 +10,8 @@ use config::lang::Language;
 use diagnostic::{Code, Severity};
 use fast_hash::FxHashMap;
 use sml_file_syntax::SourceFileSyntax;
-use sml_syntax::ast::AstNode as _;
+use sml_statics_types::def;
+use sml_syntax::{ast::AstNode as _, SyntaxKind};
 use std::fmt;
 
 pub use std_basis::StdBasis;
@@ -43,6 +44,24 @@ pub struct SourceFile {
   pub scope: sml_statics::basis::Bs,
 }
 
+impl SourceFile {
+  /// Returns the other source files with definitions this file (at the path) uses, sorted.
+  #[must_use]
+  pub fn deps(&self, path: paths::PathId) -> Vec<paths::PathId> {
+    let mut ret: Vec<_> = self
+      .info
+      .defs()
+      .filter_map(|(_, def)| match def {
+        def::Def::Path(def::Path::Regular(p), _) => (p != path).then_some(p),
+        def::Def::Path(def::Path::BuiltinLib(_), _) | def::Def::Primitive(_) => None,
+      })
+      .collect();
+    ret.sort_unstable();
+    ret.dedup();
+    ret
+  }
+}
+
 /// An error.
 #[derive(Debug)]
 pub struct Error {
@@ -308,20 +327,18 @@ fn get_source_file(
   fix_env: sml_fixity::Env,
   syntax: SourceFileSyntax,
 ) {
-  let mode = sml_statics_types::mode::Mode::Regular(Some(path));
-  let checked =
-    sml_statics::get(st.syms_tys, &scope.bs, mode, &syntax.lower.arenas, &syntax.lower.root);
+  let (checked, deprecated) = get_statics(st.syms_tys, &scope.bs, path, &syntax);
+  let mut bs = checked.info.basis().clone();
+  bs.deprecated = deprecated;
   ac.append(MBasis {
     fix_env: if lang.fixity_across_files { fix_env } else { sml_fixity::Env::default() },
     bas_env: FxHashMap::default(),
-    bs: checked.info.basis().clone(),
+    bs,
   });
-  let mut info = checked.info;
-  add_all_doc_comments(syntax.parse.root.syntax(), &syntax.lower, &mut info);
   let mut file = SourceFile {
     syntax,
     statics_errors: checked.errors,
-    info,
+    info: checked.info,
     fix_env: if lang.fixity_across_files {
       scope.fix_env.clone()
     } else {
@@ -346,6 +363,43 @@ fn get_source_file(
   st.source_files.insert(path, file);
 }
 
+/// Runs statics on the source file with the given scope, and adds the doc comments to the info.
+///
+/// Also returns the items deprecated by doc comments in this file. Uses of them in this file are
+/// reported by statics.
+fn get_statics(
+  syms_tys: &mut sml_statics_types::St,
+  scope: &sml_statics::basis::Bs,
+  path: paths::PathId,
+  syntax: &SourceFileSyntax,
+) -> (sml_statics::Statics, sml_statics::basis::Deprecated) {
+  let root = syntax.parse.root.syntax();
+  let low = &syntax.lower;
+  let docs = doc_comments(root, low);
+  let deprecated: sml_statics::basis::Deprecated = docs
+    .iter()
+    .filter(|&&(idx, _)| is_doc_comment_owner(root, low, idx))
+    .filter_map(|(idx, doc)| {
+      let reason = sml_comment::deprecated(doc)?;
+      let def =
+        sml_statics_types::def::Def::Path(sml_statics_types::def::Path::Regular(path), *idx);
+      Some((def, reason.to_owned()))
+    })
+    .collect();
+  let mode = sml_statics_types::mode::Mode::Regular(Some(path));
+  let mut checked = if deprecated.is_empty() {
+    sml_statics::get(syms_tys, scope, mode, &low.arenas, &low.root)
+  } else {
+    let mut scope = scope.clone();
+    scope.deprecated.append(&mut deprecated.clone());
+    sml_statics::get(syms_tys, &scope, mode, &low.arenas, &low.root)
+  };
+  for (idx, doc) in docs {
+    checked.info.add_doc(idx, doc);
+  }
+  (checked, deprecated)
+}
+
 /// Processes a single group file.
 fn get_group_file(st: &mut St<'_>, cx: Cx<'_>, ac: &mut MBasis, path: paths::PathId) {
   let dec = cx.bas_decs.get(&path).expect("no bas dec");
@@ -361,18 +415,69 @@ pub fn add_all_doc_comments(
   low: &sml_hir_lower::Lower,
   info: &mut sml_statics::info::Info,
 ) {
+  for (idx, doc) in doc_comments(root, low) {
+    info.add_doc(idx, doc);
+  }
+}
+
+/// Returns the doc comments in the `root`, with the indices they are attached to.
+fn doc_comments(
+  root: &sml_syntax::SyntaxNode,
+  low: &sml_hir_lower::Lower,
+) -> Vec<(sml_hir::Idx, String)> {
   let indices = std::iter::empty()
     .chain(low.arenas.pat.iter().map(|(x, _)| sml_hir::Idx::Pat(x)))
     .chain(low.arenas.dec.iter().map(|(x, _)| sml_hir::Idx::Dec(x)))
     .chain(low.arenas.str_dec.iter().map(|(x, _)| sml_hir::Idx::StrDec(x)))
     .chain(low.arenas.spec.iter().map(|(x, _)| sml_hir::Idx::Spec(x)));
-  for idx in indices {
-    let ptr = low.ptrs.hir_to_ast(idx).expect("no syntax ptr");
-    let node = ptr.to_node(root);
-    if let Some(doc) = sml_comment::doc_comment_above(&node) {
-      info.add_doc(idx, doc);
-    }
+  indices
+    .filter_map(|idx| {
+      let ptr = low.ptrs.hir_to_ast(idx).expect("no syntax ptr");
+      let node = ptr.to_node(root);
+      let doc = sml_comment::doc_comment_above(&node)?;
+      Some((idx, doc))
+    })
+    .collect()
+}
+
+/// Returns whether the doc comment attached to the index is really about the item defined by it.
+///
+/// Every pattern inside a `val` or `fun` is attached to the doc comment above it, which is fine for
+/// showing docs, but e.g. the parameters of a deprecated function are not themselves deprecated. So
+/// for patterns, we only allow ones that are a single name directly after the keyword.
+fn is_doc_comment_owner(
+  root: &sml_syntax::SyntaxNode,
+  low: &sml_hir_lower::Lower,
+  idx: sml_hir::Idx,
+) -> bool {
+  let pat = match idx {
+    sml_hir::Idx::Pat(x) => x,
+    _ => return true,
+  };
+  match &low.arenas.pat[pat] {
+    // names starting with `'` are generated by lowering, e.g. for the arguments of a `fun`.
+    sml_hir::Pat::Con(path, None)
+      if path.prefix().is_empty() && !path.last().as_str().starts_with('\'') => {}
+    _ => return false,
   }
+  let node = match low.ptrs.hir_to_ast(idx) {
+    Some(ptr) => ptr.to_node(root),
+    None => return false,
+  };
+  let mut tok = match node.first_token().and_then(|x| x.prev_token()) {
+    Some(x) => x,
+    None => return false,
+  };
+  while matches!(tok.kind(), SyntaxKind::Whitespace | SyntaxKind::BlockComment) {
+    tok = match tok.prev_token() {
+      Some(x) => x,
+      None => return false,
+    };
+  }
+  matches!(
+    tok.kind(),
+    SyntaxKind::ValKw | SyntaxKind::FunKw | SyntaxKind::AndKw | SyntaxKind::RecKw
+  )
 }
 
 /// Update a single source file.
@@ -385,9 +490,6 @@ pub fn update_one(
 ) {
   let mut fix_env = sml_fixity::STD_BASIS.clone();
   sf.syntax = sml_file_syntax::SourceFileSyntax::new(&mut fix_env, lang, contents);
-  let mode = sml_statics_types::mode::Mode::Regular(Some(path));
-  let checked =
-    sml_statics::get(syms_tys, &sf.scope, mode, &sf.syntax.lower.arenas, &sf.syntax.lower.root);
+  let (checked, _) = get_statics(syms_tys, &sf.scope, path, &sf.syntax);
   sf.info = checked.info;
-  add_all_doc_comments(sf.syntax.parse.root.syntax(), &sf.syntax.lower, &mut sf.info);
 }
@@ -0,0 +1,408 @@
+//! Reading and writing [SCIP][1] indices, with just enough of protobuf for what we use.
+//!
+//! Fields not listed here are omitted when writing and skipped when reading.
+//!
+//! [1]: https://github.com/sourcegraph/scip
+
+#![deny(clippy::pedantic, missing_debug_implementations, missing_docs, rust_2018_idioms)]
+// TODO remove once rustfmt support lands
+#![allow(clippy::manual_let_else)]
+
+use std::fmt;
+
+/// The `TextEncoding` for UTF-8.
+pub const TEXT_ENCODING_UTF8: u64 = 1;
+/// The `PositionEncoding` for UTF-16 code unit offsets from the start of the line.
+pub const POSITION_ENCODING_UTF16: u64 = 2;
+/// The `SymbolRole` bit for a definition.
+pub const ROLE_DEFINITION: u64 = 1;
+
+/// A whole index.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Index {
+  /// Field 1.
+  pub metadata: Metadata,
+  /// Field 2.
+  pub documents: Vec<Document>,
+  /// Field 3. Symbols referenced by the documents, but not defined in any of them.
+  pub external_symbols: Vec<SymbolInformation>,
+}
+
+/// Information about the index.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Metadata {
+  /// Field 2, the tool info, field 1.
+  pub tool_name: String,
+  /// Field 2, the tool info, field 2.
+  pub tool_version: String,
+  /// Field 3, a URI.
+  pub project_root: String,
+  /// Field 4.
+  pub text_document_encoding: u64,
+}
+
+/// A source file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Document {
+  /// Field 1, relative to the project root.
+  pub relative_path: String,
+  /// Field 2.
+  pub occurrences: Vec<Occurrence>,
+  /// Field 3, the symbols defined in this.
+  pub symbols: Vec<SymbolInformation>,
+  /// Field 4.
+  pub language: String,
+  /// Field 6.
+  pub position_encoding: u64,
+}
+
+/// A definition of or reference to a symbol.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Occurrence {
+  /// Field 1: the start line, start column, end line (if different from the start line), and end
+  /// column.
+  pub range: Vec<u32>,
+  /// Field 2.
+  pub symbol: String,
+  /// Field 3, a bit set.
+  pub symbol_roles: u64,
+  /// Field 7, like `range`, the range of the whole definition.
+  pub enclosing_range: Vec<u32>,
+}
+
+/// Information about a symbol.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SymbolInformation {
+  /// Field 1.
+  pub symbol: String,
+  /// Field 3, as Markdown.
+  pub documentation: Vec<String>,
+  /// Field 6.
+  pub display_name: String,
+  /// Field 8.
+  pub enclosing_symbol: String,
+}
+
+/// Returns the protobuf encoding of the index.
+#[must_use]
+pub fn encode(index: &Index) -> Vec<u8> {
+  let mut tool_info = Writer::default();
+  tool_info.string(1, index.metadata.tool_name.as_str());
+  tool_info.string(2, index.metadata.tool_version.as_str());
+  let mut metadata = Writer::default();
+  metadata.message(2, &tool_info);
+  metadata.string(3, index.metadata.project_root.as_str());
+  metadata.int(4, index.metadata.text_document_encoding);
+  let mut ret = Writer::default();
+  ret.message(1, &metadata);
+  for doc in &index.documents {
+    let mut document = Writer::default();
+    document.string(1, doc.relative_path.as_str());
+    for occ in &doc.occurrences {
+      let mut occurrence = Writer::default();
+      occurrence.packed(1, &occ.range);
+      occurrence.string(2, occ.symbol.as_str());
+      occurrence.int(3, occ.symbol_roles);
+      occurrence.packed(7, &occ.enclosing_range);
+      document.message(2, &occurrence);
+    }
+    for sym in &doc.symbols {
+      document.message(3, &symbol_information(sym));
+    }
+    document.string(4, doc.language.as_str());
+    document.int(6, doc.position_encoding);
+    ret.message(2, &document);
+  }
+  for sym in &index.external_symbols {
+    ret.message(3, &symbol_information(sym));
+  }
+  ret.0
+}
+
+fn symbol_information(sym: &SymbolInformation) -> Writer {
+  let mut ret = Writer::default();
+  ret.string(1, sym.symbol.as_str());
+  for doc in &sym.documentation {
+    ret.bytes(3, doc.as_bytes());
+  }
+  ret.string(6, sym.display_name.as_str());
+  ret.string(8, sym.enclosing_symbol.as_str());
+  ret
+}
+
+/// Decodes an index from its protobuf encoding.
+///
+/// # Errors
+///
+/// If the bytes are not a valid encoding.
+pub fn decode(bytes: &[u8]) -> Result<Index, Error> {
+  let mut ret = Index::default();
+  for field in Reader(bytes) {
+    match field? {
+      (1, Value::Len(bs)) => {
+        for field in Reader(bs) {
+          match field? {
+            (2, Value::Len(bs)) => {
+              for field in Reader(bs) {
+                match field? {
+                  (1, v) => ret.metadata.tool_name = v.string()?,
+                  (2, v) => ret.metadata.tool_version = v.string()?,
+                  _ => {}
+                }
+              }
+            }
+            (3, v) => ret.metadata.project_root = v.string()?,
+            (4, v) => ret.metadata.text_document_encoding = v.int()?,
+            _ => {}
+          }
+        }
+      }
+      (2, Value::Len(bs)) => ret.documents.push(document(bs)?),
+      (3, Value::Len(bs)) => ret.external_symbols.push(decode_symbol_information(bs)?),
+      _ => {}
+    }
+  }
+  Ok(ret)
+}
+
+fn document(bytes: &[u8]) -> Result<Document, Error> {
+  let mut ret = Document::default();
+  for field in Reader(bytes) {
+    match field? {
+      (1, v) => ret.relative_path = v.string()?,
+      (2, Value::Len(bs)) => {
+        let mut occ = Occurrence::default();
+        for field in Reader(bs) {
+          match field? {
+            (1, v) => occ.range = v.packed()?,
+            (2, v) => occ.symbol = v.string()?,
+            (3, v) => occ.symbol_roles = v.int()?,
+            (7, v) => occ.enclosing_range = v.packed()?,
+            _ => {}
+          }
+        }
+        ret.occurrences.push(occ);
+      }
+      (3, Value::Len(bs)) => ret.symbols.push(decode_symbol_information(bs)?),
+      (4, v) => ret.language = v.string()?,
+      (6, v) => ret.position_encoding = v.int()?,
+      _ => {}
+    }
+  }
+  Ok(ret)
+}
+
+fn decode_symbol_information(bytes: &[u8]) -> Result<SymbolInformation, Error> {
+  let mut ret = SymbolInformation::default();
+  for field in Reader(bytes) {
+    match field? {
+      (1, v) => ret.symbol = v.string()?,
+      (3, v) => ret.documentation.push(v.string()?),
+      (6, v) => ret.display_name = v.string()?,
+      (8, v) => ret.enclosing_symbol = v.string()?,
+      _ => {}
+    }
+  }
+  Ok(ret)
+}
+
+/// An error when decoding.
+#[derive(Debug)]
+pub enum Error {
+  /// The bytes ended in the middle of a field.
+  Eof,
+  /// A varint was too long.
+  VarintTooLong,
+  /// A field had an unknown wire type.
+  WireType(u64),
+  /// A field had a different wire type than expected.
+  UnexpectedWireType,
+  /// A string was not UTF-8.
+  Utf8(std::string::FromUtf8Error),
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Error::Eof => f.write_str("unexpected end of input"),
+      Error::VarintTooLong => f.write_str("varint too long"),
+      Error::WireType(n) => write!(f, "unknown wire type: {n}"),
+      Error::UnexpectedWireType => f.write_str("unexpected wire type"),
+      Error::Utf8(_) => f.write_str("string was not UTF-8"),
+    }
+  }
+}
+
+impl std::error::Error for Error {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      Error::Utf8(e) => Some(e),
+      Error::Eof | Error::VarintTooLong | Error::WireType(_) | Error::UnexpectedWireType => None,
+    }
+  }
+}
+
+const VARINT: u64 = 0;
+const I64: u64 = 1;
+const LEN: u64 = 2;
+const I32: u64 = 5;
+
+/// A protobuf message being encoded.
+#[derive(Debug, Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+  fn varint(&mut self, mut n: u64) {
+    loop {
+      let byte = u8::try_from(n & 0x7f).expect("masked to 7 bits");
+      n >>= 7;
+      if n == 0 {
+        self.0.push(byte);
+        return;
+      }
+      self.0.push(byte | 0x80);
+    }
+  }
+
+  fn key(&mut self, field: u32, wire_type: u64) {
+    self.varint((u64::from(field) << 3) | wire_type);
+  }
+
+  fn bytes(&mut self, field: u32, bytes: &[u8]) {
+    self.key(field, LEN);
+    self.varint(u64::try_from(bytes.len()).expect("len fits in u64"));
+    self.0.extend_from_slice(bytes);
+  }
+
+  /// Omits the default value, 0.
+  fn int(&mut self, field: u32, n: u64) {
+    if n != 0 {
+      self.key(field, VARINT);
+      self.varint(n);
+    }
+  }
+
+  /// Omits the default value, the empty string.
+  fn string(&mut self, field: u32, s: &str) {
+    if !s.is_empty() {
+      self.bytes(field, s.as_bytes());
+    }
+  }
+
+  fn message(&mut self, field: u32, msg: &Writer) {
+    self.bytes(field, msg.0.as_slice());
+  }
+
+  /// Omits the default value, the empty list.
+  fn packed(&mut self, field: u32, ns: &[u32]) {
+    if ns.is_empty() {
+      return;
+    }
+    let mut packed = Writer::default();
+    for &n in ns {
+      packed.varint(u64::from(n));
+    }
+    self.bytes(field, packed.0.as_slice());
+  }
+}
+
+/// The value of a field being decoded.
+enum Value<'a> {
+  Varint(u64),
+  Len(&'a [u8]),
+  /// A fixed-width value, which we don't use.
+  Fixed,
+}
+
+impl Value<'_> {
+  fn int(self) -> Result<u64, Error> {
+    match self {
+      Value::Varint(n) => Ok(n),
+      Value::Len(_) | Value::Fixed => Err(Error::UnexpectedWireType),
+    }
+  }
+
+  fn string(self) -> Result<String, Error> {
+    match self {
+      Value::Len(bs) => String::from_utf8(bs.to_vec()).map_err(Error::Utf8),
+      Value::Varint(_) | Value::Fixed => Err(Error::UnexpectedWireType),
+    }
+  }
+
+  fn packed(self) -> Result<Vec<u32>, Error> {
+    match self {
+      Value::Len(mut bs) => {
+        let mut ret = Vec::<u32>::new();
+        while !bs.is_empty() {
+          let n = varint(&mut bs)?;
+          ret.push(u32::try_from(n).map_err(|_| Error::VarintTooLong)?);
+        }
+        Ok(ret)
+      }
+      Value::Varint(_) | Value::Fixed => Err(Error::UnexpectedWireType),
+    }
+  }
+}
+
+/// The fields of a protobuf message being decoded.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Iterator for Reader<'a> {
+  type Item = Result<(u32, Value<'a>), Error>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.0.is_empty() {
+      return None;
+    }
+    let ret = field(&mut self.0);
+    if ret.is_err() {
+      // stop after an error.
+      self.0 = &[];
+    }
+    Some(ret)
+  }
+}
+
+fn field<'a>(bs: &mut &'a [u8]) -> Result<(u32, Value<'a>), Error> {
+  let key = varint(bs)?;
+  let field = u32::try_from(key >> 3).map_err(|_| Error::VarintTooLong)?;
+  let value = match key & 0x7 {
+    VARINT => Value::Varint(varint(bs)?),
+    LEN => {
+      let len = usize::try_from(varint(bs)?).map_err(|_| Error::Eof)?;
+      Value::Len(take(bs, len)?)
+    }
+    I64 => {
+      take(bs, 8)?;
+      Value::Fixed
+    }
+    I32 => {
+      take(bs, 4)?;
+      Value::Fixed
+    }
+    wire_type => return Err(Error::WireType(wire_type)),
+  };
+  Ok((field, value))
+}
+
+fn varint(bs: &mut &[u8]) -> Result<u64, Error> {
+  let mut ret = 0u64;
+  for shift in (0..64).step_by(7) {
+    let (&byte, rest) = bs.split_first().ok_or(Error::Eof)?;
+    *bs = rest;
+    ret |= u64::from(byte & 0x7f) << shift;
+    if byte & 0x80 == 0 {
+      return Ok(ret);
+    }
+  }
+  Err(Error::VarintTooLong)
+}
+
+fn take<'a>(bs: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+  if bs.len() < len {
+    return Err(Error::Eof);
+  }
+  let (ret, rest) = bs.split_at(len);
+  *bs = rest;
+  Ok(ret)
+}
@@ -60,3 +60,22 @@ pub fn comment_above(node: &SyntaxNode) -> Option<SyntaxToken> {
     tok = tok.prev_token()?;
   }
 }
+
+/// Returns the reason given by the `@deprecated` tag in the doc string, if there is such a tag. The
+/// reason may be empty.
+///
+/// The tag must be at the start of a line, like this:
+///
+/// ```text
+/// @deprecated use `bar` instead
+/// ```
+#[must_use]
+pub fn deprecated(doc: &str) -> Option<&str> {
+  doc.lines().find_map(|line| {
+    let rest = line.trim_start().strip_prefix("@deprecated")?;
+    if rest.starts_with(|c: char| !c.is_whitespace()) {
+      return None;
+    }
+    Some(rest.trim())
+  })
+}
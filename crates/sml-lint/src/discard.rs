@@ -0,0 +1,87 @@
+//! Lints for discarding results that are not `unit`.
+
+use crate::error::{Discard, Error, ErrorKind};
+use sml_statics::info::Info;
+use sml_statics_types::ty::TyData;
+
+type ExpIdx = sml_hir::la_arena::Idx<sml_hir::Exp>;
+
+pub(crate) fn get(
+  st: &sml_statics_types::St,
+  lang: &config::lang::Language,
+  info: &Info,
+  ars: &sml_hir::Arenas,
+) -> Vec<Error> {
+  let mut ret = Vec::<Error>::new();
+  for (_, exp) in ars.exp.iter() {
+    let (func, argument) = match exp {
+      sml_hir::Exp::App(Some(func), Some(argument)) => (*func, *argument),
+      _ => continue,
+    };
+    if !matches!(&ars.exp[func], sml_hir::Exp::Fn(_, sml_hir::FnFlavor::Seq)) {
+      continue;
+    }
+    if let Some(kind) = discarded(st, lang, info, ars, argument, Discard::Seq) {
+      ret.push(Error { idx: argument.into(), kind });
+    }
+  }
+  for (_, dec) in ars.dec.iter() {
+    let binds = match dec {
+      sml_hir::Dec::Val(_, binds, sml_hir::ValFlavor::Val) => binds,
+      _ => continue,
+    };
+    for bind in binds {
+      let (pat, exp) = match (bind.pat, bind.exp) {
+        (Some(pat), Some(exp)) => (pat, exp),
+        _ => continue,
+      };
+      if !matches!(ars.pat[pat], sml_hir::Pat::Wild) {
+        continue;
+      }
+      if let Some(kind) = discarded(st, lang, info, ars, exp, Discard::ValWild) {
+        ret.push(Error { idx: exp.into(), kind });
+      }
+    }
+  }
+  ret
+}
+
+/// Returns an error if the exp, whose result is being discarded, has a type that is not `unit` and
+/// is not a call to a function allowed to have its result discarded.
+fn discarded(
+  st: &sml_statics_types::St,
+  lang: &config::lang::Language,
+  info: &Info,
+  ars: &sml_hir::Arenas,
+  idx: ExpIdx,
+  discard: Discard,
+) -> Option<ErrorKind> {
+  let ty = info.get_ty(idx.into())?;
+  let (ty, data) = st.tys.canonicalize(ty);
+  match data {
+    TyData::None | TyData::UnsolvedMetaVar(_) | TyData::GeneralizedMetaVar(_) => return None,
+    TyData::Record(rows) if rows.is_empty() => return None,
+    TyData::BoundVar(_)
+    | TyData::FixedVar(_)
+    | TyData::Record(_)
+    | TyData::Con(_)
+    | TyData::Fn(_) => {}
+  }
+  if head_path(ars, idx).map_or(false, |path| lang.discard.contains(path)) {
+    return None;
+  }
+  let ty = ty.display(st, config::DiagnosticLines::One).to_string();
+  Some(ErrorKind::Discard(discard, ty))
+}
+
+/// Returns the path at the head of a chain of applications, like the `f` in `f x y`.
+fn head_path(ars: &sml_hir::Arenas, mut idx: ExpIdx) -> Option<&sml_path::Path> {
+  loop {
+    match &ars.exp[idx] {
+      sml_hir::Exp::App(func, _) => idx = (*func)?,
+      sml_hir::Exp::Typed(exp, _, _) => idx = (*exp)?,
+      sml_hir::Exp::Path(path) => return Some(path),
+      _ => return None,
+    }
+  }
+}
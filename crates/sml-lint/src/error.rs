@@ -0,0 +1,197 @@
+//! Errors.
+
+use diagnostic::{Code, Severity};
+use std::fmt;
+
+type ExpIdx = sml_hir::la_arena::Idx<sml_hir::Exp>;
+type PatIdx = sml_hir::la_arena::Idx<sml_hir::Pat>;
+
+#[derive(Debug)]
+pub(crate) enum Eq {
+  Eq,
+  Neq,
+}
+
+impl fmt::Display for Eq {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Eq::Eq => f.write_str("="),
+      Eq::Neq => f.write_str("<>"),
+    }
+  }
+}
+
+/// A `case` with one arm.
+#[derive(Debug)]
+pub(crate) struct OneArmedCase {
+  pub(crate) head: ExpIdx,
+  pub(crate) pat: PatIdx,
+}
+
+/// Where a result is discarded.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Discard {
+  /// A non-final expression in a sequence.
+  Seq,
+  /// A `val _ =` binding.
+  ValWild,
+}
+
+impl fmt::Display for Discard {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Discard::Seq => f.write_str("in a sequence"),
+      Discard::ValWild => f.write_str("with `val _`"),
+    }
+  }
+}
+
+#[derive(Debug)]
+pub(crate) enum ErrorKind {
+  /// The exp is the one compared to `nil`. The statics already report this, so this only offers a
+  /// fix for that error.
+  NilEq(Eq, ExpIdx),
+  /// The exp is the list whose length is compared to `0`.
+  LengthEqZero(Eq, ExpIdx),
+  /// The exp is the function.
+  EtaRedex(ExpIdx),
+  /// The exp is the body of the innermost `case`.
+  NestedOneArmedCase(Vec<OneArmedCase>, ExpIdx),
+  PartialFn(&'static str),
+  /// The string is the type of the discarded result.
+  Discard(Discard, String),
+  /// The string is the exceptions that may escape the function with the name.
+  Escape(str_util::Name, String),
+}
+
+impl fmt::Display for ErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ErrorKind::NilEq(eq, _) => write!(f, "comparing with `nil` using `{eq}`"),
+      ErrorKind::LengthEqZero(eq, _) => {
+        write!(f, "comparing the length of a list with `0` using `{eq}`")
+      }
+      ErrorKind::EtaRedex(_) => f.write_str("unnecessary `fn` wrapping a function"),
+      ErrorKind::NestedOneArmedCase(_, _) => f.write_str("nested `case`s with only one arm each"),
+      ErrorKind::PartialFn(name) => write!(f, "use of partial function `{name}`"),
+      ErrorKind::Discard(discard, ty) => {
+        write!(f, "discarding a value of type `{ty}` {discard}")
+      }
+      ErrorKind::Escape(name, exns) => write!(f, "exceptions may escape from `{name}`: {exns}"),
+    }
+  }
+}
+
+/// An error from linting.
+#[derive(Debug)]
+pub struct Error {
+  pub(crate) idx: sml_hir::Idx,
+  pub(crate) kind: ErrorKind,
+}
+
+impl Error {
+  /// Returns the idx for this.
+  #[must_use]
+  pub fn idx(&self) -> sml_hir::Idx {
+    self.idx
+  }
+
+  /// Returns the code for this, or `None` if this should not be reported, because it only offers a
+  /// fix for an error from the statics.
+  #[must_use]
+  pub fn code(&self) -> Option<Code> {
+    let ret = match self.kind {
+      ErrorKind::NilEq(_, _) => return None,
+      ErrorKind::LengthEqZero(_, _) => Code::n(7002),
+      ErrorKind::EtaRedex(_) => Code::n(7003),
+      ErrorKind::NestedOneArmedCase(_, _) => Code::n(7004),
+      ErrorKind::PartialFn(_) => Code::n(7005),
+      ErrorKind::Discard(Discard::Seq, _) => Code::n(7006),
+      ErrorKind::Discard(Discard::ValWild, _) => Code::n(7007),
+      ErrorKind::Escape(_, _) => Code::n(7008),
+    };
+    Some(ret)
+  }
+
+  /// Returns the default severity for this, or `None` if this is off unless a severity is set for
+  /// its code in the config.
+  #[must_use]
+  pub fn severity(&self) -> Option<Severity> {
+    match self.kind {
+      // `val _ = e` is a common way to evaluate `e` for its effects, so this is too noisy to be on
+      // by default.
+      ErrorKind::Discard(Discard::ValWild, _) => None,
+      ErrorKind::NilEq(_, _)
+      | ErrorKind::LengthEqZero(_, _)
+      | ErrorKind::EtaRedex(_)
+      | ErrorKind::NestedOneArmedCase(_, _)
+      | ErrorKind::PartialFn(_)
+      | ErrorKind::Discard(Discard::Seq, _)
+      | ErrorKind::Escape(_, _) => Some(Severity::Warning),
+    }
+  }
+
+  /// Returns a fix for this, if there is a mechanical one.
+  #[must_use]
+  pub fn fix(&self) -> Option<Fix> {
+    let ret = match &self.kind {
+      ErrorKind::NilEq(eq, exp) | ErrorKind::LengthEqZero(eq, exp) => {
+        let exp = FixPart::Syntax { idx: (*exp).into(), atomic: true };
+        let parts = match eq {
+          Eq::Eq => vec![FixPart::Str("List.null "), exp],
+          Eq::Neq => vec![FixPart::Str("not (List.null "), exp, FixPart::Str(")")],
+        };
+        // qualify, since `null` may be shadowed, or not even be in scope with some configs.
+        Fix { title: "Replace with `List.null`", parts }
+      }
+      ErrorKind::EtaRedex(exp) => Fix {
+        title: "Replace with the function",
+        parts: vec![FixPart::Syntax { idx: (*exp).into(), atomic: false }],
+      },
+      ErrorKind::NestedOneArmedCase(cases, body) => {
+        let mut parts = vec![FixPart::Str("let")];
+        for case in cases {
+          parts.push(FixPart::Str(" val "));
+          parts.push(FixPart::Syntax { idx: case.pat.into(), atomic: false });
+          parts.push(FixPart::Str(" = "));
+          parts.push(FixPart::Syntax { idx: case.head.into(), atomic: false });
+        }
+        parts.push(FixPart::Str(" in "));
+        parts.push(FixPart::Syntax { idx: (*body).into(), atomic: false });
+        parts.push(FixPart::Str(" end"));
+        Fix { title: "Rewrite with `let` and `val`", parts }
+      }
+      ErrorKind::PartialFn(_) | ErrorKind::Discard(_, _) | ErrorKind::Escape(_, _) => return None,
+    };
+    Some(ret)
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.kind.fmt(f)
+  }
+}
+
+/// A fix for an error, which replaces the syntax for the error's idx.
+#[derive(Debug)]
+pub struct Fix {
+  /// A description of the fix.
+  pub title: &'static str,
+  /// The parts of the text to replace with, in order.
+  pub parts: Vec<FixPart>,
+}
+
+/// A part of the text for a fix.
+#[derive(Debug)]
+pub enum FixPart {
+  /// A literal string.
+  Str(&'static str),
+  /// The text of the syntax for an idx.
+  Syntax {
+    /// The idx.
+    idx: sml_hir::Idx,
+    /// Whether the text must be atomic, i.e. be wrapped in parentheses if it is not already.
+    atomic: bool,
+  },
+}
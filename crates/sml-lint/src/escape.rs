@@ -0,0 +1,48 @@
+//! Lints for exceptions escaping entry points.
+
+use crate::error::{Error, ErrorKind};
+use sml_statics::info::Info;
+use sml_statics_types::def;
+
+pub(crate) fn get(
+  lang: &config::lang::Language,
+  raises: &sml_raise::Raises,
+  path: paths::PathId,
+  info: &Info,
+  ars: &sml_hir::Arenas,
+) -> Vec<Error> {
+  let mut ret = Vec::<Error>::new();
+  if lang.entry_points.is_empty() {
+    return ret;
+  }
+  for (_, dec) in ars.dec.iter() {
+    let binds = match dec {
+      sml_hir::Dec::Val(_, binds, _) => binds,
+      _ => continue,
+    };
+    for bind in binds {
+      let pat = match sml_raise::fn_bind(info, ars, bind) {
+        Some((pat, _)) => pat,
+        None => continue,
+      };
+      let name = match &ars.pat[pat] {
+        sml_hir::Pat::Con(p, _) => p.last(),
+        _ => continue,
+      };
+      if !lang.entry_points.contains(name) {
+        continue;
+      }
+      let def = def::Def::Path(def::Path::Regular(path), pat.into());
+      let exns = match raises.get(def) {
+        Some(x) => x,
+        None => continue,
+      };
+      if exns.is_empty() {
+        continue;
+      }
+      let kind = ErrorKind::Escape(name.clone(), exns.join(", "));
+      ret.push(Error { idx: pat.into(), kind });
+    }
+  }
+  ret
+}
@@ -0,0 +1,196 @@
+//! Lints for expressions.
+
+use crate::error::{Eq, Error, ErrorKind, OneArmedCase};
+use fast_hash::FxHashSet;
+use sml_statics::info::Info;
+use sml_statics_types::def;
+
+type ExpIdx = sml_hir::la_arena::Idx<sml_hir::Exp>;
+
+/// Partial functions in built-in libraries, with the structure they are in.
+const PARTIAL_FNS: [(&str, &str); 3] = [("List", "hd"), ("List", "tl"), ("Option", "valOf")];
+
+pub(crate) fn get(info: &Info, ars: &sml_hir::Arenas) -> Vec<Error> {
+  // for nested `case`s, we only report the outermost one.
+  let mut inner_cases = FxHashSet::<ExpIdx>::default();
+  for (idx, _) in ars.exp.iter() {
+    if let Some(body) = one_armed_case(ars, idx).and_then(|(_, arm)| arm.exp) {
+      if one_armed_case(ars, body).is_some() {
+        inner_cases.insert(body);
+      }
+    }
+  }
+  let mut ret = Vec::<Error>::new();
+  for (idx, exp) in ars.exp.iter() {
+    let kind = match exp {
+      sml_hir::Exp::App(func, argument) => app(info, ars, &inner_cases, idx, *func, *argument),
+      sml_hir::Exp::Fn(arms, sml_hir::FnFlavor::Fn) => eta_redex(info, ars, arms),
+      sml_hir::Exp::Path(_) => partial_fn(info, ars, idx),
+      _ => None,
+    };
+    if let Some(kind) = kind {
+      ret.push(Error { idx: idx.into(), kind });
+    }
+  }
+  ret
+}
+
+fn app(
+  info: &Info,
+  ars: &sml_hir::Arenas,
+  inner_cases: &FxHashSet<ExpIdx>,
+  idx: ExpIdx,
+  func: sml_hir::ExpIdx,
+  argument: sml_hir::ExpIdx,
+) -> Option<ErrorKind> {
+  let func = func?;
+  match &ars.exp[func] {
+    sml_hir::Exp::Path(_) => {
+      let eq = if is_primitive(info, func, def::Primitive::Eq) {
+        Eq::Eq
+      } else if is_primitive(info, func, def::Primitive::Neq) {
+        Eq::Neq
+      } else {
+        return None;
+      };
+      let [lhs, rhs] = get_pair(ars, argument?)?;
+      for (a, b) in [(lhs, rhs), (rhs, lhs)] {
+        if is_primitive(info, b, def::Primitive::Nil) {
+          return Some(ErrorKind::NilEq(eq, a));
+        }
+      }
+      for (a, b) in [(lhs, rhs), (rhs, lhs)] {
+        if is_zero(ars, b) {
+          if let Some(xs) = length_argument(info, ars, a) {
+            return Some(ErrorKind::LengthEqZero(eq, xs));
+          }
+        }
+      }
+      None
+    }
+    sml_hir::Exp::Fn(_, sml_hir::FnFlavor::Case) => {
+      if inner_cases.contains(&idx) {
+        return None;
+      }
+      let mut cases = Vec::<OneArmedCase>::new();
+      let mut body = idx;
+      while let Some((head, arm)) = one_armed_case(ars, body) {
+        cases.push(OneArmedCase { head, pat: arm.pat? });
+        body = arm.exp?;
+      }
+      (cases.len() >= 2).then_some(ErrorKind::NestedOneArmedCase(cases, body))
+    }
+    _ => None,
+  }
+}
+
+/// Returns the function in `fn x => f x`, where `f` is a path not referring to `x`.
+fn eta_redex(info: &Info, ars: &sml_hir::Arenas, arms: &[sml_hir::Arm]) -> Option<ErrorKind> {
+  let arm = match arms {
+    [arm] => arm,
+    _ => return None,
+  };
+  let pat = arm.pat?;
+  match &ars.pat[pat] {
+    sml_hir::Pat::Con(path, None) if path.prefix().is_empty() => {}
+    _ => return None,
+  }
+  let (func, argument) = match &ars.exp[arm.exp?] {
+    sml_hir::Exp::App(func, argument) => ((*func)?, (*argument)?),
+    _ => return None,
+  };
+  if !matches!(ars.exp[func], sml_hir::Exp::Path(_))
+    || !matches!(ars.exp[argument], sml_hir::Exp::Path(_))
+  {
+    return None;
+  }
+  let pat = sml_hir::Idx::Pat(pat);
+  let is_pat = |d: &def::Def| matches!(*d, def::Def::Path(_, idx) if idx == pat);
+  let argument_defs = info.get_defs(argument.into());
+  if argument_defs.len() != 1 || !argument_defs.iter().all(is_pat) {
+    return None;
+  }
+  if info.get_defs(func.into()).iter().any(is_pat) {
+    return None;
+  }
+  Some(ErrorKind::EtaRedex(func))
+}
+
+fn partial_fn(info: &Info, ars: &sml_hir::Arenas, idx: ExpIdx) -> Option<ErrorKind> {
+  PARTIAL_FNS
+    .iter()
+    .find(|&&(structure, name)| is_builtin_val(info, ars, idx, structure, name))
+    .map(|&(_, name)| ErrorKind::PartialFn(name))
+}
+
+/// Returns the `xs` in `length xs`.
+fn length_argument(info: &Info, ars: &sml_hir::Arenas, idx: ExpIdx) -> Option<ExpIdx> {
+  match &ars.exp[idx] {
+    sml_hir::Exp::App(func, argument) => {
+      is_builtin_val(info, ars, (*func)?, "List", "length").then_some((*argument)?)
+    }
+    _ => None,
+  }
+}
+
+/// Returns the head and the only arm of a `case` with one arm.
+fn one_armed_case(ars: &sml_hir::Arenas, idx: ExpIdx) -> Option<(ExpIdx, &sml_hir::Arm)> {
+  let (func, head) = match &ars.exp[idx] {
+    sml_hir::Exp::App(func, head) => ((*func)?, (*head)?),
+    _ => return None,
+  };
+  match &ars.exp[func] {
+    sml_hir::Exp::Fn(arms, sml_hir::FnFlavor::Case) => match arms.as_slice() {
+      [arm] => Some((head, arm)),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+fn get_pair(ars: &sml_hir::Arenas, idx: ExpIdx) -> Option<[ExpIdx; 2]> {
+  match &ars.exp[idx] {
+    sml_hir::Exp::Record(rows) => match rows.as_slice() {
+      &[(sml_hir::Lab::Num(1), a), (sml_hir::Lab::Num(2), b)] => Some([a?, b?]),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+fn is_zero(ars: &sml_hir::Arenas, idx: ExpIdx) -> bool {
+  match &ars.exp[idx] {
+    sml_hir::Exp::SCon(sml_hir::SCon::Int(n)) => *n == sml_hir::Int::from(0),
+    _ => false,
+  }
+}
+
+fn is_primitive(info: &Info, idx: ExpIdx, prim: def::Primitive) -> bool {
+  let defs = info.get_defs(idx.into());
+  defs.len() == 1 && defs.contains(&def::Def::Primitive(prim))
+}
+
+/// Returns whether the exp is a path to the value with the name, either unqualified or qualified
+/// by the structure, defined in a built-in library like the std basis.
+fn is_builtin_val(
+  info: &Info,
+  ars: &sml_hir::Arenas,
+  idx: ExpIdx,
+  structure: &str,
+  name: &str,
+) -> bool {
+  let path = match &ars.exp[idx] {
+    sml_hir::Exp::Path(x) => x,
+    _ => return false,
+  };
+  let prefix_ok = match path.prefix() {
+    [] => true,
+    [s] => s.as_str() == structure,
+    _ => false,
+  };
+  if !prefix_ok || path.last().as_str() != name {
+    return false;
+  }
+  let defs = info.get_defs(idx.into());
+  !defs.is_empty() && defs.iter().all(|d| matches!(d, def::Def::Path(def::Path::BuiltinLib(_), _)))
+}
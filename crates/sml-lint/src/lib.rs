@@ -0,0 +1,32 @@
+//! Lints, which are opinionated checks on HIR for style and possible bugs.
+//!
+//! These run after statics, so they may use the information from statics, like what names refer
+//! to.
+
+#![deny(clippy::pedantic, missing_debug_implementations, missing_docs, rust_2018_idioms)]
+#![allow(clippy::single_match_else)]
+// TODO remove once rustfmt support lands
+#![allow(clippy::manual_let_else)]
+
+mod discard;
+mod error;
+mod escape;
+mod exp;
+
+pub use error::{Error, Fix, FixPart};
+
+/// Returns the lint errors for the HIR.
+#[must_use]
+pub fn get(
+  st: &sml_statics_types::St,
+  lang: &config::lang::Language,
+  raises: &sml_raise::Raises,
+  path: paths::PathId,
+  info: &sml_statics::info::Info,
+  arenas: &sml_hir::Arenas,
+) -> Vec<Error> {
+  let mut ret = exp::get(info, arenas);
+  ret.extend(discard::get(st, lang, info, arenas));
+  ret.extend(escape::get(lang, raises, path, info, arenas));
+  ret
+}
@@ -0,0 +1,387 @@
+//! Infer what exceptions functions may raise.
+//!
+//! This is a conservative, flow-insensitive analysis over HIR. A function may raise an exception if
+//! its body `raise`s it without handling it, or if its body mentions a function that may raise it.
+//! Some partial functions in the std basis, like `List.hd`, are also known to raise exceptions.
+
+#![deny(clippy::pedantic, missing_debug_implementations, missing_docs, rust_2018_idioms)]
+#![allow(clippy::single_match_else)]
+// TODO remove once rustfmt support lands
+#![allow(clippy::manual_let_else)]
+
+use fast_hash::{FxHashMap, FxHashSet};
+use sml_statics::info::Info;
+use sml_statics_types::def;
+use str_util::Name;
+
+type ExpIdx = sml_hir::la_arena::Idx<sml_hir::Exp>;
+type PatIdx = sml_hir::la_arena::Idx<sml_hir::Pat>;
+
+/// Partial functions in built-in libraries, with the structure they are in and the exception they
+/// may raise.
+const PARTIAL_FNS: [(&str, &str, &str); 11] = [
+  ("List", "hd", "Empty"),
+  ("List", "tl", "Empty"),
+  ("List", "last", "Empty"),
+  ("List", "nth", "Subscript"),
+  ("List", "take", "Subscript"),
+  ("List", "drop", "Subscript"),
+  ("Option", "valOf", "Option"),
+  ("String", "sub", "Subscript"),
+  ("Vector", "sub", "Subscript"),
+  ("Array", "sub", "Subscript"),
+  ("Array", "update", "Subscript"),
+];
+
+/// An exception that may be raised.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Exn {
+  name: Name,
+  /// `None` for exceptions from built-in libraries.
+  def: Option<def::Def>,
+}
+
+impl Exn {
+  fn builtin(name: &str) -> Self {
+    Self { name: Name::new(name), def: None }
+  }
+
+  /// Returns the name of this.
+  #[must_use]
+  pub fn name(&self) -> &Name {
+    &self.name
+  }
+}
+
+/// The exceptions each function may raise.
+#[derive(Debug, Default)]
+pub struct Raises {
+  fns: FxHashMap<def::Def, FxHashSet<Exn>>,
+}
+
+impl Raises {
+  /// Returns the sorted, de-duplicated names of the exceptions the function defined at the def may
+  /// raise, or `None` if the def is not a function.
+  #[must_use]
+  pub fn get(&self, def: def::Def) -> Option<Vec<&str>> {
+    let exns = self.fns.get(&def)?;
+    let mut ret: Vec<_> = exns.iter().map(|x| x.name.as_str()).collect();
+    ret.sort_unstable();
+    ret.dedup();
+    Some(ret)
+  }
+
+  /// Updates the functions in the file at `path`, which changed.
+  ///
+  /// Functions in other files that use them are not updated, just as other files are not analyzed
+  /// again when a single file changes.
+  pub fn update(&mut self, path: paths::PathId, info: &Info, ars: &sml_hir::Arenas) {
+    self
+      .fns
+      .retain(|def, _| !matches!(def, def::Def::Path(def::Path::Regular(p), _) if *p == path));
+    let mut files = paths::PathMap::default();
+    files.insert(path, (info, ars));
+    let fns = get_fns(path, info, ars);
+    self.fixpoint(&files, &fns);
+  }
+
+  /// Adds the fns, and the exceptions they may raise, given the exceptions raised by the functions
+  /// already in this.
+  fn fixpoint(&mut self, files: &paths::PathMap<(&Info, &sml_hir::Arenas)>, fns: &[FnInfo]) {
+    for f in fns {
+      self.fns.insert(f.def, FxHashSet::default());
+    }
+    // the sets only ever grow, so this terminates.
+    loop {
+      let mut changed = false;
+      for f in fns {
+        let (info, ars) = files[&f.path];
+        let ac = {
+          let mut cx = Cx { path: f.path, info, ars, fns: &self.fns, caught: FxHashMap::default() };
+          let mut ac = FxHashSet::<Exn>::default();
+          exp(&mut cx, &mut ac, Some(f.body));
+          ac
+        };
+        let old = self.fns.get_mut(&f.def).expect("should have inserted all fns");
+        if ac.len() > old.len() {
+          *old = ac;
+          changed = true;
+        }
+      }
+      if !changed {
+        break;
+      }
+    }
+  }
+}
+
+/// A function, which is a `val` binding whose pattern is a name and whose expression is a `fn`.
+struct FnInfo {
+  def: def::Def,
+  path: paths::PathId,
+  body: ExpIdx,
+}
+
+/// Returns what exceptions the functions in the files may raise.
+#[must_use]
+pub fn get<'a, I>(files: I) -> Raises
+where
+  I: IntoIterator<Item = (paths::PathId, &'a Info, &'a sml_hir::Arenas)>,
+{
+  let files: paths::PathMap<_> = files.into_iter().map(|(p, info, ars)| (p, (info, ars))).collect();
+  let fns: Vec<_> =
+    files.iter().flat_map(|(&path, &(info, ars))| get_fns(path, info, ars)).collect();
+  let mut ret = Raises::default();
+  ret.fixpoint(&files, &fns);
+  ret
+}
+
+/// Returns the functions in the file.
+fn get_fns(path: paths::PathId, info: &Info, ars: &sml_hir::Arenas) -> Vec<FnInfo> {
+  let mut ret = Vec::<FnInfo>::new();
+  for (_, dec) in ars.dec.iter() {
+    let binds = match dec {
+      sml_hir::Dec::Val(_, binds, _) => binds,
+      _ => continue,
+    };
+    for bind in binds {
+      if let Some((pat, body)) = fn_bind(info, ars, bind) {
+        let def = def::Def::Path(def::Path::Regular(path), pat.into());
+        ret.push(FnInfo { def, path, body });
+      }
+    }
+  }
+  ret
+}
+
+/// If the val bind binds a function, returns the name pattern and the `fn` body.
+#[must_use]
+pub fn fn_bind(
+  info: &Info,
+  ars: &sml_hir::Arenas,
+  bind: &sml_hir::ValBind,
+) -> Option<(PatIdx, ExpIdx)> {
+  let pat = bind.pat?;
+  let body = bind.exp?;
+  match &ars.pat[pat] {
+    sml_hir::Pat::Con(path, None) if path.prefix().is_empty() => {}
+    _ => return None,
+  }
+  // a constructor, not a variable.
+  if !info.get_defs(pat.into()).is_empty() {
+    return None;
+  }
+  matches!(ars.exp[body], sml_hir::Exp::Fn(_, _)).then_some((pat, body))
+}
+
+struct Cx<'a> {
+  path: paths::PathId,
+  info: &'a Info,
+  ars: &'a sml_hir::Arenas,
+  fns: &'a FxHashMap<def::Def, FxHashSet<Exn>>,
+  /// The exceptions caught by variable patterns in handlers.
+  caught: FxHashMap<def::Def, FxHashSet<Exn>>,
+}
+
+fn exp(cx: &mut Cx<'_>, ac: &mut FxHashSet<Exn>, idx: sml_hir::ExpIdx) {
+  let idx = match idx {
+    Some(x) => x,
+    None => return,
+  };
+  match &cx.ars.exp[idx] {
+    sml_hir::Exp::Hole | sml_hir::Exp::SCon(_) => {}
+    sml_hir::Exp::Path(path) => {
+      let defs = cx.info.get_defs(idx.into());
+      for def in &defs {
+        if let Some(exns) = cx.fns.get(def) {
+          ac.extend(exns.iter().cloned());
+        }
+        if let def::Def::Primitive(def::Primitive::Div | def::Primitive::Mod) = def {
+          ac.insert(Exn::builtin("Div"));
+        }
+      }
+      let all_builtin = !defs.is_empty()
+        && defs.iter().all(|d| matches!(d, def::Def::Path(def::Path::BuiltinLib(_), _)));
+      if all_builtin {
+        ac.extend(partial_fn(path).map(Exn::builtin));
+      }
+    }
+    sml_hir::Exp::Record(rows) => {
+      for &(_, e) in rows {
+        exp(cx, ac, e);
+      }
+    }
+    sml_hir::Exp::Let(decs, e) => {
+      for &d in decs {
+        dec(cx, ac, d);
+      }
+      exp(cx, ac, *e);
+    }
+    sml_hir::Exp::App(func, argument) => {
+      exp(cx, ac, *func);
+      exp(cx, ac, *argument);
+    }
+    sml_hir::Exp::Handle(e, arms) => {
+      let mut inner = FxHashSet::<Exn>::default();
+      exp(cx, &mut inner, *e);
+      for arm in arms {
+        handle_pat(cx, &mut inner, arm.pat);
+      }
+      ac.extend(inner);
+      for arm in arms {
+        exp(cx, ac, arm.exp);
+      }
+    }
+    sml_hir::Exp::Raise(e) => {
+      exp(cx, ac, *e);
+      raised(cx, ac, *e);
+    }
+    sml_hir::Exp::Fn(arms, _) => {
+      for arm in arms {
+        exp(cx, ac, arm.exp);
+      }
+    }
+    sml_hir::Exp::Typed(e, _, _) => exp(cx, ac, *e),
+  }
+}
+
+fn dec(cx: &mut Cx<'_>, ac: &mut FxHashSet<Exn>, idx: sml_hir::DecIdx) {
+  match &cx.ars.dec[idx] {
+    sml_hir::Dec::Val(_, binds, _) => {
+      for bind in binds {
+        // defining a function does not call it.
+        if fn_bind(cx.info, cx.ars, bind).is_none() {
+          exp(cx, ac, bind.exp);
+        }
+      }
+    }
+    sml_hir::Dec::Abstype(_, _, decs) => {
+      for &d in decs {
+        dec(cx, ac, d);
+      }
+    }
+    sml_hir::Dec::Local(local_decs, in_decs) => {
+      for &d in local_decs.iter().chain(in_decs) {
+        dec(cx, ac, d);
+      }
+    }
+    sml_hir::Dec::Ty(_)
+    | sml_hir::Dec::Datatype(_, _)
+    | sml_hir::Dec::DatatypeCopy(_, _)
+    | sml_hir::Dec::Exception(_)
+    | sml_hir::Dec::Open(_) => {}
+  }
+}
+
+/// Adds the exceptions raised by `raise idx` to `ac`.
+fn raised(cx: &Cx<'_>, ac: &mut FxHashSet<Exn>, idx: sml_hir::ExpIdx) {
+  let mut idx = match idx {
+    Some(x) => x,
+    None => return,
+  };
+  let path = loop {
+    match &cx.ars.exp[idx] {
+      sml_hir::Exp::App(func, _) => match func {
+        Some(x) => idx = *x,
+        None => return,
+      },
+      sml_hir::Exp::Typed(e, _, _) => match e {
+        Some(x) => idx = *x,
+        None => return,
+      },
+      sml_hir::Exp::Path(path) => break path,
+      _ => return,
+    }
+  };
+  for def in cx.info.get_defs(idx.into()) {
+    match def {
+      def::Def::Path(def::Path::BuiltinLib(_), _) => {
+        ac.insert(Exn { name: path.last().clone(), def: None });
+      }
+      // a variable, which may have been bound by a handler.
+      def::Def::Path(def::Path::Regular(_), sml_hir::Idx::Pat(_)) => {
+        if let Some(exns) = cx.caught.get(&def) {
+          ac.extend(exns.iter().cloned());
+        }
+      }
+      def::Def::Path(def::Path::Regular(_), _) => {
+        ac.insert(Exn { name: path.last().clone(), def: Some(def) });
+      }
+      def::Def::Primitive(_) => {}
+    }
+  }
+}
+
+/// Removes the exceptions caught by the handler pattern from `inner`. A constructor pattern only
+/// catches its exception if its argument, if any, matches every value, like `E _` but not `E "x"`.
+fn handle_pat(cx: &mut Cx<'_>, inner: &mut FxHashSet<Exn>, pat: sml_hir::PatIdx) {
+  let pat = match pat {
+    Some(x) => x,
+    None => return,
+  };
+  match &cx.ars.pat[pat] {
+    sml_hir::Pat::Wild => inner.clear(),
+    sml_hir::Pat::Con(path, argument) => {
+      let defs = cx.info.get_defs(pat.into());
+      if defs.is_empty() {
+        if argument.is_none() && path.prefix().is_empty() {
+          // a variable, which catches everything.
+          let caught = std::mem::take(inner);
+          let def = def::Def::Path(def::Path::Regular(cx.path), pat.into());
+          cx.caught.insert(def, caught);
+        }
+        return;
+      }
+      if !argument.map_or(true, |arg| irrefutable(cx, arg)) {
+        return;
+      }
+      inner.retain(|exn| {
+        if exn.name != *path.last() {
+          return true;
+        }
+        !defs.iter().any(|&d| match d {
+          def::Def::Path(def::Path::BuiltinLib(_), _) => exn.def.is_none(),
+          _ => exn.def == Some(d),
+        })
+      });
+    }
+    sml_hir::Pat::Typed(p, _) | sml_hir::Pat::As(_, p) => handle_pat(cx, inner, *p),
+    sml_hir::Pat::Or(or_pat) => {
+      handle_pat(cx, inner, or_pat.first);
+      for &p in &or_pat.rest {
+        handle_pat(cx, inner, p);
+      }
+    }
+    sml_hir::Pat::SCon(_) | sml_hir::Pat::Record { .. } => {}
+  }
+}
+
+/// Returns whether the pattern matches every value, which is only when it is made of only wildcards,
+/// variables, and records.
+fn irrefutable(cx: &Cx<'_>, pat: sml_hir::PatIdx) -> bool {
+  let pat = match pat {
+    Some(x) => x,
+    None => return true,
+  };
+  match &cx.ars.pat[pat] {
+    sml_hir::Pat::Wild => true,
+    sml_hir::Pat::Con(path, argument) => {
+      argument.is_none() && path.prefix().is_empty() && cx.info.get_defs(pat.into()).is_empty()
+    }
+    sml_hir::Pat::Record { rows, .. } => rows.iter().all(|&(_, p)| irrefutable(cx, p)),
+    sml_hir::Pat::Typed(p, _) | sml_hir::Pat::As(_, p) => irrefutable(cx, *p),
+    sml_hir::Pat::SCon(_) | sml_hir::Pat::Or(_) => false,
+  }
+}
+
+/// Returns the exception raised by the partial function at the path, if it is one.
+fn partial_fn(path: &sml_path::Path) -> Option<&'static str> {
+  PARTIAL_FNS.iter().find_map(|&(structure, name, exn)| {
+    let prefix_ok = match path.prefix() {
+      [] => true,
+      [s] => s.as_str() == structure,
+      _ => false,
+    };
+    (prefix_ok && path.last().as_str() == name).then_some(exn)
+  })
+}
@@ -0,0 +1,220 @@
+//! Showing the difference between two types.
+
+use crate::ty::{Ty, TyData};
+use crate::St;
+
+/// Types whose one-line display is at most this long are short enough to not need a diff.
+const MAX_UNDIFFED_LEN: usize = 40;
+
+/// Replaces the parts of the types that are the same. This is not `...`, since that already
+/// appears in a displayed type as the extra row of an unresolved record type.
+const ELIDED: &str = "…";
+
+/// The difference between two types.
+#[derive(Debug)]
+pub struct TyDiff {
+  /// The wanted type, with the parts that are the same as in `got` elided.
+  pub want: String,
+  /// The gotten type, with the parts that are the same as in `want` elided.
+  pub got: String,
+  /// The byte ranges of the first part that differs, in `want` and `got` respectively.
+  pub first: (std::ops::Range<usize>, std::ops::Range<usize>),
+}
+
+/// Returns the difference between the types.
+///
+/// Returns `None` if both types are short, or if there was nothing to elide.
+#[must_use]
+pub fn get(st: &St, want: Ty, got: Ty) -> Option<TyDiff> {
+  let is_short =
+    |ty: Ty| ty.display(st, config::DiagnosticLines::One).to_string().len() <= MAX_UNDIFFED_LEN;
+  if is_short(want) && is_short(got) {
+    return None;
+  }
+  let want_side = Side::get(st, want, got, Which::Want)?;
+  let got_side = Side::get(st, want, got, Which::Got)?;
+  Some(TyDiff {
+    want: want_side.buf,
+    got: got_side.buf,
+    first: (want_side.first.unwrap_or_default(), got_side.first.unwrap_or_default()),
+  })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Prec {
+  Arrow,
+  Star,
+  App,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Which {
+  Want,
+  Got,
+}
+
+/// One of the two types being diffed.
+struct Side<'a> {
+  st: &'a St,
+  which: Which,
+  buf: String,
+  first: Option<std::ops::Range<usize>>,
+  elided: bool,
+}
+
+impl<'a> Side<'a> {
+  fn get(st: &'a St, want: Ty, got: Ty, which: Which) -> Option<Self> {
+    let mut ret = Side { st, which, buf: String::new(), first: None, elided: false };
+    ret.go(want, got, Prec::Arrow);
+    ret.elided.then_some(ret)
+  }
+
+  fn go(&mut self, want: Ty, got: Ty, prec: Prec) {
+    let (want, want_data) = self.st.tys.canonicalize(want);
+    let (got, got_data) = self.st.tys.canonicalize(got);
+    if same(self.st, want, got) {
+      if is_atomic(&want_data) {
+        self.full(want, &want_data, prec);
+      } else {
+        self.buf.push_str(ELIDED);
+        self.elided = true;
+      }
+      return;
+    }
+    match (&want_data, &got_data) {
+      (TyData::Record(want_rows), TyData::Record(got_rows))
+        if !want_rows.is_empty() && want_rows.keys().eq(got_rows.keys()) =>
+      {
+        if is_tuple(want_rows) {
+          let needs_parens = prec > Prec::Star;
+          if needs_parens {
+            self.buf.push('(');
+          }
+          for (idx, (&want, &got)) in want_rows.values().zip(got_rows.values()).enumerate() {
+            if idx != 0 {
+              self.buf.push_str(" * ");
+            }
+            self.go(want, got, Prec::App);
+          }
+          if needs_parens {
+            self.buf.push(')');
+          }
+        } else {
+          self.buf.push_str("{ ");
+          for (idx, ((lab, &want), &got)) in want_rows.iter().zip(got_rows.values()).enumerate() {
+            if idx != 0 {
+              self.buf.push_str(", ");
+            }
+            self.buf.push_str(&format!("{lab} : "));
+            self.go(want, got, Prec::Arrow);
+          }
+          self.buf.push_str(" }");
+        }
+      }
+      (TyData::Con(want_data), TyData::Con(got_data)) if want_data.sym == got_data.sym => {
+        match want_data.args.len() {
+          0 => {}
+          1 => {
+            self.go(want_data.args[0], got_data.args[0], Prec::App);
+            self.buf.push(' ');
+          }
+          _ => {
+            self.buf.push('(');
+            for (idx, (&want, &got)) in want_data.args.iter().zip(got_data.args.iter()).enumerate()
+            {
+              if idx != 0 {
+                self.buf.push_str(", ");
+              }
+              self.go(want, got, Prec::Arrow);
+            }
+            self.buf.push_str(") ");
+          }
+        }
+        self.buf.push_str(&want_data.sym.display(&self.st.syms).to_string());
+      }
+      (TyData::Fn(want_data), TyData::Fn(got_data)) => {
+        let needs_parens = prec > Prec::Arrow;
+        if needs_parens {
+          self.buf.push('(');
+        }
+        self.go(want_data.param, got_data.param, Prec::Star);
+        self.buf.push_str(" -> ");
+        self.go(want_data.res, got_data.res, Prec::Arrow);
+        if needs_parens {
+          self.buf.push(')');
+        }
+      }
+      _ => {
+        let start = self.buf.len();
+        match self.which {
+          Which::Want => self.full(want, &want_data, prec),
+          Which::Got => self.full(got, &got_data, prec),
+        }
+        if self.first.is_none() {
+          self.first = Some(start..self.buf.len());
+        }
+      }
+    }
+  }
+
+  /// Writes the whole type, without eliding anything.
+  fn full(&mut self, ty: Ty, data: &TyData, prec: Prec) {
+    let needs_parens = match data {
+      TyData::Fn(_) => prec > Prec::Arrow,
+      TyData::Record(rows) => is_tuple(rows) && prec > Prec::Star,
+      _ => false,
+    };
+    if needs_parens {
+      self.buf.push('(');
+    }
+    self.buf.push_str(&ty.display(self.st, config::DiagnosticLines::One).to_string());
+    if needs_parens {
+      self.buf.push(')');
+    }
+  }
+}
+
+/// Returns whether the types are the same. Unlike `==`, this looks through solved meta vars in the
+/// sub-terms of the types.
+fn same(st: &St, a: Ty, b: Ty) -> bool {
+  let (a, a_data) = st.tys.canonicalize(a);
+  let (b, b_data) = st.tys.canonicalize(b);
+  if a == b {
+    return true;
+  }
+  match (a_data, b_data) {
+    (TyData::Record(a_rows), TyData::Record(b_rows)) => {
+      a_rows.len() == b_rows.len()
+        && a_rows
+          .iter()
+          .zip(b_rows.iter())
+          .all(|((a_lab, &a), (b_lab, &b))| a_lab == b_lab && same(st, a, b))
+    }
+    (TyData::Con(a_data), TyData::Con(b_data)) => {
+      a_data.sym == b_data.sym
+        && a_data.args.iter().zip(b_data.args.iter()).all(|(&a, &b)| same(st, a, b))
+    }
+    (TyData::Fn(a_data), TyData::Fn(b_data)) => {
+      same(st, a_data.param, b_data.param) && same(st, a_data.res, b_data.res)
+    }
+    _ => false,
+  }
+}
+
+/// Returns whether this type is so small that it's not worth eliding.
+fn is_atomic(data: &TyData) -> bool {
+  match data {
+    TyData::Record(rows) => rows.is_empty(),
+    TyData::Con(data) => data.args.is_empty(),
+    TyData::Fn(_) => false,
+    TyData::None
+    | TyData::BoundVar(_)
+    | TyData::UnsolvedMetaVar(_)
+    | TyData::GeneralizedMetaVar(_)
+    | TyData::FixedVar(_) => true,
+  }
+}
+
+fn is_tuple(rows: &crate::ty::RecordData) -> bool {
+  rows.len() > 1 && rows.keys().enumerate().all(|(idx, lab)| sml_hir::Lab::tuple(idx) == *lab)
+}
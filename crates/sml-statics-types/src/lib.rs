@@ -8,6 +8,7 @@
 mod data;
 
 pub mod def;
+pub mod diff;
 pub mod disallow;
 pub mod display;
 pub mod env;
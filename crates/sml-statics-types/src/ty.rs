@@ -2,7 +2,8 @@
 
 #![allow(clippy::module_name_repetitions)]
 
-use crate::{data, fmt_util, overload, sym::Sym};
+use crate::{data, def, fmt_util, overload, sym::Sym};
+use fast_hash::FxHashSet;
 use std::collections::BTreeMap;
 
 /// Storage of all the semantic types.
@@ -12,6 +13,8 @@ use std::collections::BTreeMap;
 pub struct Tys {
   meta_var_rank: u16,
   pub(crate) meta_var_data: Vec<MetaTyVarData>,
+  /// where each meta var was solved, if it was and we know.
+  meta_var_src: Vec<Option<def::Def>>,
   pub(crate) fixed_var_data: Vec<FixedTyVarData>,
   record: data::Map<RecordData>,
   con: data::Map<ConData>,
@@ -23,6 +26,7 @@ impl Default for Tys {
     let mut ret = Self {
       meta_var_rank: 0,
       meta_var_data: Vec::new(),
+      meta_var_src: Vec::new(),
       fixed_var_data: Vec::new(),
       record: data::Map::default(),
       con: data::Map::default(),
@@ -70,6 +74,7 @@ impl Tys {
     };
     let ret = Ty { kind: TyKind::MetaVar, idx: idx::Idx::new(self.meta_var_data.len()) };
     self.meta_var_data.push(MetaTyVarData::Unsolved(UnsolvedMetaTyVarData { rank, kind }));
+    self.meta_var_src.push(None);
     ret
   }
 
@@ -191,6 +196,90 @@ impl Tys {
     }
   }
 
+  /// Calls `f` for each generalized meta var in `ty`. Unlike unsolved meta vars, these may be
+  /// displayed as type variables, like `'a`.
+  pub fn generalized_meta_vars<F>(&self, ty: Ty, f: &mut F)
+  where
+    F: FnMut(Ty),
+  {
+    match ty.kind {
+      // interesting case
+      TyKind::MetaVar => match &self.meta_var_data[ty.idx.to_usize()] {
+        // recur for solved meta vars
+        MetaTyVarData::Solved(new_ty) => self.generalized_meta_vars(*new_ty, f),
+        MetaTyVarData::Unsolved(_) => {}
+        MetaTyVarData::Generalized(_) => f(ty),
+      },
+      // trivial base cases
+      TyKind::None | TyKind::BoundVar | TyKind::FixedVar => {}
+      // recursive cases
+      TyKind::Record => {
+        for &new_ty in self.record.get_data(ty.idx).values() {
+          self.generalized_meta_vars(new_ty, f);
+        }
+      }
+      TyKind::Con => {
+        for &new_ty in &self.con.get_data(ty.idx).args {
+          self.generalized_meta_vars(new_ty, f);
+        }
+      }
+      TyKind::Fn => {
+        let data = self.fn_.get_data(ty.idx);
+        self.generalized_meta_vars(data.param, f);
+        self.generalized_meta_vars(data.res, f);
+      }
+    }
+  }
+
+  /// Notes that the meta var was solved because of the thing defined at `src`.
+  pub(crate) fn set_meta_var_src(&mut self, mv: idx::Idx, src: def::Def) {
+    self.meta_var_src[mv.to_usize()] = Some(src);
+  }
+
+  /// Returns where the meta vars in `ty` were solved, along with what they were solved to, from the
+  /// outside in. Each meta var appears at most once.
+  #[must_use]
+  pub fn meta_var_srcs(&self, ty: Ty) -> Vec<(def::Def, Ty)> {
+    let mut ret = Vec::<(def::Def, Ty)>::new();
+    self.meta_var_srcs_(ty, &mut FxHashSet::default(), &mut ret);
+    ret
+  }
+
+  fn meta_var_srcs_(&self, ty: Ty, seen: &mut FxHashSet<idx::Idx>, ac: &mut Vec<(def::Def, Ty)>) {
+    match ty.kind {
+      // interesting case
+      TyKind::MetaVar => {
+        if !seen.insert(ty.idx) {
+          return;
+        }
+        if let MetaTyVarData::Solved(new_ty) = &self.meta_var_data[ty.idx.to_usize()] {
+          if let Some(src) = self.meta_var_src[ty.idx.to_usize()] {
+            ac.push((src, *new_ty));
+          }
+          self.meta_var_srcs_(*new_ty, seen, ac);
+        }
+      }
+      // trivial base cases
+      TyKind::None | TyKind::BoundVar | TyKind::FixedVar => {}
+      // recursive cases
+      TyKind::Record => {
+        for &new_ty in self.record.get_data(ty.idx).values() {
+          self.meta_var_srcs_(new_ty, seen, ac);
+        }
+      }
+      TyKind::Con => {
+        for &new_ty in &self.con.get_data(ty.idx).args {
+          self.meta_var_srcs_(new_ty, seen, ac);
+        }
+      }
+      TyKind::Fn => {
+        let data = self.fn_.get_data(ty.idx);
+        self.meta_var_srcs_(data.param, seen, ac);
+        self.meta_var_srcs_(data.res, seen, ac);
+      }
+    }
+  }
+
   /// Returns in O(1) time.
   pub(crate) fn is_generalizable(&self, rank: MetaTyVarRank) -> bool {
     match rank {
@@ -273,6 +362,12 @@ pub enum TyData {
 pub struct BoundTyVar(idx::Idx);
 
 impl BoundTyVar {
+  /// Returns the index of this in its binder.
+  #[must_use]
+  pub fn to_usize(self) -> usize {
+    self.0.to_usize()
+  }
+
   pub(crate) fn index_into<T>(self, xs: &[T]) -> &T {
     &xs[self.0.to_usize()]
   }
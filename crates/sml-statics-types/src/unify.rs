@@ -5,7 +5,7 @@ use crate::ty::{
   MetaTyVarData, RecordData, Ty, TyData, TyKind, TyVarKind, Tys, UnsolvedMetaTyVarData,
   UnsolvedMetaTyVarKind,
 };
-use crate::{equality, mode::Mode, overload};
+use crate::{def, equality, mode::Mode, overload};
 
 /// An error when unifying.
 #[derive(Debug)]
@@ -51,6 +51,9 @@ impl From<Incompatible> for Error {
 
 /// Unifies two types, updating `tys` as necessary to record how.
 ///
+/// If `src` is given, meta vars solved by this unification remember it as the reason they were
+/// solved.
+///
 /// # Errors
 ///
 /// If the types couldn't be unified.
@@ -58,7 +61,13 @@ impl From<Incompatible> for Error {
 /// # Panics
 ///
 /// If the types contain bound variables.
-pub fn unify(tys: &mut Tys, syms: &Syms, want: Ty, got: Ty) -> Result<(), Error> {
+pub fn unify(
+  tys: &mut Tys,
+  syms: &Syms,
+  src: Option<def::Def>,
+  want: Ty,
+  got: Ty,
+) -> Result<(), Error> {
   let (want, want_data) = tys.canonicalize(want);
   let (got, got_data) = tys.canonicalize(got);
   // if `Ty`s are `==`, they are semantically the same type, because of interning.
@@ -70,8 +79,8 @@ pub fn unify(tys: &mut Tys, syms: &Syms, want: Ty, got: Ty) -> Result<(), Error>
     (TyData::BoundVar(_), _) | (_, TyData::BoundVar(_)) => {
       unreachable!("bound vars should be instantiated")
     }
-    (TyData::UnsolvedMetaVar(umv), _) => unify_mv(tys, syms, want, umv, got),
-    (_, TyData::UnsolvedMetaVar(umv)) => unify_mv(tys, syms, got, umv, want),
+    (TyData::UnsolvedMetaVar(umv), _) => unify_mv(tys, syms, src, want, umv, got),
+    (_, TyData::UnsolvedMetaVar(umv)) => unify_mv(tys, syms, src, got, umv, want),
     (TyData::FixedVar(want), TyData::FixedVar(got)) => {
       // already checked not equal
       Err(Incompatible::FixedTyVar(want.ty_var, got.ty_var).into())
@@ -80,7 +89,7 @@ pub fn unify(tys: &mut Tys, syms: &Syms, want: Ty, got: Ty) -> Result<(), Error>
       for (lab, want_ty) in want_rows {
         match got_rows.remove(&lab) {
           None => return Err(Incompatible::MissingRow(lab.clone()).into()),
-          Some(got_ty) => unify(tys, syms, want_ty, got_ty)?,
+          Some(got_ty) => unify(tys, syms, src, want_ty, got_ty)?,
         }
       }
       if got_rows.is_empty() {
@@ -95,13 +104,13 @@ pub fn unify(tys: &mut Tys, syms: &Syms, want: Ty, got: Ty) -> Result<(), Error>
       }
       assert_eq!(want.args.len(), got.args.len());
       for (&want, &got) in want.args.iter().zip(got.args.iter()) {
-        unify(tys, syms, want, got)?;
+        unify(tys, syms, src, want, got)?;
       }
       Ok(())
     }
     (TyData::Fn(want), TyData::Fn(got)) => {
-      unify(tys, syms, want.param, got.param)?;
-      unify(tys, syms, want.res, got.res)
+      unify(tys, syms, src, want.param, got.param)?;
+      unify(tys, syms, src, want.res, got.res)
     }
     _ => Err(Incompatible::HeadMismatch(want, got).into()),
   }
@@ -111,6 +120,7 @@ pub fn unify(tys: &mut Tys, syms: &Syms, want: Ty, got: Ty) -> Result<(), Error>
 fn unify_mv(
   tys: &mut Tys,
   syms: &Syms,
+  src: Option<def::Def>,
   mv: Ty,
   umv: UnsolvedMetaTyVarData,
   mut ty: Ty,
@@ -134,7 +144,7 @@ fn unify_mv(
     Err(()) => return Err(Error::Circularity(Circularity { meta_var: mv, ty })),
   }
   // check the solution is allowed.
-  check_mv_solution(tys, syms, mv, umv.kind, ty)?;
+  check_mv_solution(tys, syms, src, mv, umv.kind, ty)?;
   // solve mv to ty.
   //
   // there may be a chain: e.g. mv could already have been solved to another meta var was solved to
@@ -153,6 +163,9 @@ fn unify_mv(
       },
       MetaTyVarData::Unsolved(_) => {
         *cur_data = MetaTyVarData::Solved(ty);
+        if let Some(src) = src {
+          tys.set_meta_var_src(cur, src);
+        }
         break;
       }
       MetaTyVarData::Generalized(_) => unreachable!("should not try to unify generalized mv"),
@@ -206,6 +219,7 @@ fn adjust_mv_ranks(tys: &mut Tys, mv: Ty, umv: &UnsolvedMetaTyVarData, ty: Ty) -
 fn check_mv_solution(
   tys: &mut Tys,
   syms: &Syms,
+  src: Option<def::Def>,
   mv: Ty,
   mv_kind: UnsolvedMetaTyVarKind,
   ty: Ty,
@@ -263,14 +277,14 @@ fn check_mv_solution(
         for (lab, want) in want.rows {
           match got_rows.remove(&lab) {
             None => return Err(Incompatible::UnresolvedRecordMissingRow(lab).into()),
-            Some(got) => unify(tys, syms, want, got)?,
+            Some(got) => unify(tys, syms, src, want, got)?,
           }
         }
         Ok(())
       }
       // ty is an unsolved meta var as well. it must now be a unresolved record meta var.
       TyData::UnsolvedMetaVar(ty_unsolved) => {
-        want.rows = new_solved_rows(tys, syms, ty_unsolved.kind, mv, want.rows)?;
+        want.rows = new_solved_rows(tys, syms, src, ty_unsolved.kind, mv, want.rows)?;
         tys.unsolved_meta_var(ty).kind = UnsolvedMetaTyVarKind::UnresolvedRecord(want);
         Ok(())
       }
@@ -310,6 +324,7 @@ fn new_overload(
 fn new_solved_rows(
   tys: &mut Tys,
   syms: &Syms,
+  src: Option<def::Def>,
   kind: UnsolvedMetaTyVarKind,
   mv: Ty,
   mut rows: RecordData,
@@ -332,7 +347,7 @@ fn new_solved_rows(
     UnsolvedMetaTyVarKind::UnresolvedRecord(got) => {
       for (lab, got) in got.rows {
         if let Some(&want) = rows.get(&lab) {
-          unify(tys, syms, want, got)?;
+          unify(tys, syms, src, want, got)?;
         }
         rows.insert(lab, got);
       }
@@ -1,13 +1,17 @@
 //! Bases. (The plural of "basis".)
 
 use crate::get_env::get_mut_env;
+use chain_map::ChainMap;
 use fast_hash::FxHashMap;
 use sml_statics_types::disallow::{self, Disallow};
 use sml_statics_types::env::{Cx, Env, FunEnv, SigEnv, StrEnv};
 use sml_statics_types::info::{IdStatus, TyEnv, TyInfo, ValEnv, ValInfo};
 use sml_statics_types::sym::{Equality, Sym, SymTyInfo, SymValEnv, Syms};
 use sml_statics_types::ty::{BoundTyVar, BoundTyVarData, RecordData, Ty, TyScheme, TyVarKind, Tys};
-use sml_statics_types::{def::Primitive, item::Item, overload};
+use sml_statics_types::{def, def::Primitive, item::Item, overload};
+
+/// A mapping from deprecated items to the reasons they were deprecated, which may be empty.
+pub type Deprecated = ChainMap<def::Def, String>;
 
 /// A basis.
 #[derive(Debug, Default, Clone)]
@@ -18,6 +22,8 @@ pub struct Bs {
   pub sig_env: SigEnv,
   /// The functor env.
   pub fun_env: FunEnv,
+  /// The deprecated items.
+  pub deprecated: Deprecated,
 }
 
 impl Bs {
@@ -30,6 +36,7 @@ impl Bs {
     self.env.append(&mut other.env);
     self.sig_env.append(&mut other.sig_env);
     self.fun_env.append(&mut other.fun_env);
+    self.deprecated.append_shared(&other.deprecated);
   }
 
   /// Consolidates internal memory for this, so that it will be faster to clone next time.
@@ -37,6 +44,7 @@ impl Bs {
     self.env.consolidate();
     self.sig_env.consolidate();
     self.fun_env.consolidate();
+    self.deprecated.consolidate();
   }
 
   /// Adds the item named `other_name` from `other` into `self` with the name `name`, or
@@ -48,6 +56,9 @@ impl Bs {
     other: &Self,
     other_name: &str_util::Name,
   ) -> bool {
+    // deprecations are by def, not name, so keep all of them. they are shared, so this doesn't
+    // copy them, and adding many items from the same basis only adds them once.
+    self.deprecated.append_shared(&other.deprecated);
     match ns {
       sml_namespace::Module::Structure => match other.env.str_env.get(other_name) {
         Some(env) => {
@@ -240,6 +251,7 @@ pub fn minimal() -> (sml_statics_types::St, Bs) {
     fun_env: FunEnv::default(),
     sig_env: SigEnv::default(),
     env: Env { str_env: StrEnv::default(), ty_env, val_env, def: None, disallow: None },
+    deprecated: Deprecated::default(),
   };
   (sml_statics_types::St { syms, tys }, bs)
 }
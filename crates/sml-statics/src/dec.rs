@@ -2,7 +2,7 @@
 
 use crate::error::ErrorKind;
 use crate::get_env::{get_env, get_env_raw, get_ty_info, get_val_info};
-use crate::util::{check_name, ins_check_name, ins_no_dupe};
+use crate::util::{check_name, ins_check_name, ins_no_dupe, HasDef};
 use crate::{config::Cfg, exp, pat, pat_match::Pat, st::St, ty, unify::unify};
 use fast_hash::{FxHashMap, FxHashSet};
 use sml_statics_types::env::{Cx, Env};
@@ -151,7 +151,7 @@ fn get_one(
           env.ty_env.insert(name.clone(), ty_info.clone());
           env.val_env.append(&mut ty_info.val_env.clone());
         }
-        Err(e) => st.err(dec, e.into()),
+        Err(e) => st.err(dec, e.into_error_kind(st.info.mode)),
       }
     }
     // @def(19)
@@ -188,7 +188,7 @@ fn get_one(
             let val_info = match val_info.val {
               Ok(x) => x,
               Err(e) => {
-                st.err(dec, e.into());
+                st.err(dec, e.into_error_kind(st.info.mode));
                 return;
               }
             };
@@ -230,7 +230,7 @@ fn get_one(
         }
         match got_env.val {
           Ok(got_env) => env.append(&mut got_env.clone()),
-          Err(e) => st.err(dec, e.into()),
+          Err(e) => st.err(dec, e.into_error_kind(st.info.mode)),
         }
       }
     }
@@ -271,7 +271,7 @@ pub(crate) fn add_fixed_ty_vars(
   for ty_var in ty_vars.iter() {
     let fv = st.syms_tys.tys.fixed_var(ty_var.clone(), src);
     if cx.fixed.insert(ty_var.clone(), fv).is_some() {
-      let e = ErrorKind::Duplicate(Item::TyVar, ty_var.as_name().clone());
+      let e = ErrorKind::Duplicate(Item::TyVar, ty_var.as_name().clone(), None);
       st.err(idx, e);
     }
     ret.push(fv);
@@ -375,7 +375,7 @@ pub(crate) fn get_dat_binds(
         _ => unreachable!("not a fixed var"),
       };
       if cx.fixed.insert(ty_var.clone(), fv).is_some() {
-        let e = ErrorKind::Duplicate(Item::TyVar, ty_var.as_name().clone());
+        let e = ErrorKind::Duplicate(Item::TyVar, ty_var.as_name().clone(), None);
         st.err(idx, e);
       }
     }
@@ -397,8 +397,9 @@ pub(crate) fn get_dat_binds(
         disallow: None,
       };
       let e = check_name(&con_bind.name).or_else(|| {
+        let prev = val_env.get(&con_bind.name).and_then(HasDef::def);
         (!val_env.insert(con_bind.name.clone(), vi))
-          .then(|| ErrorKind::Duplicate(Item::Val, con_bind.name.clone()))
+          .then(|| ErrorKind::Duplicate(Item::Val, con_bind.name.clone(), prev))
       });
       if let Some(e) = e {
         st.err(idx, e);
@@ -0,0 +1,29 @@
+//! Reporting uses of deprecated items.
+
+use crate::basis::Deprecated;
+use crate::error::ErrorKind;
+use crate::st::St;
+use fast_hash::FxHashSet;
+
+/// Reports every index in this file whose def is deprecated, except the definitions themselves.
+pub(crate) fn get(st: &mut St<'_>, deprecated: &Deprecated) {
+  if deprecated.is_empty() || st.info.mode.is_path_order() {
+    return;
+  }
+  let mut seen = FxHashSet::<sml_hir::Idx>::default();
+  let uses: Vec<_> = st
+    .info
+    .entries
+    .defs
+    .iter()
+    .filter_map(|(idx, def)| {
+      let reason = deprecated.get(&def)?;
+      (st.def(idx) != Some(def)).then(|| (idx, reason.clone()))
+    })
+    .collect();
+  for (idx, reason) in uses {
+    if seen.insert(idx) {
+      st.err(idx, ErrorKind::Deprecated(reason));
+    }
+  }
+}
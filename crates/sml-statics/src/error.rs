@@ -1,24 +1,28 @@
 //! Errors.
 
 mod non_exhaustive;
-mod suggestion;
+pub(crate) mod suggestion;
 
 use crate::pat_match::Pat;
 use diagnostic::{Code, Severity};
 use sml_statics_types::display::record_meta_var;
 use sml_statics_types::ty::{RecordData, Ty, TyScheme};
 use sml_statics_types::unify::{Circularity, Incompatible};
-use sml_statics_types::{disallow::Disallow, item::Item};
+use sml_statics_types::{def, disallow::Disallow, item::Item};
 use std::fmt;
 
 #[derive(Debug)]
 pub(crate) enum ErrorKind {
   /// must be first here, but have the highest error code
   Unsupported(&'static str),
-  Undefined(Item, str_util::Name),
-  Duplicate(Item, str_util::Name),
-  Missing(Item, str_util::Name),
-  Extra(Item, str_util::Name),
+  /// The last argument is the similar names in scope.
+  Undefined(Item, str_util::Name, Vec<suggestion::Similar>),
+  /// The last argument is the def of the previous item with the name.
+  Duplicate(Item, str_util::Name, Option<def::Def>),
+  /// The last argument is the def of the spec requiring the item.
+  Missing(Item, str_util::Name, Option<def::Def>),
+  /// The last argument is the def of the spec the item is not present in.
+  Extra(Item, str_util::Name, Option<def::Def>),
   Circularity(Circularity),
   IncompatibleTys(Incompatible, Ty, Ty),
   DuplicateLab(sml_hir::Lab),
@@ -56,6 +60,8 @@ pub(crate) enum ErrorKind {
   UnreachableHandle,
   DecWithoutEffect,
   Disallowed(Item, Disallow, str_util::Name),
+  /// The argument is the reason, which may be empty.
+  Deprecated(String),
 }
 
 struct ErrorKindDisplay<'a> {
@@ -68,16 +74,22 @@ impl fmt::Display for ErrorKindDisplay<'_> {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self.kind {
       ErrorKind::Unsupported(s) => write!(f, "unsupported: {s}"),
-      ErrorKind::Undefined(item, name) => {
+      ErrorKind::Undefined(item, name, similar) => {
         write!(f, "undefined {item}: `{name}`")?;
-        if let Some(sug) = suggestion::get(name.as_str()) {
+        let sug =
+          suggestion::get(name.as_str()).or_else(|| similar.first().map(|(name, _)| name.as_str()));
+        if let Some(sug) = sug {
           write!(f, " (did you mean `{sug}`?)")?;
         }
         Ok(())
       }
-      ErrorKind::Duplicate(item, name) => write!(f, "duplicate {item}: `{name}`"),
-      ErrorKind::Missing(item, name) => write!(f, "missing {item} required by signature: `{name}`"),
-      ErrorKind::Extra(item, name) => write!(f, "extra {item} not present in signature: `{name}`"),
+      ErrorKind::Duplicate(item, name, _) => write!(f, "duplicate {item}: `{name}`"),
+      ErrorKind::Missing(item, name, _) => {
+        write!(f, "missing {item} required by signature: `{name}`")
+      }
+      ErrorKind::Extra(item, name, _) => {
+        write!(f, "extra {item} not present in signature: `{name}`")
+      }
       ErrorKind::Circularity(circ) => {
         let mv = circ.meta_var.display(self.st, self.lines);
         let ty = circ.ty.display(self.st, self.lines);
@@ -86,6 +98,19 @@ impl fmt::Display for ErrorKindDisplay<'_> {
       ErrorKind::IncompatibleTys(reason, want, got) => {
         let reason = reason.display(self.st, self.lines);
         write!(f, "incompatible types: {reason}")?;
+        if let Some(diff) = sml_statics_types::diff::get(self.st, *want, *got) {
+          return match self.lines {
+            config::DiagnosticLines::One => {
+              write!(f, ": expected `{}`, found `{}`", diff.want, diff.got)
+            }
+            config::DiagnosticLines::Many => {
+              writeln!(f, "\n  expected `{}`", diff.want)?;
+              writeln!(f, "{}", Carets { s: &diff.want, range: diff.first.0 })?;
+              writeln!(f, "     found `{}`", diff.got)?;
+              write!(f, "{}", Carets { s: &diff.got, range: diff.first.1 })
+            }
+          };
+        }
         let want = want.display(self.st, self.lines);
         let got = got.display(self.st, self.lines);
         match self.lines {
@@ -169,6 +194,13 @@ impl fmt::Display for ErrorKindDisplay<'_> {
       ErrorKind::UnreachableHandle => f.write_str("unreachable `handle`"),
       ErrorKind::DecWithoutEffect => f.write_str("declaration with no effect"),
       ErrorKind::Disallowed(item, d, name) => write!(f, "{d} disallowed {item}: `{name}`"),
+      ErrorKind::Deprecated(reason) => {
+        f.write_str("use of deprecated item")?;
+        if !reason.is_empty() {
+          write!(f, ": {reason}")?;
+        }
+        Ok(())
+      }
     }
   }
 }
@@ -212,11 +244,57 @@ impl fmt::Display for AppendArg {
   }
 }
 
+/// How a location is related to an error.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Related {
+  PreviouslyDefined,
+  RequiredBySpec,
+  Spec,
+  Annotation,
+}
+
+impl fmt::Display for Related {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Related::PreviouslyDefined => f.write_str("previously defined here"),
+      Related::RequiredBySpec => f.write_str("required by this specification"),
+      Related::Spec => f.write_str("specified here"),
+      Related::Annotation => f.write_str("expected type comes from this annotation"),
+    }
+  }
+}
+
+/// Points at the range in a type that was written after `expected` or `found`.
+struct Carets<'a> {
+  s: &'a str,
+  range: std::ops::Range<usize>,
+}
+
+impl fmt::Display for Carets<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    // the width of `  expected `` and `     found ``.
+    let start = 12 + self.s[..self.range.start].chars().count();
+    let len = self.s[self.range.clone()].chars().count().max(1);
+    write!(f, "{}{}", " ".repeat(start), "^".repeat(len))
+  }
+}
+
+/// Extra information about an error, which editors may use to render it differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tag {
+  /// The error is about unused or unreachable code.
+  Unnecessary,
+  /// The error is about a use of a deprecated item.
+  Deprecated,
+}
+
 /// A statics error.
 #[derive(Debug)]
 pub struct Error {
   pub(crate) idx: sml_hir::Idx,
   pub(crate) kind: ErrorKind,
+  /// Locations related to this error, other than the ones in the kind.
+  pub(crate) related: Vec<(def::Def, Related)>,
 }
 
 impl Error {
@@ -226,6 +304,57 @@ impl Error {
     self.idx
   }
 
+  /// Returns the kind and name of the item, if this is an error about an undefined item.
+  #[must_use]
+  pub fn undefined(&self) -> Option<(Item, &str_util::Name)> {
+    match &self.kind {
+      ErrorKind::Undefined(item, name, _) => Some((*item, name)),
+      _ => None,
+    }
+  }
+
+  /// Returns the names in scope similar to the undefined name, most similar first, and where they
+  /// were defined, if known. Empty if this is not an error about an undefined item.
+  #[must_use]
+  pub fn similar(&self) -> &[(str_util::Name, Option<sml_statics_types::def::Def>)] {
+    match &self.kind {
+      ErrorKind::Undefined(_, _, similar) => similar.as_slice(),
+      _ => &[],
+    }
+  }
+
+  /// Returns the kind and name of the item, if this is an error about an item required by a
+  /// signature but missing from a structure.
+  #[must_use]
+  pub fn missing(&self) -> Option<(Item, &str_util::Name)> {
+    match &self.kind {
+      ErrorKind::Missing(item, name, _) => Some((*item, name)),
+      _ => None,
+    }
+  }
+
+  /// Returns other locations related to this error, with messages about how they are related. A
+  /// location of `None` means the location of this error itself.
+  #[must_use]
+  pub fn related(&self) -> Vec<(Option<def::Def>, String)> {
+    let mut ret = Vec::<(Option<def::Def>, String)>::new();
+    match &self.kind {
+      ErrorKind::Undefined(_, _, similar) => {
+        ret.extend(similar.iter().map(|(name, def)| (*def, format!("similar name: `{name}`"))));
+      }
+      ErrorKind::Duplicate(_, _, Some(def)) => {
+        ret.push((Some(*def), Related::PreviouslyDefined.to_string()));
+      }
+      ErrorKind::Missing(_, _, Some(def)) => {
+        ret.push((Some(*def), Related::RequiredBySpec.to_string()));
+      }
+      ErrorKind::Extra(_, _, Some(def)) => ret.push((Some(*def), Related::Spec.to_string())),
+      _ => {}
+    }
+    ret.extend(self.related.iter().map(|&(def, related)| (Some(def), related.to_string())));
+    ret
+  }
+
   /// Returns a value that displays the message.
   #[must_use]
   pub fn display<'a>(
@@ -245,10 +374,10 @@ impl Error {
   pub fn code(&self) -> Code {
     match self.kind {
       ErrorKind::Unsupported(_) => Code::n(5999),
-      ErrorKind::Undefined(_, _) => Code::n(5001),
-      ErrorKind::Duplicate(_, _) => Code::n(5002),
-      ErrorKind::Missing(_, _) => Code::n(5003),
-      ErrorKind::Extra(_, _) => Code::n(5004),
+      ErrorKind::Undefined(_, _, _) => Code::n(5001),
+      ErrorKind::Duplicate(_, _, _) => Code::n(5002),
+      ErrorKind::Missing(_, _, _) => Code::n(5003),
+      ErrorKind::Extra(_, _, _) => Code::n(5004),
       ErrorKind::Circularity(_) => Code::n(5005),
       ErrorKind::IncompatibleTys(_, _, _) => Code::n(5006),
       ErrorKind::DuplicateLab(_) => Code::n(5008),
@@ -285,6 +414,7 @@ impl Error {
       ErrorKind::UnreachableHandle => Code::n(5039),
       ErrorKind::DecWithoutEffect => Code::n(5040),
       ErrorKind::Disallowed(_, _, _) => Code::n(5041),
+      ErrorKind::Deprecated(_) => Code::n(5042),
     }
   }
 
@@ -300,8 +430,21 @@ impl Error {
       | ErrorKind::AppFn
       | ErrorKind::Use(_)
       | ErrorKind::UnreachableHandle
-      | ErrorKind::DecWithoutEffect => Severity::Warning,
+      | ErrorKind::DecWithoutEffect
+      | ErrorKind::Deprecated(_) => Severity::Warning,
       _ => Severity::Error,
     }
   }
+
+  /// Returns the tag for this, if any.
+  #[must_use]
+  pub fn tag(&self) -> Option<Tag> {
+    match self.kind {
+      ErrorKind::UnreachablePattern | ErrorKind::Unused(_, _) | ErrorKind::UnreachableHandle => {
+        Some(Tag::Unnecessary)
+      }
+      ErrorKind::Deprecated(_) => Some(Tag::Deprecated),
+      _ => None,
+    }
+  }
 }
@@ -1,4 +1,25 @@
-//! See [`get`].
+//! Suggestions for undefined names. See [`get`] and [`env`].
+
+use sml_statics_types::{def, env::Env, item::Item, mode::Mode};
+
+/// The max number of similar names to suggest.
+const MAX_SIMILAR: usize = 3;
+
+/// A name in scope similar to an undefined name, and where it was defined, if known.
+pub(crate) type Similar = (str_util::Name, Option<def::Def>);
+
+/// Returns whether errors may be shown to the user in this mode, and so whether it's worth looking
+/// for similar names to suggest.
+///
+/// In the path order mode, we only check whether there were any errors at all, and we check every
+/// file many times, so the work would be both wasted and repeated. In the built-in library mode,
+/// any error is a bug in Millet, not a typo to suggest a fix for.
+pub(crate) fn is_shown(mode: Mode) -> bool {
+  match mode {
+    Mode::Regular(_) | Mode::Dynamics => true,
+    Mode::BuiltinLib(_) | Mode::PathOrder => false,
+  }
+}
 
 /// Suggests a bit of syntax that may be similar to the input.
 pub(crate) fn get(s: &str) -> Option<&'static str> {
@@ -40,3 +61,68 @@ pub(crate) fn get(s: &str) -> Option<&'static str> {
   };
   Some(ret)
 }
+
+/// Returns the names of the item in the env similar to the name, most similar first.
+pub(crate) fn env(env: &Env, item: Item, name: &str_util::Name) -> Vec<Similar> {
+  match item {
+    Item::Val => similar(
+      name,
+      env
+        .val_env
+        .iter()
+        .filter(|(_, x)| x.disallow.is_none())
+        .map(|(n, x)| (n, x.defs.iter().next().copied())),
+    ),
+    Item::Ty => similar(
+      name,
+      env.ty_env.iter().filter(|(_, x)| x.disallow.is_none()).map(|(n, x)| (n, x.def)),
+    ),
+    Item::Struct => similar(
+      name,
+      env.str_env.iter().filter(|(_, x)| x.disallow.is_none()).map(|(n, x)| (n, x.def)),
+    ),
+    Item::TyVar | Item::Sig | Item::Functor => Vec::new(),
+  }
+}
+
+/// Returns the candidates similar to the name, most similar first.
+pub(crate) fn similar<'a, I>(name: &str_util::Name, candidates: I) -> Vec<Similar>
+where
+  I: IntoIterator<Item = (&'a str_util::Name, Option<def::Def>)>,
+{
+  let name = name.as_str();
+  // allow about one edit for every three chars.
+  let max = name.chars().count() / 3;
+  let mut ret: Vec<_> = candidates
+    .into_iter()
+    .filter(|(cand, _)| cand.as_str() != name)
+    .filter_map(|(cand, def)| Some((distance(name, cand.as_str(), max)?, cand, def)))
+    .collect();
+  ret.sort_unstable_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.as_str().cmp(b.1.as_str())));
+  ret.into_iter().take(MAX_SIMILAR).map(|(_, cand, def)| (cand.clone(), def)).collect()
+}
+
+/// Returns the edit distance between the strings, if it is at most `max`. Changing only the case of
+/// a char is free.
+fn distance(a: &str, b: &str, max: usize) -> Option<usize> {
+  let a: Vec<_> = a.chars().collect();
+  let b: Vec<_> = b.chars().collect();
+  if a.len().abs_diff(b.len()) > max {
+    return None;
+  }
+  let mut prev: Vec<usize> = (0..=b.len()).collect();
+  let mut cur = vec![0usize; b.len() + 1];
+  for (i, &ac) in a.iter().enumerate() {
+    cur[0] = i + 1;
+    for (j, &bc) in b.iter().enumerate() {
+      let sub = usize::from(!ac.eq_ignore_ascii_case(&bc));
+      cur[j + 1] = (prev[j] + sub).min(prev[j + 1] + 1).min(cur[j] + 1);
+    }
+    if cur.iter().all(|&d| d > max) {
+      return None;
+    }
+    std::mem::swap(&mut prev, &mut cur);
+  }
+  let ret = prev[b.len()];
+  (ret <= max).then_some(ret)
+}
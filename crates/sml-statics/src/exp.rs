@@ -1,6 +1,6 @@
 //! Checking expressions.
 
-use crate::error::{AppendArg, ErrorKind};
+use crate::error::{AppendArg, ErrorKind, Related};
 use crate::get_env::{get_env_raw, get_val_info};
 use crate::info::TyEntry;
 use crate::util::record;
@@ -52,7 +52,7 @@ fn get(st: &mut St<'_>, cfg: Cfg, cx: &Cx, ars: &sml_hir::Arenas, exp: sml_hir::
       }
       match val_info.val {
         Err(e) => {
-          st.err(exp, e.into());
+          st.err(exp, e.into_error_kind(st.info.mode));
           Ty::NONE
         }
         Ok(val_info) => {
@@ -143,10 +143,13 @@ fn get(st: &mut St<'_>, cfg: Cfg, cx: &Cx, ars: &sml_hir::Arenas, exp: sml_hir::
       st.syms_tys.tys.fun(param, res)
     }
     // @def(9)
-    sml_hir::Exp::Typed(inner, want, _) => {
+    sml_hir::Exp::Typed(inner, want_ty, _) => {
       let got = get(st, cfg, cx, ars, *inner);
-      let want = ty::get(st, cx, ars, ty::Mode::Regular, *want);
+      let want = ty::get(st, cx, ars, ty::Mode::Regular, *want_ty);
+      let n = st.errors_len();
       unify(st, exp.into(), want, got);
+      let def = want_ty.and_then(|x| st.def(x.into()));
+      st.relate_errors_since(n, def, Related::Annotation);
       want
     }
   };
@@ -1,21 +1,30 @@
 //! Getting information from environments.
 
-use crate::error::ErrorKind;
+use crate::error::{suggestion, ErrorKind};
 use sml_statics_types::info::{TyInfo, ValInfo};
-use sml_statics_types::{disallow::Disallow, env::Env, item::Item};
+use sml_statics_types::{disallow::Disallow, env::Env, item::Item, mode::Mode};
 
 #[derive(Debug)]
-pub(crate) struct GetEnvResult<T> {
-  pub(crate) val: Result<T, UndefinedError>,
+pub(crate) struct GetEnvResult<'e, T> {
+  pub(crate) val: Result<T, UndefinedError<'e>>,
   pub(crate) disallow: Vec<DisallowError>,
 }
 
+/// NOTE: this holds on to the env in which the name was not found, instead of eagerly computing
+/// the similar names in it, since many lookups are allowed to fail (e.g. for variable patterns) and
+/// never become errors. Even when it does become an error, we only compute the similar names if the
+/// mode is one in which the error may be shown. See [`UndefinedError::into_error_kind`].
 #[derive(Debug)]
-pub(crate) struct UndefinedError(Item, str_util::Name);
+pub(crate) struct UndefinedError<'e>(Item, str_util::Name, &'e Env);
 
-impl From<UndefinedError> for ErrorKind {
-  fn from(val: UndefinedError) -> ErrorKind {
-    ErrorKind::Undefined(val.0, val.1)
+impl UndefinedError<'_> {
+  pub(crate) fn into_error_kind(self, mode: Mode) -> ErrorKind {
+    let similar = if suggestion::is_shown(mode) {
+      suggestion::env(self.2, self.0, &self.1)
+    } else {
+      Vec::new()
+    };
+    ErrorKind::Undefined(self.0, self.1, similar)
   }
 }
 
@@ -29,7 +38,7 @@ impl From<DisallowError> for ErrorKind {
 }
 
 /// uses the `names` to traverse through the `StrEnv`s of successive `env`s.
-pub(crate) fn get_env<'e, 'n, I>(mut env: &'e Env, names: I) -> GetEnvResult<&'e Env>
+pub(crate) fn get_env<'e, 'n, I>(mut env: &'e Env, names: I) -> GetEnvResult<'e, &'e Env>
 where
   I: IntoIterator<Item = &'n str_util::Name>,
 {
@@ -37,7 +46,8 @@ where
   for name in names {
     env = match env.str_env.get(name) {
       None => {
-        return GetEnvResult { val: Err(UndefinedError(Item::Struct, name.clone())), disallow }
+        let e = UndefinedError(Item::Struct, name.clone(), env);
+        return GetEnvResult { val: Err(e), disallow };
       }
       Some(x) => x,
     };
@@ -49,7 +59,7 @@ where
 }
 
 /// DOES include [`DisallowError`] from the [`TyInfo`]
-pub(crate) fn get_ty_info<'e>(env: &'e Env, path: &sml_path::Path) -> GetEnvResult<&'e TyInfo> {
+pub(crate) fn get_ty_info<'e>(env: &'e Env, path: &sml_path::Path) -> GetEnvResult<'e, &'e TyInfo> {
   get_ty_info_raw(env, path.prefix().iter(), path.last())
 }
 
@@ -58,18 +68,18 @@ pub(crate) fn get_ty_info_raw<'e, 'n, S>(
   env: &'e Env,
   prefix: S,
   last: &'n str_util::Name,
-) -> GetEnvResult<&'e TyInfo>
+) -> GetEnvResult<'e, &'e TyInfo>
 where
   S: IntoIterator<Item = &'n str_util::Name>,
 {
   let got_env = get_env(env, prefix);
   let mut disallow = got_env.disallow;
-  let ty_info = match got_env.val {
-    Ok(got_env) => got_env.ty_env.get(last),
+  let (got_env, ty_info) = match got_env.val {
+    Ok(got_env) => (got_env, got_env.ty_env.get(last)),
     Err(e) => return GetEnvResult { val: Err(e), disallow },
   };
   let val = match ty_info {
-    None => Err(UndefinedError(Item::Ty, last.clone())),
+    None => Err(UndefinedError(Item::Ty, last.clone(), got_env)),
     Some(ty_info) => {
       if let Some(d) = &ty_info.disallow {
         disallow.push(DisallowError(Item::Ty, d.clone(), last.clone()));
@@ -81,14 +91,17 @@ where
 }
 
 /// DOES NOT include [`DisallowError`] from the [`ValInfo`]
-pub(crate) fn get_val_info<'e>(env: &'e Env, path: &sml_path::Path) -> GetEnvResult<&'e ValInfo> {
+pub(crate) fn get_val_info<'e>(
+  env: &'e Env,
+  path: &sml_path::Path,
+) -> GetEnvResult<'e, &'e ValInfo> {
   let got_env = get_env(env, path.prefix());
   let disallow = got_env.disallow;
-  let val_info = match got_env.val {
-    Ok(got_env) => got_env.val_env.get(path.last()),
+  let (got_env, val_info) = match got_env.val {
+    Ok(got_env) => (got_env, got_env.val_env.get(path.last())),
     Err(e) => return GetEnvResult { val: Err(e), disallow },
   };
-  let val = val_info.ok_or_else(|| UndefinedError(Item::Val, path.last().clone()));
+  let val = val_info.ok_or_else(|| UndefinedError(Item::Val, path.last().clone(), got_env));
   GetEnvResult { val, disallow }
 }
 
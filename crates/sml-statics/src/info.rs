@@ -34,6 +34,12 @@ impl Defs {
   }
 
   fn with_def(&self, def: def::Def) -> impl Iterator<Item = sml_hir::Idx> + '_ {
+    self.iter().filter_map(move |(idx, d)| (d == def).then_some(idx))
+  }
+
+  /// Returns all the pairs of indices and their defs. An index may appear more than once, but the
+  /// pairs for an index are adjacent.
+  pub(crate) fn iter(&self) -> impl Iterator<Item = (sml_hir::Idx, def::Def)> + '_ {
     std::iter::empty::<(sml_hir::Idx, def::Def)>()
       .chain(self.str_exp.iter().map(|(idx, &d)| (idx.into(), d)))
       .chain(self.sig_exp.iter().map(|(idx, &d)| (idx.into(), d)))
@@ -41,7 +47,6 @@ impl Defs {
       .chain(self.exp.iter().flat_map(|(idx, ds)| ds.iter().map(move |&d| (idx.into(), d))))
       .chain(self.pat.iter().flat_map(|(idx, ds)| ds.iter().map(move |&d| (idx.into(), d))))
       .chain(self.ty.iter().map(|(idx, &d)| (idx.into(), d)))
-      .filter_map(move |(idx, d)| (d == def).then_some(idx))
   }
 }
 
@@ -146,6 +151,49 @@ impl Info {
     Some(ty_entry.to_string())
   }
 
+  /// Returns the type of this index.
+  #[must_use]
+  pub fn get_ty(&self, idx: sml_hir::Idx) -> Option<Ty> {
+    self.entries.tys.get(idx).map(|x| x.ty)
+  }
+
+  /// Returns the most general type of this index if it is polymorphic, else its type, like the
+  /// first type shown on hover.
+  #[must_use]
+  pub fn get_ty_str(
+    &self,
+    st: &sml_statics_types::St,
+    idx: sml_hir::Idx,
+    lines: config::DiagnosticLines,
+  ) -> Option<String> {
+    let ty_entry = self.entries.tys.get(idx)?;
+    let ret = match &ty_entry.ty_scheme {
+      Some(ty_scheme) => ty_scheme.display(st, lines).to_string(),
+      None => ty_entry.ty.display(st, lines).to_string(),
+    };
+    Some(ret)
+  }
+
+  /// Returns where the type variables in the type of this index were solved, along with what they
+  /// were solved to. This explains why the index has the type it does.
+  #[must_use]
+  pub fn get_ty_srcs(
+    &self,
+    st: &sml_statics_types::St,
+    idx: sml_hir::Idx,
+    lines: config::DiagnosticLines,
+  ) -> Vec<(def::Def, String)> {
+    let ty_entry = match self.entries.tys.get(idx) {
+      Some(x) => x,
+      None => return Vec::new(),
+    };
+    st.tys
+      .meta_var_srcs(ty_entry.ty)
+      .into_iter()
+      .map(|(def, ty)| (def, ty.display(st, lines).to_string()))
+      .collect()
+  }
+
   /// Returns documentation for this index.
   #[must_use]
   pub fn get_doc(&self, idx: sml_hir::Idx) -> Option<&str> {
@@ -243,6 +291,11 @@ impl Info {
     ret
   }
 
+  /// Returns all the indices and their definitions. An index may appear more than once.
+  pub fn defs(&self) -> impl Iterator<Item = (sml_hir::Idx, def::Def)> + '_ {
+    self.entries.defs.iter()
+  }
+
   /// Returns indices that have the given definition.
   pub fn get_with_def(&self, def: def::Def) -> impl Iterator<Item = sml_hir::Idx> + '_ {
     self.entries.defs.with_def(def)
@@ -260,6 +313,46 @@ impl Info {
     Some(format!(" : {ty}"))
   }
 
+  /// Returns the generalized type variables in the type of the pattern or expression, like `'a`.
+  /// Each is returned with how it is displayed, and with every pattern and expression in this whose
+  /// type contains it.
+  #[must_use]
+  pub fn generalized_ty_vars(
+    &self,
+    st: &sml_statics_types::St,
+    idx: sml_hir::Idx,
+  ) -> Vec<(String, Vec<sml_hir::Idx>)> {
+    let ty_entry = match self.entries.tys.get(idx) {
+      Some(x) => x,
+      None => return Vec::new(),
+    };
+    let mut vars = Vec::<Ty>::new();
+    st.tys.generalized_meta_vars(ty_entry.ty, &mut |ty| {
+      if !vars.contains(&ty) {
+        vars.push(ty);
+      }
+    });
+    let mut ret: Vec<_> = vars
+      .iter()
+      .map(|ty| (ty.display(st, config::DiagnosticLines::One).to_string(), Vec::new()))
+      .collect();
+    let entries = std::iter::empty::<(sml_hir::Idx, &TyEntry)>()
+      .chain(self.entries.tys.exp.iter().map(|(idx, entry)| (idx.into(), entry)))
+      .chain(self.entries.tys.pat.iter().map(|(idx, entry)| (idx.into(), entry)));
+    for (idx, entry) in entries {
+      st.tys.generalized_meta_vars(entry.ty, &mut |ty| {
+        let (_, idxs) = match vars.iter().position(|&x| x == ty) {
+          Some(i) => &mut ret[i],
+          None => return,
+        };
+        if idxs.last() != Some(&idx) {
+          idxs.push(idx);
+        }
+      });
+    }
+    ret
+  }
+
   /// Returns a string representation of a type annotation for the expression.
   #[must_use]
   pub fn show_ty_annot(
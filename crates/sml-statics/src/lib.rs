@@ -12,6 +12,7 @@
 mod compatible;
 mod config;
 mod dec;
+mod deprecated;
 mod error;
 mod exp;
 mod get_env;
@@ -27,7 +28,7 @@ pub mod basis;
 pub mod info;
 pub mod path_order;
 
-pub use error::Error;
+pub use error::{Error, Tag};
 
 /// The result of statics.
 #[derive(Debug)]
@@ -53,6 +54,7 @@ pub fn get(
   elapsed::log("sml_statics::get", || {
     let mut st = st::St::new(mode, syms_tys);
     st.info.bs = top_dec::get(&mut st, bs, arenas, root);
+    deprecated::get(&mut st, &bs.deprecated);
     let errors = st.finish();
     Statics {
       info: st.info,
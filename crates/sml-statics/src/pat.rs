@@ -1,12 +1,10 @@
 //! Checking patterns.
 
+use crate::error::{ErrorKind, Related};
 use crate::info::TyEntry;
 use crate::pat_match::{Con, Pat, VariantName};
 use crate::util::{ins_check_name, record};
-use crate::{
-  compatible::eq_ty_scheme, config, error::ErrorKind, get_env::get_val_info, st::St, ty,
-  unify::unify,
-};
+use crate::{compatible::eq_ty_scheme, config, get_env::get_val_info, st::St, ty, unify::unify};
 use fast_hash::FxHashSet;
 use sml_statics_types::info::{IdStatus, ValEnv, ValInfo};
 use sml_statics_types::ty::{Generalizable, Ty, TyData, TyScheme};
@@ -108,7 +106,7 @@ fn get_(
       let val_info = match val_info.val {
         Ok(x) => x,
         Err(e) => {
-          st.err(pat_idx, e.into());
+          st.err(pat_idx, e.into_error_kind(st.info.mode));
           return None;
         }
       };
@@ -198,10 +196,13 @@ fn get_(
       (Pat::con(con, pats, pat), ty)
     }
     // @def(42)
-    sml_hir::Pat::Typed(inner, want) => {
+    sml_hir::Pat::Typed(inner, want_ty) => {
       let (pm_pat, got) = get(st, cfg, ars, cx, ve, *inner);
-      let want = ty::get(st, cx, ars, ty::Mode::Regular, *want);
+      let want = ty::get(st, cx, ars, ty::Mode::Regular, *want_ty);
+      let n = st.errors_len();
       unify(st, inner.unwrap_or(pat_idx).into(), want, got);
+      let def = want_ty.and_then(|x| st.def(x.into()));
+      st.relate_errors_since(n, def, Related::Annotation);
       (pm_pat, want)
     }
     // @def(43)
@@ -1,6 +1,6 @@
 //! See [`St`].
 
-use crate::error::{Error, ErrorKind};
+use crate::error::{Error, ErrorKind, Related};
 use crate::info::Info;
 use crate::pat_match::{self, Pat};
 use fast_hash::FxHashSet;
@@ -53,14 +53,28 @@ impl<'a> St<'a> {
     I: Into<sml_hir::Idx>,
   {
     match (self.info.mode, &kind) {
-      (Mode::PathOrder, ErrorKind::Undefined(Item::Struct | Item::Sig | Item::Functor, _))
+      (Mode::PathOrder, ErrorKind::Undefined(Item::Struct | Item::Sig | Item::Functor, _, _))
       | (Mode::Regular(_) | Mode::BuiltinLib(_) | Mode::Dynamics, _) => {
-        self.errors.push(Error { idx: idx.into(), kind });
+        self.errors.push(Error { idx: idx.into(), kind, related: Vec::new() });
       }
       (Mode::PathOrder, _) => {}
     }
   }
 
+  /// Returns the number of errors so far, for use with [`Self::relate_errors_since`].
+  pub(crate) fn errors_len(&self) -> usize {
+    self.errors.len()
+  }
+
+  /// Notes that the def is related to all the errors emitted since there were `n` errors.
+  pub(crate) fn relate_errors_since(&mut self, n: usize, def: Option<def::Def>, related: Related) {
+    if let Some(def) = def {
+      for e in self.errors.iter_mut().skip(n) {
+        e.related.push((def, related));
+      }
+    }
+  }
+
   pub(crate) fn insert_bind(&mut self, idx: sml_hir::Idx, pat: Pat, want: Ty) {
     if self.info.mode.is_path_order() {
       return;
@@ -178,7 +192,11 @@ fn get_match(
   let mut unreachable: Vec<_> = ck.unreachable.into_iter().flatten().collect();
   unreachable.sort_unstable_by_key(|x| x.into_raw());
   for idx in unreachable {
-    errors.push(Error { idx: idx.into(), kind: ErrorKind::UnreachablePattern });
+    errors.push(Error {
+      idx: idx.into(),
+      kind: ErrorKind::UnreachablePattern,
+      related: Vec::new(),
+    });
   }
   ck.missing
 }
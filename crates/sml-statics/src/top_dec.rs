@@ -8,7 +8,7 @@ mod sharing_ty;
 mod ty_con_paths;
 mod where_ty;
 
-use crate::error::{ErrorKind, FunctorSugarUser};
+use crate::error::{suggestion, ErrorKind, FunctorSugarUser};
 use crate::get_env::{get_env, get_ty_info};
 use crate::util::{ins_check_name, ins_no_dupe};
 use crate::{basis::Bs, config::Cfg, dec, st::St, ty};
@@ -27,7 +27,7 @@ pub(crate) fn get(
 ) -> Bs {
   let mut ac = Bs::default();
   get_str_dec(st, bs, ars, StrDecAc::Bs(&mut ac), root);
-  Bs { fun_env: ac.fun_env, sig_env: ac.sig_env, env: ac.env }
+  Bs { fun_env: ac.fun_env, sig_env: ac.sig_env, env: ac.env, deprecated: ac.deprecated }
 }
 
 enum StrDecAc<'a> {
@@ -216,7 +216,7 @@ fn get_str_exp(
           }
           ac.append(&mut got_env.clone());
         }
-        Err(e) => st.err(str_exp, e.into()),
+        Err(e) => st.err(str_exp, e.into_error_kind(st.info.mode)),
       }
     }
     // @def(52), @def(53)
@@ -279,7 +279,18 @@ fn get_str_exp(
       let fun_sig = match bs.fun_env.get(fun_name) {
         Some(x) => x,
         None => {
-          st.err(str_exp, ErrorKind::Undefined(Item::Functor, fun_name.clone()));
+          let similar = if suggestion::is_shown(st.info.mode) {
+            suggestion::similar(
+              fun_name,
+              bs.fun_env
+                .iter()
+                .filter(|(_, x)| x.disallow.is_none())
+                .map(|(n, x)| (n, x.body_env.def)),
+            )
+          } else {
+            Vec::new()
+          };
+          st.err(str_exp, ErrorKind::Undefined(Item::Functor, fun_name.clone(), similar));
           return;
         }
       };
@@ -356,7 +367,15 @@ fn get_sig_exp(
       let sig = match bs.sig_env.get(name) {
         Some(x) => x,
         None => {
-          st.err(sig_exp, ErrorKind::Undefined(Item::Sig, name.clone()));
+          let similar = if suggestion::is_shown(st.info.mode) {
+            suggestion::similar(
+              name,
+              bs.sig_env.iter().filter(|(_, x)| x.disallow.is_none()).map(|(n, x)| (n, x.env.def)),
+            )
+          } else {
+            Vec::new()
+          };
+          st.err(sig_exp, ErrorKind::Undefined(Item::Sig, name.clone(), similar));
           return None;
         }
       };
@@ -524,7 +543,7 @@ fn get_spec_one(
             }
           }
         }
-        Err(e) => st.err(spec, e.into()),
+        Err(e) => st.err(spec, e.into_error_kind(st.info.mode)),
       }
     }
     // @def(73), @def(83)
@@ -582,7 +601,7 @@ fn get_spec_one(
               match ty_con_paths.val {
                 Ok(x) => Some((path, x)),
                 Err(e) => {
-                  st.err(spec, e.into());
+                  st.err(spec, e.into_error_kind(st.info.mode));
                   None
                 }
               }
@@ -638,7 +657,7 @@ fn get_ty_desc(
   let started = st.syms_tys.syms.start(st.mk_path(ty_desc.name.clone()));
   for ty_var in &ty_desc.ty_vars {
     if !ty_vars.insert(ty_var) {
-      let e = ErrorKind::Duplicate(Item::TyVar, ty_var.as_name().clone());
+      let e = ErrorKind::Duplicate(Item::TyVar, ty_var.as_name().clone(), None);
       st.err(idx, e);
     }
   }
@@ -5,7 +5,8 @@
 //! env only for use here that has the indices?
 
 use crate::compatible::{eq_ty_fn, generalizes};
-use crate::{error::ErrorKind, st::St};
+use crate::error::{ErrorKind, Related};
+use crate::{st::St, util::HasDef as _};
 use sml_statics_types::info::{IdStatus, TyInfo, ValInfo};
 use sml_statics_types::{env::Env, item::Item};
 
@@ -13,41 +14,45 @@ pub(crate) fn get_env(st: &mut St<'_>, idx: sml_hir::Idx, general: &Env, specifi
   for (name, specific) in specific.str_env.iter() {
     match general.str_env.get(name) {
       Some(general) => get_env(st, idx, general, specific),
-      None => st.err(idx, ErrorKind::Missing(Item::Struct, name.clone())),
+      None => st.err(idx, ErrorKind::Missing(Item::Struct, name.clone(), specific.def)),
     }
   }
   for (name, specific) in specific.ty_env.iter() {
     match general.ty_env.get(name) {
       Some(general) => get_ty_info(st, idx, general.clone(), specific.clone()),
-      None => st.err(idx, ErrorKind::Missing(Item::Ty, name.clone())),
+      None => st.err(idx, ErrorKind::Missing(Item::Ty, name.clone(), specific.def)),
     }
   }
   for (name, specific) in specific.val_env.iter() {
     match general.val_env.get(name) {
       Some(general) => get_val_info(st, idx, general, specific, name),
-      None => st.err(idx, ErrorKind::Missing(Item::Val, name.clone())),
+      None => st.err(idx, ErrorKind::Missing(Item::Val, name.clone(), specific.def())),
     }
   }
 }
 
 fn get_ty_info(st: &mut St<'_>, idx: sml_hir::Idx, mut general: TyInfo, specific: TyInfo) {
+  let n = st.errors_len();
   eq_ty_fn(st, idx, specific.ty_scheme, general.ty_scheme.clone());
+  st.relate_errors_since(n, specific.def, Related::Spec);
   if specific.val_env.is_empty() {
     return;
   }
-  for (name, specific) in specific.val_env {
+  for (name, specific_val) in specific.val_env {
     match general.val_env.remove(&name) {
       Some(general) => {
-        if !general.id_status.same_kind_as(specific.id_status) {
+        let n = st.errors_len();
+        if !general.id_status.same_kind_as(specific_val.id_status) {
           st.err(idx, ErrorKind::WrongIdStatus(name.clone()));
         }
-        eq_ty_fn(st, idx, specific.ty_scheme, general.ty_scheme.clone());
+        eq_ty_fn(st, idx, specific_val.ty_scheme, general.ty_scheme.clone());
+        st.relate_errors_since(n, specific.def, Related::Spec);
       }
-      None => st.err(idx, ErrorKind::Missing(Item::Val, name.clone())),
+      None => st.err(idx, ErrorKind::Missing(Item::Val, name.clone(), specific.def)),
     }
   }
   for (name, _) in general.val_env.iter() {
-    st.err(idx, ErrorKind::Extra(Item::Val, name.clone()));
+    st.err(idx, ErrorKind::Extra(Item::Val, name.clone(), specific.def));
   }
 }
 
@@ -58,10 +63,12 @@ fn get_val_info(
   specific: &ValInfo,
   name: &str_util::Name,
 ) {
+  let n = st.errors_len();
   generalizes(st, idx, &general.ty_scheme, &specific.ty_scheme);
   if !general.id_status.same_kind_as(specific.id_status)
     && !matches!(specific.id_status, IdStatus::Val)
   {
     st.err(idx, ErrorKind::WrongIdStatus(name.clone()));
   }
+  st.relate_errors_since(n, specific.def(), Related::Spec);
 }
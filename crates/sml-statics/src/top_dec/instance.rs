@@ -36,7 +36,7 @@ pub(crate) fn env_of_sig(
           st.err(idx, ErrorKind::WrongNumTyArgs(want, got));
         }
       }
-      Err(e) => st.err(idx, e.into()),
+      Err(e) => st.err(idx, e.into_error_kind(st.info.mode)),
     }
   }
 }
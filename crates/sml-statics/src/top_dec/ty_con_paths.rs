@@ -4,7 +4,10 @@ use crate::get_env::{get_env, GetEnvResult};
 use fast_hash::FxHashSet;
 use sml_statics_types::env::Env;
 
-pub(crate) fn get(env: &Env, path: &sml_path::Path) -> GetEnvResult<FxHashSet<sml_path::Path>> {
+pub(crate) fn get<'e>(
+  env: &'e Env,
+  path: &sml_path::Path,
+) -> GetEnvResult<'e, FxHashSet<sml_path::Path>> {
   let got_env = get_env(env, path.all_names());
   let val = got_env.val.map(|env| {
     let mut ty_cons = FxHashSet::<sml_path::Path>::default();
@@ -33,7 +33,7 @@ pub(crate) fn get(
       let lhs_ty_cons = match lhs_ty_cons.val {
         Ok(x) => x,
         Err(e) => {
-          st.err(idx, e.into());
+          st.err(idx, e.into_error_kind(st.info.mode));
           return;
         }
       };
@@ -44,7 +44,7 @@ pub(crate) fn get(
       let rhs_ty_cons = match rhs_ty_cons.val {
         Ok(x) => x,
         Err(e) => {
-          st.err(idx, e.into());
+          st.err(idx, e.into_error_kind(st.info.mode));
           return;
         }
       };
@@ -63,7 +63,7 @@ pub(crate) fn get(
             let ty_scheme = ty_info.ty_scheme.clone();
             get_where_type(st, idx, marker, inner_env, &lhs, ty_scheme, false);
           }
-          Err(e) => st.err(idx, e.into()),
+          Err(e) => st.err(idx, e.into_error_kind(st.info.mode)),
         }
       }
     }
@@ -88,7 +88,7 @@ fn get_where_type(
   let path_ty_scheme = match ty_info.val {
     Ok(x) => &x.ty_scheme,
     Err(e) => {
-      st.err(idx, e.into());
+      st.err(idx, e.into_error_kind(st.info.mode));
       return;
     }
   };
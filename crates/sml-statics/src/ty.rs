@@ -36,7 +36,7 @@ pub(crate) fn get(
     // @def(44)
     sml_hir::Ty::Var(v) => match cx.fixed.get(v) {
       None => {
-        st.err(ty, ErrorKind::Undefined(Item::TyVar, v.as_name().clone()));
+        st.err(ty, ErrorKind::Undefined(Item::TyVar, v.as_name().clone(), Vec::new()));
         Ty::NONE
       }
       Some(fv) => {
@@ -84,7 +84,7 @@ pub(crate) fn get(
           }
         }
         Err(e) => {
-          st.err(ty, e.into());
+          st.err(ty, e.into_error_kind(st.info.mode));
           Ty::NONE
         }
       }
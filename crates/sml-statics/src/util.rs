@@ -2,8 +2,10 @@
 
 use crate::{error::ErrorKind, st::St};
 use chain_map::ChainMap;
-use sml_statics_types::item::Item;
+use sml_statics_types::env::{Env, FunSig, Sig};
+use sml_statics_types::info::{TyInfo, ValInfo};
 use sml_statics_types::ty::{RecordData, Ty};
+use sml_statics_types::{def, item::Item};
 
 /// @def(6), @def(39), @def(49)
 pub(crate) fn record<T, F>(
@@ -34,8 +36,12 @@ pub(crate) fn ins_no_dupe<V>(
   name: str_util::Name,
   val: V,
   item: Item,
-) -> Option<ErrorKind> {
-  (!map.insert(name.clone(), val)).then_some(ErrorKind::Duplicate(item, name))
+) -> Option<ErrorKind>
+where
+  V: HasDef,
+{
+  let prev = map.get(&name).and_then(HasDef::def);
+  (!map.insert(name.clone(), val)).then(|| ErrorKind::Duplicate(item, name, prev))
 }
 
 /// inerts a name that is not one of the special reserved names like `true`.
@@ -44,7 +50,10 @@ pub(crate) fn ins_check_name<V>(
   name: str_util::Name,
   val: V,
   item: Item,
-) -> Option<ErrorKind> {
+) -> Option<ErrorKind>
+where
+  V: HasDef,
+{
   check_name(&name).or_else(|| ins_no_dupe(map, name, val, item))
 }
 
@@ -53,3 +62,39 @@ pub(crate) fn check_name(name: &str_util::Name) -> Option<ErrorKind> {
   let no = matches!(name.as_str(), "true" | "false" | "nil" | "::" | "ref" | "=" | "it");
   no.then(|| ErrorKind::InvalidRebindName(name.clone()))
 }
+
+/// Something that may have a def site.
+pub(crate) trait HasDef {
+  fn def(&self) -> Option<def::Def>;
+}
+
+impl HasDef for ValInfo {
+  fn def(&self) -> Option<def::Def> {
+    // NOTE: there may be many defs with or patterns, but any one is fine.
+    self.defs.iter().next().copied()
+  }
+}
+
+impl HasDef for TyInfo {
+  fn def(&self) -> Option<def::Def> {
+    self.def
+  }
+}
+
+impl HasDef for Env {
+  fn def(&self) -> Option<def::Def> {
+    self.def
+  }
+}
+
+impl HasDef for Sig {
+  fn def(&self) -> Option<def::Def> {
+    self.env.def
+  }
+}
+
+impl HasDef for FunSig {
+  fn def(&self) -> Option<def::Def> {
+    self.body_env.def
+  }
+}
@@ -115,6 +115,19 @@ pub(crate) fn check_with_warnings(s: &str) {
   raw::get(raw::one_file_fs(s), opts);
 }
 
+/// Like [`check_with_warnings`], but with the given config file and std basis.
+#[track_caller]
+pub(crate) fn check_with_config(config: &str, std_basis: raw::StdBasis, s: &str) {
+  let opts = raw::Opts {
+    std_basis,
+    outcome: raw::Outcome::Pass,
+    limit: raw::Limit::First,
+    min_severity: Severity::Warning,
+    expected_input: raw::ExpectedInput::Good,
+  };
+  raw::get([(config::file::PATH, config), ("s.mlb", "f.sml"), ("f.sml", s)], opts);
+}
+
 /// Asserts the input from the files generates an error at the given path containing the given
 /// message.
 #[track_caller]
@@ -131,3 +144,103 @@ where
   };
   raw::get(files, opts);
 }
+
+/// Given an SML program with the cursor marked by `$0`, asserts that applying the code action with
+/// the given title at the cursor turns the program into `want`.
+#[track_caller]
+pub(crate) fn check_code_action(s: &str, title: &str, want: &str) {
+  let (s, actions) = code_actions(s);
+  let action = match actions.into_iter().find(|x| x.title == title) {
+    Some(x) => x,
+    None => panic!("no code action titled {title:?}"),
+  };
+  let got = apply_edits(s.as_str(), action.edits);
+  pretty_assertions::assert_str_eq!(want, got.as_str());
+}
+
+/// Asserts that applying all of the fixes for the SML program turns it into `want`. The fixes must
+/// not overlap.
+#[track_caller]
+pub(crate) fn check_fixes(s: &str, want: &str) {
+  let (an, input, store) = raw::analysis(raw::one_file_fs(s), raw::StdBasis::Minimal);
+  let path = raw::source_path(&input, &store, "f.sml");
+  let edits: Vec<_> = an.fixes(path).into_iter().flat_map(|x| x.edits).collect();
+  let got = apply_edits(s, edits);
+  pretty_assertions::assert_str_eq!(want, got.as_str());
+}
+
+fn apply_edits(s: &str, edits: Vec<analysis::TextEdit>) -> String {
+  let pos_db = text_pos::PositionDb::new(s);
+  let mut edits: Vec<_> = edits
+    .into_iter()
+    .map(|edit| {
+      let range: std::ops::Range<usize> =
+        pos_db.text_range_utf16(edit.range).expect("edit range out of range").into();
+      (range, edit.new_text)
+    })
+    .collect();
+  edits.sort_unstable_by_key(|(range, _)| std::cmp::Reverse(range.start));
+  for w in edits.windows(2) {
+    assert!(w[1].0.end <= w[0].0.start, "overlapping edits");
+  }
+  let mut ret = s.to_owned();
+  for (range, new_text) in edits {
+    ret.replace_range(range, new_text.as_str());
+  }
+  ret
+}
+
+/// Asserts that there is no code action with the title at the cursor, marked with `$0`, in the SML
+/// program.
+#[track_caller]
+pub(crate) fn check_no_code_action(s: &str, title: &str) {
+  let (_, actions) = code_actions(s);
+  if actions.iter().any(|x| x.title == title) {
+    panic!("unexpected code action titled {title:?}");
+  }
+}
+
+/// Asserts that the hover at the cursor, marked with `$0`, in the SML program does not contain
+/// `msg`.
+#[track_caller]
+pub(crate) fn check_hover_lacks(s: &str, msg: &str) {
+  let (_, an, pos) = at_cursor(s);
+  let (md, _) = an.get_md(pos, false).expect("no hover");
+  assert!(!md.contains(msg), "hover unexpectedly contains {msg:?}: {md}");
+}
+
+/// Returns the program without the cursor, and the code actions at the cursor.
+fn code_actions(s: &str) -> (String, Vec<analysis::CodeAction>) {
+  let (s, an, pos) = at_cursor(s);
+  let actions = an.code_actions(pos);
+  (s, actions)
+}
+
+/// Returns the program without the cursor, marked with `$0`, the analysis of the program, and the
+/// position of the cursor.
+fn at_cursor(s: &str) -> (String, analysis::Analysis, paths::WithPath<text_pos::PositionUtf16>) {
+  let cursor = s.find("$0").expect("no cursor");
+  let s = s.replacen("$0", "", 1);
+  let (an, input, store) = raw::analysis(raw::one_file_fs(s.as_str()), raw::StdBasis::Minimal);
+  let path = raw::source_path(&input, &store, "f.sml");
+  let before = &s[..cursor];
+  let line = u32::try_from(before.matches('\n').count()).unwrap();
+  let col = before.rsplit('\n').next().unwrap_or_default().encode_utf16().count();
+  let pos = text_pos::PositionUtf16 { line, col: u32::try_from(col).unwrap() };
+  (s, an, path.wrap(pos))
+}
+
+/// Asserts that the first diagnostic for the SML program has exactly the given related locations,
+/// given as the zero-based line each starts on and its message.
+#[track_caller]
+pub(crate) fn check_related(s: &str, want: &[(u32, &str)]) {
+  let (mut an, input, store) = raw::analysis(raw::one_file_fs(s), raw::StdBasis::Minimal);
+  let path = raw::source_path(&input, &store, "f.sml");
+  let mut ds: Vec<_> =
+    an.get_many(&input).into_iter().filter(|&(id, _)| id == path).flat_map(|(_, ds)| ds).collect();
+  ds.sort_by_key(|d| (d.range.start.line, d.range.start.col));
+  let d = ds.first().expect("no diagnostics");
+  let got: Vec<_> =
+    d.related.iter().map(|r| (r.range.val.start.line, r.message.as_str())).collect();
+  assert_eq!(want, got.as_slice());
+}
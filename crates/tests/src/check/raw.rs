@@ -241,6 +241,37 @@ where
   }
 }
 
+/// Returns an analysis that has analyzed the files, and the input and store for the files.
+pub(crate) fn analysis<'a, I>(
+  files: I,
+  std_basis: StdBasis,
+) -> (analysis::Analysis, ::input::Input, paths::Store)
+where
+  I: IntoIterator<Item = (&'a str, &'a str)>,
+{
+  analysis_with_options(files, std_basis, analysis::Options::default())
+}
+
+/// Like [`analysis`], but with the given options.
+pub(crate) fn analysis_with_options<'a, I>(
+  files: I,
+  std_basis: StdBasis,
+  options: analysis::Options,
+) -> (analysis::Analysis, ::input::Input, paths::Store)
+where
+  I: IntoIterator<Item = (&'a str, &'a str)>,
+{
+  let (input, store) = input::get(files);
+  assert!(input.errors.is_empty(), "unexpectedly bad input: {:?}", input.errors);
+  let std_basis = match std_basis {
+    StdBasis::Minimal => analysis::StdBasis::minimal(),
+    StdBasis::Full => FULL.clone(),
+  };
+  let mut an = analysis::Analysis::new(std_basis, options);
+  an.get_many(&input);
+  (an, input, store)
+}
+
 const STD_NAMES: [&str; 20] = [
   "*", "+", "-", "/", "::", "<", "<=", "<>", "=", ">", ">=", "abs", "div", "false", "mod", "nil",
   "ref", "true", "use", "~",
@@ -252,6 +283,21 @@ pub(crate) fn one_file_fs(s: &str) -> [(&str, &str); 2] {
   [("s.mlb", "f.sml"), ("f.sml", s)]
 }
 
+/// Returns the id of the source file in the input whose path ends with `name`, like `f.sml` for
+/// [`one_file_fs`].
+#[track_caller]
+pub(crate) fn source_path(
+  input: &::input::Input,
+  store: &paths::Store,
+  name: &str,
+) -> paths::PathId {
+  *input
+    .sources
+    .keys()
+    .find(|&&id| store.get_path(id).as_path().ends_with(name))
+    .expect("no source file")
+}
+
 /// Returns whether the env var is set to `1`.
 pub(crate) fn env_var_enabled(s: &str) -> bool {
   std::env::var_os(s).map_or(false, |x| x == "1")
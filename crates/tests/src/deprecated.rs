@@ -0,0 +1,85 @@
+//! Deprecating items with doc comments.
+
+use crate::check::{check_with_warnings, raw};
+
+#[test]
+fn val() {
+  check_with_warnings(
+    r#"
+(*!
+ * The answer.
+ * @deprecated use `answer` instead
+ *)
+val old = 42
+val answer = 42
+val _ = old + answer
+(**     ^^^ use of deprecated item: use `answer` instead *)
+"#,
+  );
+}
+
+#[test]
+fn no_reason() {
+  check_with_warnings(
+    r#"
+(*!
+ * @deprecated
+ *)
+type t = int
+val x : t = 3
+(**     ^ exact: use of deprecated item *)
+"#,
+  );
+}
+
+#[test]
+fn fun_params() {
+  check_with_warnings(
+    r#"
+(*!
+ * Increments.
+ * @deprecated
+ *)
+fun inc x = x + 1
+val _ = inc 3
+(**     ^^^ use of deprecated item *)
+"#,
+  );
+}
+
+#[test]
+fn not_a_tag() {
+  check_with_warnings(
+    r#"
+(*!
+ * This was once @deprecated but it is no longer.
+ *)
+fun inc x = x + 1
+val _ = inc 3
+"#,
+  );
+}
+
+#[test]
+fn across_files() {
+  let opts = raw::Opts {
+    std_basis: raw::StdBasis::Minimal,
+    outcome: raw::Outcome::Pass,
+    limit: raw::Limit::First,
+    min_severity: diagnostic::Severity::Warning,
+    expected_input: raw::ExpectedInput::Good,
+  };
+  let a = r#"
+structure A = struct
+  (*!
+   * @deprecated use `B.f` instead
+   *)
+  fun f () = ()
+end
+"#;
+  let b = r#"
+val _ = A.f ()
+(**     ^^^ use of deprecated item: use `B.f` instead *)
+"#;
+  raw::get([("s.mlb", "a.sml b.sml"), ("a.sml", a), ("b.sml", b)], opts);
+}
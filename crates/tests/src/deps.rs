@@ -0,0 +1,22 @@
+//! The other source files whose definitions a source file uses.
+
+use crate::check::raw;
+
+#[test]
+fn source_deps() {
+  let cm = r#"
+Group is
+  a.sml
+  b.sml
+  c.sml
+"#;
+  let a = "structure A = struct val x = B.y end";
+  let b = "structure B = struct val y = 3 end";
+  let c = "signature SIG = sig val y : int end  structure C : SIG = B";
+  let files = [("a.cm", cm), ("a.sml", a), ("b.sml", b), ("c.sml", c)];
+  let (an, input, store) = raw::analysis(files, raw::StdBasis::Minimal);
+  let path = |name: &str| raw::source_path(&input, &store, name);
+  assert_eq!(an.source_deps(path("a.sml")).expect("no deps"), vec![path("b.sml")]);
+  assert!(an.source_deps(path("b.sml")).expect("no deps").is_empty());
+  assert_eq!(an.source_deps(path("c.sml")).expect("no deps"), vec![path("b.sml")]);
+}
@@ -0,0 +1,154 @@
+//! Discarding values whose type is not `unit`.
+
+use crate::check::{check_with_config, check_with_warnings, raw};
+
+/// A config that turns on the warning for `val _ =`, which is off by default.
+const VAL_WILD: &str = r#"
+version = 1
+[diagnostics]
+7007.severity = "warning"
+"#;
+
+#[test]
+fn seq() {
+  check_with_warnings(
+    r#"
+fun f x = x + 1
+val () = (f 3; ())
+(**       ^^^ discarding a value of type `int` in a sequence *)
+"#,
+  );
+}
+
+#[test]
+fn seq_let() {
+  check_with_warnings(
+    r#"
+val x = let in 1; () end
+(**            ^ discarding a value of type `int` in a sequence *)
+"#,
+  );
+}
+
+#[test]
+fn seq_unit() {
+  check_with_warnings(
+    r#"
+fun f () = ()
+val () = (f (); f (); ())
+"#,
+  );
+}
+
+#[test]
+fn seq_fn() {
+  check_with_warnings(
+    r#"
+fun f x = x
+val () = (f; ())
+(**       ^ discarding a value of type *)
+"#,
+  );
+}
+
+#[test]
+fn seq_raise() {
+  check_with_warnings(
+    r#"
+exception E
+val () = (raise E; ())
+"#,
+  );
+}
+
+#[test]
+fn val_wild() {
+  let sml = r#"
+val _ = 3 + 4
+(**     ^^^^^ discarding a value of type `int` with `val _` *)
+"#;
+  check_with_config(VAL_WILD, raw::StdBasis::Minimal, sml);
+}
+
+#[test]
+fn val_wild_off_by_default() {
+  check_with_warnings(
+    r#"
+val _ = 3 + 4
+"#,
+  );
+}
+
+#[test]
+fn val_wild_unit() {
+  let sml = r#"
+fun f () = ()
+val _ = f ()
+"#;
+  check_with_config(VAL_WILD, raw::StdBasis::Minimal, sml);
+}
+
+#[test]
+fn val_named() {
+  let sml = r#"
+val x = 3 + 4
+"#;
+  check_with_config(VAL_WILD, raw::StdBasis::Minimal, sml);
+}
+
+#[test]
+fn allow() {
+  let config = r#"
+version = 1
+[diagnostics]
+7007.severity = "warning"
+[language.discard]
+f = true
+"#;
+  let sml = r#"
+fun f x = x + 1
+fun g x = x + 1
+val () = (f 3; ())
+val _ = f 4
+val _ = (f 5 : int)
+val () = (g 3; ())
+(**       ^^^ discarding a value of type `int` in a sequence *)
+"#;
+  check_with_config(config, raw::StdBasis::Minimal, sml);
+}
+
+#[test]
+fn allow_qualified() {
+  let config = r#"
+version = 1
+[diagnostics]
+7007.severity = "warning"
+[language.discard]
+"S.f" = true
+"#;
+  let sml = r#"
+structure S = struct fun f x = x + 1 end
+val _ = S.f 3
+val h = S.f
+val _ = h 3
+(**     ^^^ discarding a value of type `int` with `val _` *)
+"#;
+  check_with_config(config, raw::StdBasis::Minimal, sml);
+}
+
+#[test]
+fn allow_not_allowed() {
+  let config = r#"
+version = 1
+[diagnostics]
+7007.severity = "warning"
+[language.discard]
+f = false
+"#;
+  let sml = r#"
+fun f x = x + 1
+val _ = f 3
+(**     ^^^ discarding a value of type `int` with `val _` *)
+"#;
+  check_with_config(config, raw::StdBasis::Minimal, sml);
+}
@@ -3,10 +3,27 @@
 use crate::{check::raw, repo::root_dir};
 use diagnostic::Severity;
 use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag};
+use std::fmt::Write as _;
 
 const SML: &str = "sml";
 
-fn check_all(contents: &str) {
+/// Returns a config setting the severity of every lint, i.e. every diagnostic with a 7xxx code.
+///
+/// Lints are opinionated, and some are off by default, so we turn them on only in their own docs
+/// and ignore them in the examples for everything else.
+fn lints_config(severity: &str) -> String {
+  let mut ret = String::from("version = 1\n[diagnostics]\n");
+  for entry in std::fs::read_dir(root_dir().join("docs").join("diagnostics")).unwrap() {
+    let name = entry.unwrap().file_name();
+    let code = name.to_str().unwrap().strip_suffix(".md").unwrap();
+    if code.starts_with('7') {
+      writeln!(ret, "{code}.severity = \"{severity}\"").unwrap();
+    }
+  }
+  ret
+}
+
+fn check_all(contents: &str, config: &str) {
   let mut options = Options::empty();
   options.insert(Options::ENABLE_TABLES);
   let parser = Parser::new_ext(contents, options);
@@ -30,7 +47,8 @@ fn check_all(contents: &str) {
       Event::End(Tag::CodeBlock(CodeBlockKind::Fenced(lang))) => {
         if lang.as_ref() == SML {
           if !ignore_next {
-            raw::get(raw::one_file_fs(ac.as_ref()), opts);
+            let files = [(config::file::PATH, config), ("s.mlb", "f.sml"), ("f.sml", ac.as_str())];
+            raw::get(files, opts);
           }
           ac.clear();
           inside = false;
@@ -58,16 +76,18 @@ fn diagnostics() {
     let entry = entry.unwrap();
     let path = entry.path();
     let contents = std::fs::read_to_string(&path).unwrap();
-    check_all(&contents);
+    let is_lint = path.file_name().unwrap().to_str().unwrap().starts_with('7');
+    let config = lints_config(if is_lint { "warning" } else { "ignore" });
+    check_all(&contents, config.as_str());
   }
 }
 
 #[test]
 fn tokens() {
-  check_all(include_str!("../../../docs/tokens.md"));
+  check_all(include_str!("../../../docs/tokens.md"), lints_config("ignore").as_str());
 }
 
 #[test]
 fn primitives() {
-  check_all(include_str!("../../../docs/primitives.md"));
+  check_all(include_str!("../../../docs/primitives.md"), lints_config("ignore").as_str());
 }
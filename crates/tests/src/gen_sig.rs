@@ -0,0 +1,105 @@
+//! Code actions to generate a signature from a structure.
+
+use crate::check::check_code_action;
+
+#[test]
+fn smoke() {
+  check_code_action(
+    r#"
+structure $0Queue = struct
+  (*!
+   * A queue.
+   *)
+  datatype 'a t = Q of 'a list * 'a list
+  exception Empty
+  val empty = Q ([], [])
+  fun push (x, Q (f, b)) = Q (f, x :: b)
+end
+"#,
+    "Generate signature",
+    r#"
+signature QUEUE_SIG = sig
+  (*!
+   * A queue.
+   *)
+  datatype 'a t = Q of 'a list * 'a list
+  exception Empty
+  val empty : 'a t
+  val push : 'a * 'a t -> 'a t
+end
+
+structure Queue = struct
+  (*!
+   * A queue.
+   *)
+  datatype 'a t = Q of 'a list * 'a list
+  exception Empty
+  val empty = Q ([], [])
+  fun push (x, Q (f, b)) = Q (f, x :: b)
+end
+"#,
+  );
+}
+
+#[test]
+fn nested() {
+  check_code_action(
+    r#"
+structure $0S = struct
+  type t = int
+  structure Util = struct
+    exception Bad of string
+    fun id x = x
+  end
+  val bad = Util.Bad
+end
+"#,
+    "Generate signature",
+    r#"
+signature S_SIG = sig
+  type t = int
+  structure Util : sig
+    exception Bad of string
+    val id : 'a -> 'a
+  end
+  val bad : string -> exn
+end
+
+structure S = struct
+  type t = int
+  structure Util = struct
+    exception Bad of string
+    fun id x = x
+  end
+  val bad = Util.Bad
+end
+"#,
+  );
+}
+
+#[test]
+fn ascribe() {
+  check_code_action(
+    r#"
+(*!
+ * Some numbers.
+ *)
+structure $0Nums = struct
+  val zero = 0
+end
+"#,
+    "Generate signature and ascribe to it",
+    r#"
+signature NUMS_SIG = sig
+  val zero : int
+end
+
+(*!
+ * Some numbers.
+ *)
+structure Nums : NUMS_SIG = struct
+  val zero = 0
+end
+"#,
+  );
+}
@@ -1,4 +1,5 @@
 //! Hover tests.
 
 mod doc;
+mod explain_ty;
 mod ty;
@@ -1,6 +1,6 @@
 //! Test for getting documentation on hover.
 
-use crate::check::{check, check_with_std_basis, fail};
+use crate::check::{check, check_with_std_basis, fail, raw};
 
 #[test]
 fn val() {
@@ -128,3 +128,30 @@ structure L = List
 "#,
   );
 }
+
+#[test]
+fn doc_items() {
+  let s = r#"
+signature SIG = sig
+  (*!
+   * The value.
+   *)
+  val x : int
+end
+
+(*!
+ * A structure.
+ *)
+structure S : SIG = struct val x = 3 end
+"#;
+  let (an, input, store) = raw::analysis(raw::one_file_fs(s), raw::StdBasis::Minimal);
+  let path = raw::source_path(&input, &store, "f.sml");
+  let items = an.doc_items(path).expect("no doc items");
+  let sig = items.iter().find(|x| x.name == "SIG").expect("no SIG");
+  let x = sig.children.iter().find(|x| x.name == "x").expect("no x");
+  assert_eq!(x.doc.as_deref().map(str::trim), Some("The value."));
+  assert_eq!(x.detail.as_deref(), Some("int"));
+  let structure = items.iter().find(|x| x.name == "S").expect("no S");
+  assert_eq!(structure.doc.as_deref().map(str::trim), Some("A structure."));
+  assert!(structure.links.iter().any(|link| link.path == path && link.val == sig.range));
+}
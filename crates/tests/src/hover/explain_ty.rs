@@ -0,0 +1,34 @@
+//! Test for explaining why something has its type on hover.
+
+use crate::check::{check, fail};
+
+#[test]
+fn app() {
+  check(
+    r#"
+fun g (y : int) = y
+fun f x = g x
+(**   ^ hover: `x` on line 3: `int` *)
+"#,
+  );
+}
+
+#[test]
+fn annotation() {
+  check(
+    r#"
+fun f x = (x : string)
+(**   ^ hover: The type was inferred from *)
+"#,
+  );
+}
+
+#[test]
+fn literal() {
+  fail(
+    r#"
+val _ = "hi"
+(**     ^ hover: The type was inferred from *)
+"#,
+  );
+}
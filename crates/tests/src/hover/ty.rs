@@ -1,6 +1,6 @@
 //! Test for getting type information on hover.
 
-use crate::check::{check, fail};
+use crate::check::{check, fail, raw};
 
 #[test]
 fn smoke() {
@@ -210,3 +210,23 @@ fun foo x =
 "#,
   );
 }
+
+#[test]
+fn get_ty() {
+  let s = "val f = fn (x : int) => x\n";
+  let (an, input, store) = raw::analysis(raw::one_file_fs(s), raw::StdBasis::Minimal);
+  let path = raw::source_path(&input, &store, "f.sml");
+  let pos = text_pos::PositionUtf16 { line: 0, col: 4 };
+  let (ty, _) = an.get_ty(path.wrap(pos)).expect("no type");
+  assert_eq!(ty, "int -> int");
+}
+
+#[test]
+fn get_ty_generalized() {
+  let s = "fun id x = x\nval _ = id 3\n";
+  let (an, input, store) = raw::analysis(raw::one_file_fs(s), raw::StdBasis::Minimal);
+  let path = raw::source_path(&input, &store, "f.sml");
+  let pos = text_pos::PositionUtf16 { line: 1, col: 8 };
+  let (ty, _) = an.get_ty(path.wrap(pos)).expect("no type");
+  assert_eq!(ty, "'a -> 'a");
+}
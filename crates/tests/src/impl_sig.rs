@@ -0,0 +1,157 @@
+//! Code actions to implement a signature with a stub structure.
+
+use crate::check::check_code_action;
+
+#[test]
+fn implement() {
+  check_code_action(
+    r#"
+signature $0QUEUE = sig
+  type 'a t
+  datatype dir = Front | Back
+  exception Empty
+  val empty : 'a t
+  val push : 'a * 'a t -> 'a t
+end
+"#,
+    "Implement signature",
+    r#"
+signature QUEUE = sig
+  type 'a t
+  datatype dir = Front | Back
+  exception Empty
+  val empty : 'a t
+  val push : 'a * 'a t -> 'a t
+end
+
+structure Queue :> QUEUE = struct
+  type 'a t = unit
+  datatype dir = Front | Back
+  exception Empty
+  val empty : 'a t = raise Fail "unimplemented"
+  fun push _ = raise Fail "unimplemented"
+end
+"#,
+  );
+}
+
+#[test]
+fn nested() {
+  check_code_action(
+    r#"
+signature $0HASH_TABLE_SIG = sig
+  type key = int
+  structure Key : sig
+    val hash : key -> int
+  end
+end
+"#,
+    "Implement signature",
+    r#"
+signature HASH_TABLE_SIG = sig
+  type key = int
+  structure Key : sig
+    val hash : key -> int
+  end
+end
+
+structure HashTable :> HASH_TABLE_SIG = struct
+  type key = int
+  structure Key = struct
+    fun hash _ = raise Fail "unimplemented"
+  end
+end
+"#,
+  );
+}
+
+#[test]
+fn add_missing() {
+  check_code_action(
+    r#"
+signature SIG = sig
+  type t
+  val x : int
+  val y : t -> string
+end
+
+structure $0Str : SIG = struct
+  type t = int
+  val x = 3
+end
+"#,
+    "Add missing items",
+    r#"
+signature SIG = sig
+  type t
+  val x : int
+  val y : t -> string
+end
+
+structure Str : SIG = struct
+  type t = int
+  val x = 3
+  fun y _ = raise Fail "unimplemented"
+end
+"#,
+  );
+}
+
+#[test]
+fn annotate_val() {
+  check_code_action(
+    r#"
+signature SIG = sig
+  type t
+  val x : int
+  val y : t list
+end
+
+structure $0Str : SIG = struct
+  type t = int
+end
+"#,
+    "Add missing items",
+    r#"
+signature SIG = sig
+  type t
+  val x : int
+  val y : t list
+end
+
+structure Str : SIG = struct
+  type t = int
+  val x : int = raise Fail "unimplemented"
+  val y : t list = raise Fail "unimplemented"
+end
+"#,
+  );
+}
+
+#[test]
+fn add_missing_functor() {
+  check_code_action(
+    r#"
+signature SIG = sig
+  type t
+  val f : t -> int
+end
+
+functor $0F (A : sig type t end) : SIG = struct
+  type t = A.t
+end
+"#,
+    "Add missing items",
+    r#"
+signature SIG = sig
+  type t
+  val f : t -> int
+end
+
+functor F (A : sig type t end) : SIG = struct
+  type t = A.t
+  fun f _ = raise Fail "unimplemented"
+end
+"#,
+  );
+}
@@ -1,6 +1,6 @@
 //! Tests for config for diagnostics.
 
-use crate::check::{check_bad_input, check_multi};
+use crate::check::{check_bad_input, check_multi, raw};
 
 #[test]
 fn ok() {
@@ -61,3 +61,181 @@ val _ = (1)
 "#;
   check_multi([(config::file::PATH, config), ("a.mlb", "a.sml"), ("a.sml", sml)]);
 }
+
+#[test]
+fn override_paths() {
+  let config = r#"
+version = 1
+[diagnostics]
+4014.severity = "error"
+[[diagnostics.override]]
+paths = ["gen/**"]
+4014.severity = "ignore"
+"#;
+  let a = r#"
+val _ = (1)
+(**     ^^^ unnecessary parentheses *)
+"#;
+  check_multi([
+    (config::file::PATH, config),
+    ("a.mlb", "a.sml gen/b.sml"),
+    ("a.sml", a),
+    ("gen/b.sml", "val _ = (2)"),
+  ]);
+}
+
+#[test]
+fn override_precedence() {
+  let config = r#"
+version = 1
+[diagnostics]
+4014.severity = "ignore"
+[[diagnostics.override]]
+paths = ["*.sml"]
+4014.severity = "error"
+[[diagnostics.override]]
+paths = ["b.sml"]
+4014.severity = "ignore"
+"#;
+  let a = r#"
+val _ = (1)
+(**     ^^^ unnecessary parentheses *)
+"#;
+  check_multi([
+    (config::file::PATH, config),
+    ("a.mlb", "a.sml b.sml"),
+    ("a.sml", a),
+    ("b.sml", "val _ = (2)"),
+  ]);
+}
+
+#[test]
+fn override_invalid_code() {
+  let config = r#"
+version = 1
+[[diagnostics.override]]
+paths = ["gen/**"]
+foo.severity = "ignore"
+"#;
+  check_bad_input(
+    config::file::PATH,
+    "invalid error code foo",
+    [("a.mlb", ""), (config::file::PATH, config)],
+  );
+}
+
+/// Returns the fingerprints of the diagnostics with this code in the SML, in order.
+fn fingerprints(sml: &str, code: u16) -> Vec<String> {
+  let (an, input, store) = raw::analysis(raw::one_file_fs(sml), raw::StdBasis::Minimal);
+  let path = raw::source_path(&input, &store, "f.sml");
+  let code = diagnostic::Code::n(code);
+  let mut ds = an.diagnostics_text_range(&input);
+  ds.remove(&path)
+    .unwrap_or_default()
+    .into_iter()
+    .filter(|d| d.code == code)
+    .map(|d| an.fingerprint(path, code, d.range).expect("no fingerprint").to_string())
+    .collect()
+}
+
+#[test]
+fn baseline() {
+  let config = r#"
+version = 1
+[diagnostics]
+4014.severity = "error"
+baseline = "millet-baseline.json"
+"#;
+  let a = r#"
+val a = (2)
+val b = (1)
+(**     ^^^ unnecessary parentheses *)
+"#;
+  let fps = fingerprints(a, 4014);
+  assert_eq!(fps.len(), 2);
+  assert_ne!(fps[0], fps[1]);
+  let baseline = format!(
+    r#"
+{{
+  "version": 1,
+  "diagnostics": [
+    {{ "path": "a.sml", "code": "4014", "fingerprint": "{fp}", "count": 1 }},
+    {{ "path": "gone.sml", "code": "4014", "fingerprint": "{fp}", "count": 1 }}
+  ]
+}}
+"#,
+    fp = fps[0]
+  );
+  check_multi([
+    (config::file::PATH, config),
+    (config::baseline::PATH, baseline.as_str()),
+    ("a.mlb", "a.sml"),
+    ("a.sml", a),
+  ]);
+}
+
+#[test]
+fn baseline_count() {
+  let config = r#"
+version = 1
+[diagnostics]
+4014.severity = "error"
+baseline = "millet-baseline.json"
+"#;
+  let a = r#"
+val _ = (2)
+val _ = (2)
+(**     ^^^ unnecessary parentheses *)
+"#;
+  let fps = fingerprints(a, 4014);
+  assert_eq!(fps.len(), 2);
+  assert_eq!(fps[0], fps[1]);
+  let baseline = format!(
+    r#"
+{{
+  "version": 1,
+  "diagnostics": [
+    {{ "path": "a.sml", "code": "4014", "fingerprint": "{fp}", "count": 1 }}
+  ]
+}}
+"#,
+    fp = fps[0]
+  );
+  check_multi([
+    (config::file::PATH, config),
+    (config::baseline::PATH, baseline.as_str()),
+    ("a.mlb", "a.sml"),
+    ("a.sml", a),
+  ]);
+}
+
+#[test]
+fn baseline_invalid() {
+  let config = r#"
+version = 1
+[diagnostics]
+baseline = "millet-baseline.json"
+"#;
+  check_bad_input(
+    config::baseline::PATH,
+    "couldn't parse baseline",
+    [("a.mlb", ""), (config::file::PATH, config), (config::baseline::PATH, "{")],
+  );
+}
+
+#[test]
+fn baseline_invalid_version() {
+  let config = r#"
+version = 1
+[diagnostics]
+baseline = "millet-baseline.json"
+"#;
+  let baseline = r#"
+{"version": 2, "diagnostics": []}
+"#;
+  check_bad_input(
+    config::baseline::PATH,
+    "invalid baseline version: expected 1, found 2",
+    [("a.mlb", ""), (config::file::PATH, config), (config::baseline::PATH, baseline)],
+  );
+}
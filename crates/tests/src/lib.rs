@@ -14,7 +14,10 @@ mod circularity;
 mod common;
 mod completions;
 mod datatype_copy;
+mod deprecated;
+mod deps;
 mod deviations;
+mod discard;
 mod disallow;
 mod docs;
 mod dupe;
@@ -24,12 +27,15 @@ mod exn;
 mod fixity;
 mod forbid_opaque_asc;
 mod functor;
+mod gen_sig;
 mod generalize;
 mod goto_def;
 mod hover;
+mod impl_sig;
 mod incomplete;
 mod infix_without_op;
 mod input;
+mod lint;
 mod literal;
 mod local;
 mod matching;
@@ -38,15 +44,24 @@ mod num_record;
 mod open;
 mod overload;
 mod pat;
+mod qualify;
+mod raise;
+mod related;
 mod repo;
 mod rest_pat;
 mod rust;
+mod scip;
+mod search_ty;
 mod sep;
 mod shadow;
 mod sig;
+mod similar;
 mod smoke;
 mod std_basis;
+mod suppress;
 mod symbolic;
+mod ty_annot;
+mod ty_diff;
 mod ty_escape;
 mod ty_var;
 mod unused;
@@ -0,0 +1,195 @@
+//! Lints, which run after statics.
+
+use crate::check::{check_code_action, check_fixes, check_with_config, check_with_warnings, raw};
+
+#[test]
+fn nil_eq() {
+  check_with_warnings(
+    r#"
+fun isEmpty xs = xs = []
+(**              ^^^^^^^ calling `=` or `<>` on `nil` *)
+"#,
+  );
+}
+
+#[test]
+fn nil_neq_lhs() {
+  check_with_warnings(
+    r#"
+fun isNonEmpty xs = nil <> xs
+(**                 ^^^^^^^^^ calling `=` or `<>` on `nil` *)
+"#,
+  );
+}
+
+#[test]
+fn nil_eq_fix() {
+  check_code_action(
+    r#"
+fun isEmpty xs = $0xs = []
+"#,
+    "Replace with `List.null`",
+    r#"
+fun isEmpty xs = List.null xs
+"#,
+  );
+}
+
+#[test]
+fn nil_fixes_all() {
+  let s = r#"
+fun isEmpty xs = xs = []
+fun isNonEmpty xs = nil <> xs
+"#;
+  let want = r#"
+fun isEmpty xs = List.null xs
+fun isNonEmpty xs = not (List.null xs)
+"#;
+  check_fixes(s, want);
+}
+
+#[test]
+fn nil_neq_fix_parens() {
+  check_code_action(
+    r#"
+fun isNonEmpty f x = $0f x <> []
+"#,
+    "Replace with `List.null`",
+    r#"
+fun isNonEmpty f x = not (List.null (f x))
+"#,
+  );
+}
+
+#[test]
+fn length_eq_zero() {
+  check_with_config(
+    "version = 1",
+    raw::StdBasis::Full,
+    r#"
+fun isEmpty xs = List.length xs = 0
+(**              ^^^^^^^^^^^^^^^^^^ comparing the length of a list with `0` using `=` *)
+"#,
+  );
+}
+
+#[test]
+fn length_neq_zero_lhs() {
+  check_with_config(
+    "version = 1",
+    raw::StdBasis::Full,
+    r#"
+fun isNonEmpty xs = 0 <> length xs
+(**                 ^^^^^^^^^^^^^^ comparing the length of a list with `0` using `<>` *)
+"#,
+  );
+}
+
+#[test]
+fn length_eq_other() {
+  check_with_config(
+    "version = 1",
+    raw::StdBasis::Full,
+    r#"
+fun isSingleton xs = length xs = 1
+"#,
+  );
+}
+
+#[test]
+fn eta_redex() {
+  check_with_warnings(
+    r#"
+fun apply f = fn x => f x
+(**           ^^^^^^^^^^^ unnecessary `fn` wrapping a function *)
+"#,
+  );
+}
+
+#[test]
+fn eta_redex_fix() {
+  check_code_action(
+    r#"
+fun apply f = fn x => $0f x
+"#,
+    "Replace with the function",
+    r#"
+fun apply f = f
+"#,
+  );
+}
+
+#[test]
+fn not_eta_redex() {
+  check_with_warnings(
+    r#"
+fun compose f g = fn x => f (g x)
+"#,
+  );
+}
+
+#[test]
+fn nested_one_armed_case() {
+  check_with_warnings(
+    r#"
+(* millet-ignore 4016 *)
+fun sum p =
+  case p of (a, b) =>
+(** + nested `case`s with only one arm each *)
+    case b of (c, d) => a + c + d
+"#,
+  );
+}
+
+#[test]
+fn nested_one_armed_case_fix() {
+  check_code_action(
+    r#"
+fun sum p =
+  $0case p of (a, b) =>
+    case b of (c, d) =>
+      case d of (e, f) => a + c + e + f
+"#,
+    "Rewrite with `let` and `val`",
+    r#"
+fun sum p =
+  let val (a, b) = p val (c, d) = b val (e, f) = d in a + c + e + f end
+"#,
+  );
+}
+
+#[test]
+fn partial_hd() {
+  check_with_config(
+    "version = 1",
+    raw::StdBasis::Full,
+    r#"
+fun first xs = hd xs
+(**            ^^ use of partial function `hd` *)
+"#,
+  );
+}
+
+#[test]
+fn partial_qualified() {
+  check_with_config(
+    "version = 1",
+    raw::StdBasis::Full,
+    r#"
+fun rest xs = List.tl xs
+(**           ^^^^^^^ use of partial function `tl` *)
+"#,
+  );
+}
+
+#[test]
+fn partial_shadowed() {
+  check_with_config(
+    "version = 1",
+    raw::StdBasis::Full,
+    r#"
+fun valOf (SOME x) = x | valOf NONE = 0
+val _ = valOf (SOME 3)
+"#,
+  );
+}
@@ -1,6 +1,6 @@
 //! Miscellaneous tests. If unsure where to put a test, put it here.
 
-use crate::check::check;
+use crate::check::{check, raw};
 
 #[test]
 fn arrow_ty_arg() {
@@ -417,3 +417,23 @@ val _ = add (7, 8)
 "#,
   );
 }
+
+#[test]
+fn document_symbol_selection_range() {
+  let sml = "structure S = struct\n  val x = 1\n  fun f y = y\nend\n";
+  let (an, input, store) = raw::analysis(raw::one_file_fs(sml), raw::StdBasis::Minimal);
+  let path = raw::source_path(&input, &store, "f.sml");
+  let mut syms = an.document_symbols(path).expect("no document symbols");
+  let mut got = Vec::<(String, u32, u32, u32)>::new();
+  while let Some(sym) = syms.pop() {
+    let r = sym.selection_range;
+    assert_eq!(r.start.line, r.end.line);
+    got.push((sym.name, r.start.line, r.start.col, r.end.col));
+    syms.extend(sym.children);
+  }
+  got.sort_unstable();
+  let want = [("S", 0, 10, 11), ("f", 2, 6, 7), ("x", 1, 6, 7)];
+  let want: Vec<_> =
+    want.into_iter().map(|(name, line, s, e)| (name.to_owned(), line, s, e)).collect();
+  assert_eq!(got, want);
+}
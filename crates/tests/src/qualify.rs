@@ -0,0 +1,198 @@
+//! Code actions to qualify names.
+
+use crate::check::{check_code_action, check_fixes, check_no_code_action};
+
+#[test]
+fn remove_open() {
+  check_code_action(
+    r#"
+structure Foo = struct
+  val x = 1
+  fun inc y = y + x
+  datatype d = D
+end
+
+op$0en Foo
+
+val a = inc x
+val b : d = D
+fun f D = a
+"#,
+    "Remove `open Foo` and qualify names from it",
+    r#"
+structure Foo = struct
+  val x = 1
+  fun inc y = y + x
+  datatype d = D
+end
+
+val a = Foo.inc Foo.x
+val b : Foo.d = Foo.D
+fun f Foo.D = a
+"#,
+  );
+}
+
+#[test]
+fn remove_open_shadow() {
+  check_code_action(
+    r#"
+structure Foo = struct
+  val x = 1
+  val y = 2
+end
+
+op$0en Foo
+
+val x = y
+val z = x
+"#,
+    "Remove `open Foo` and qualify names from it",
+    r#"
+structure Foo = struct
+  val x = 1
+  val y = 2
+end
+
+val x = Foo.y
+val z = x
+"#,
+  );
+}
+
+#[test]
+fn undefined() {
+  check_code_action(
+    r#"
+structure Foo = struct
+  val x = 1
+end
+
+val y = $0x
+"#,
+    "Qualify as `Foo.x`",
+    r#"
+structure Foo = struct
+  val x = 1
+end
+
+val y = Foo.x
+"#,
+  );
+}
+
+#[test]
+fn remove_open_many() {
+  check_code_action(
+    r#"
+structure Foo = struct
+  val x = 1
+end
+
+structure Bar = struct
+  val y = 2
+end
+
+open Foo B$0ar
+
+val a = x + y
+"#,
+    "Remove `Bar` from `open` and qualify names from it",
+    r#"
+structure Foo = struct
+  val x = 1
+end
+
+structure Bar = struct
+  val y = 2
+end
+
+open Foo
+
+val a = x + Bar.y
+"#,
+  );
+}
+
+#[test]
+fn remove_open_many_on_open() {
+  check_no_code_action(
+    r#"
+structure Foo = struct
+  val x = 1
+end
+
+structure Bar = struct
+  val y = 2
+end
+
+op$0en Foo Bar
+
+val a = x + y
+"#,
+    "Remove `open Foo` and qualify names from it",
+  );
+}
+
+#[test]
+fn remove_open_infix() {
+  check_no_code_action(
+    r#"
+structure Foo = struct
+  fun ++ (x, y) = x + y
+end
+
+op$0en Foo
+
+infix ++
+val a = 1 ++ 2
+"#,
+    "Remove `open Foo` and qualify names from it",
+  );
+}
+
+#[test]
+fn undefined_later() {
+  check_no_code_action(
+    r#"
+val y = $0x
+
+structure Foo = struct
+  val x = 1
+end
+"#,
+    "Qualify as `Foo.x`",
+  );
+}
+
+#[test]
+fn fixes() {
+  check_fixes(
+    r#"
+structure Foo = struct
+  val width = 1
+  val height = 2
+end
+
+structure Bar = struct
+  val height = 3
+end
+
+val a = width
+val b = height
+"#,
+    r#"
+structure Foo = struct
+  val width = 1
+  val height = 2
+end
+
+structure Bar = struct
+  val height = 3
+end
+
+val a = Foo.width
+val b = height
+"#,
+  );
+}
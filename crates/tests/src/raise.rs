@@ -0,0 +1,183 @@
+//! Inferring what exceptions functions may raise.
+
+use crate::check::{check, check_hover_lacks, check_with_config, check_with_std_basis, raw};
+
+#[test]
+fn smoke() {
+  check(
+    r#"
+exception E
+fun f () = raise E
+val g = f
+(**     ^ hover: may raise: E *)
+"#,
+  );
+}
+
+#[test]
+fn call() {
+  check(
+    r#"
+exception A
+exception B
+fun f x = if x then raise A else raise B
+fun g () = f true
+val h = g
+(**     ^ hover: may raise: A, B *)
+"#,
+  );
+}
+
+#[test]
+fn recursive() {
+  check(
+    r#"
+exception Neg
+fun count n = if n < 0 then raise Neg else if n = 0 then 0 else 1 + count (n - 1)
+val h = count
+(**     ^^^^^ hover: may raise: Neg *)
+"#,
+  );
+}
+
+#[test]
+fn handle() {
+  check(
+    r#"
+exception A
+exception B
+fun f x = if x then raise A else raise B
+fun g () = f true handle A => ()
+val h = g
+(**     ^ hover: may raise: B *)
+"#,
+  );
+}
+
+#[test]
+fn handle_all() {
+  check_hover_lacks(
+    r#"
+exception A
+fun f () = raise A
+fun g () = f () handle _ => ()
+val h = $0g
+"#,
+    "may raise",
+  );
+}
+
+#[test]
+fn handle_arg() {
+  check(
+    r#"
+exception A of string
+fun f x = raise A x
+fun g () = f "y" handle A "x" => ()
+val h = g
+(**     ^ hover: may raise: A *)
+"#,
+  );
+}
+
+#[test]
+fn handle_arg_var() {
+  check_hover_lacks(
+    r#"
+exception A of string
+fun f x = raise A x
+fun g () = f "y" handle A _ => ()
+val h = $0g
+"#,
+    "may raise",
+  );
+}
+
+#[test]
+fn reraise() {
+  check(
+    r#"
+exception A
+fun f () = raise A
+fun g () = f () handle e => raise e
+val h = g
+(**     ^ hover: may raise: A *)
+"#,
+  );
+}
+
+#[test]
+fn arg() {
+  check(
+    r#"
+exception Bad of string
+fun f () = raise Bad "oops"
+val h = f
+(**     ^ hover: may raise: Bad *)
+"#,
+  );
+}
+
+#[test]
+fn div() {
+  check(
+    r#"
+fun half x = x div 2
+val h = half
+(**     ^^^^ hover: may raise: Div *)
+"#,
+  );
+}
+
+#[test]
+fn std_basis() {
+  check_with_std_basis(
+    r#"
+fun second xs = hd (List.tl xs)
+fun third xs = List.nth (xs, 2) + second xs handle Empty => 0
+val h = third
+(**     ^^^^^ hover: may raise: Subscript *)
+"#,
+  );
+}
+
+#[test]
+fn entry_point() {
+  let config = r#"
+version = 1
+[language]
+entry-points = ["main"]
+"#;
+  let sml = r#"
+exception Bad
+fun run x = if x then raise Bad else ()
+fun main () = run true
+(** + exceptions may escape from `main`: Bad *)
+"#;
+  check_with_config(config, raw::StdBasis::Minimal, sml);
+}
+
+#[test]
+fn entry_point_handled() {
+  let config = r#"
+version = 1
+[language]
+entry-points = ["main"]
+"#;
+  let sml = r#"
+exception Bad
+fun run x = if x then raise Bad else ()
+fun main () = run true handle Bad => ()
+"#;
+  check_with_config(config, raw::StdBasis::Minimal, sml);
+}
+
+#[test]
+fn entry_point_not_configured() {
+  let sml = r#"
+exception Bad
+fun run x = if x then raise Bad else ()
+fun main () = run true
+"#;
+  check_with_config("version = 1", raw::StdBasis::Minimal, sml);
+}
@@ -0,0 +1,80 @@
+//! Related locations for diagnostics.
+
+use crate::check::check_related;
+
+#[test]
+fn duplicate() {
+  check_related(
+    r#"
+val (x,
+     x) = (1, 2)
+"#,
+    &[(1, "previously defined here")],
+  );
+}
+
+#[test]
+fn missing() {
+  check_related(
+    r#"
+signature SIG = sig
+  val x : int
+end
+structure S : SIG = struct end
+"#,
+    &[(2, "required by this specification")],
+  );
+}
+
+#[test]
+fn extra() {
+  check_related(
+    r#"
+signature SIG = sig
+  datatype d = A
+end
+structure S : SIG = struct
+  datatype d = A | B
+end
+"#,
+    &[(2, "specified here")],
+  );
+}
+
+#[test]
+fn sig_mismatch() {
+  check_related(
+    r#"
+signature SIG = sig
+  val x : int
+end
+structure S : SIG = struct
+  val x = "hi"
+end
+"#,
+    &[(2, "specified here")],
+  );
+}
+
+#[test]
+fn annotation() {
+  check_related(
+    r#"
+val x = (
+  "hi"
+  : int)
+"#,
+    &[(3, "expected type comes from this annotation")],
+  );
+}
+
+#[test]
+fn similar() {
+  check_related(
+    r#"
+val counter = 3
+val _ = countr
+"#,
+    &[(1, "similar name: `counter`")],
+  );
+}
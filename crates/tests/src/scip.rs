@@ -0,0 +1,69 @@
+//! Encoding SCIP indices.
+
+#[test]
+fn round_trip() {
+  let index = scip::Index {
+    metadata: scip::Metadata {
+      tool_name: "millet".to_owned(),
+      tool_version: "0.1.0".to_owned(),
+      project_root: "file:///root/".to_owned(),
+      text_document_encoding: scip::TEXT_ENCODING_UTF8,
+    },
+    documents: vec![
+      scip::Document {
+        relative_path: "a.sml".to_owned(),
+        occurrences: vec![
+          scip::Occurrence {
+            range: vec![0, 4, 5],
+            symbol: "millet . . . a.sml/x.".to_owned(),
+            symbol_roles: scip::ROLE_DEFINITION,
+            enclosing_range: vec![0, 0, 2, 3],
+          },
+          scip::Occurrence {
+            range: vec![3, 8, 9],
+            symbol: "millet . . . a.sml/x.".to_owned(),
+            symbol_roles: 0,
+            enclosing_range: Vec::new(),
+          },
+          scip::Occurrence {
+            range: vec![3, 12, 300],
+            symbol: "millet . . . std/map.".to_owned(),
+            symbol_roles: 0,
+            enclosing_range: Vec::new(),
+          },
+        ],
+        symbols: vec![scip::SymbolInformation {
+          symbol: "millet . . . a.sml/x.".to_owned(),
+          documentation: vec!["```sml\nval x : int\n```".to_owned()],
+          display_name: "x".to_owned(),
+          enclosing_symbol: String::new(),
+        }],
+        language: "sml".to_owned(),
+        position_encoding: scip::POSITION_ENCODING_UTF16,
+      },
+      scip::Document {
+        relative_path: "dir/b é.sml".to_owned(),
+        language: "sml".to_owned(),
+        ..Default::default()
+      },
+    ],
+    external_symbols: vec![scip::SymbolInformation {
+      symbol: "millet . . . std/map.".to_owned(),
+      documentation: vec!["first".to_owned(), "second".to_owned()],
+      display_name: "map".to_owned(),
+      enclosing_symbol: "millet . . . std/List/".to_owned(),
+    }],
+  };
+  let bytes = scip::encode(&index);
+  assert_eq!(scip::decode(&bytes).unwrap(), index);
+}
+
+#[test]
+fn truncated() {
+  let index = scip::Index {
+    metadata: scip::Metadata { tool_name: "millet".to_owned(), ..Default::default() },
+    ..Default::default()
+  };
+  let bytes = scip::encode(&index);
+  assert!(scip::decode(&bytes[..bytes.len() - 1]).is_err());
+}
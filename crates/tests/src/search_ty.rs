@@ -0,0 +1,78 @@
+//! Searching for values by their types.
+
+use crate::check::raw;
+
+/// Checks that searching for the query in the program finds exactly the values named `want` that
+/// were defined in the program, in order.
+#[track_caller]
+fn check(s: &str, query: &str, want: &[&str]) {
+  let (an, _, _) = raw::analysis(raw::one_file_fs(s), raw::StdBasis::Minimal);
+  let got: Vec<_> = an
+    .search_by_ty(query)
+    .expect("couldn't parse query")
+    .into_iter()
+    .filter_map(|x| x.def.is_some().then_some(x.name))
+    .collect();
+  assert_eq!(want, got.as_slice());
+}
+
+const PROGRAM: &str = r#"
+fun count (xs : 'a list) : int =
+  case xs of
+    [] => 0
+  | _ :: xs => 1 + count xs
+
+fun add (x : int, y : int) : int = x + y
+fun addCurried (x : int) (y : int) : int = x + y
+fun choose (n : int, xs : string list) : string = "hi"
+fun swap (x : 'a, y : 'b) : 'b * 'a = (y, x)
+
+structure S = struct
+  val zero = 0
+  fun inc (x : int) : int = x + 1
+end
+"#;
+
+#[test]
+fn poly() {
+  check(PROGRAM, "'a list -> int", &["count"]);
+}
+
+#[test]
+fn rename_ty_var() {
+  check(PROGRAM, "'elem list -> int", &["count"]);
+}
+
+#[test]
+fn curry_and_tuple() {
+  check(PROGRAM, "int -> int -> int", &["add", "addCurried"]);
+  check(PROGRAM, "int * int -> int", &["add", "addCurried"]);
+}
+
+#[test]
+fn param_order() {
+  check(PROGRAM, "string list -> int -> string", &["choose"]);
+}
+
+#[test]
+fn param_order_poly() {
+  check(PROGRAM, "'b * 'a -> 'a * 'b", &["swap"]);
+  check(PROGRAM, "'a * 'a -> 'a * 'a", &[]);
+}
+
+#[test]
+fn structure() {
+  check(PROGRAM, "int", &["S.zero"]);
+  check(PROGRAM, "int -> int", &["S.inc"]);
+}
+
+#[test]
+fn hole() {
+  check(PROGRAM, "_ -> int", &["S.inc", "count"]);
+}
+
+#[test]
+fn not_a_ty() {
+  let (an, _, _) = raw::analysis(raw::one_file_fs(PROGRAM), raw::StdBasis::Minimal);
+  assert!(an.search_by_ty("int ->").is_none());
+}
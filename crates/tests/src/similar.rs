@@ -0,0 +1,121 @@
+//! Suggesting similar names in scope for undefined names.
+
+use crate::check::{check, check_code_action, check_fixes};
+
+#[test]
+fn val() {
+  check(
+    r#"
+val counter = 3
+val _ = countr + 1
+(**     ^^^^^^ undefined value: `countr` (did you mean `counter`?) *)
+"#,
+  );
+}
+
+#[test]
+fn case_only() {
+  check(
+    r#"
+datatype d = Foo
+val _ = FOO
+(**     ^^^ undefined value: `FOO` (did you mean `Foo`?) *)
+"#,
+  );
+}
+
+#[test]
+fn in_structure() {
+  check(
+    r#"
+structure S = struct
+  type thing = int
+end
+val _ : S.thign = 3
+(**     ^^^^^^^ undefined type: `thign` (did you mean `thing`?) *)
+"#,
+  );
+}
+
+#[test]
+fn structure() {
+  check(
+    r#"
+structure Queue = struct
+  val empty = []
+end
+val _ = Quue.empty
+(**     ^^^^^^^^^^ undefined structure: `Quue` (did you mean `Queue`?) *)
+"#,
+  );
+}
+
+#[test]
+fn signature() {
+  check(
+    r#"
+signature STACK = sig end
+structure S : STAK = struct end
+(**           ^^^^ undefined signature: `STAK` (did you mean `STACK`?) *)
+"#,
+  );
+}
+
+#[test]
+fn too_different() {
+  check(
+    r#"
+val abc = 3
+val _ = xyz
+(**     ^^^ exact: undefined value: `xyz` *)
+"#,
+  );
+}
+
+#[test]
+fn quick_fix() {
+  check_code_action(
+    r#"
+fun increment x = x + 1
+val _ = incre$0mnt 2
+"#,
+    "Replace with `increment`",
+    r#"
+fun increment x = x + 1
+val _ = increment 2
+"#,
+  );
+}
+
+#[test]
+fn quick_fix_structure() {
+  check_code_action(
+    r#"
+structure Queue = struct
+  val empty = []
+end
+val _ = Qu$0ue.empty
+"#,
+    "Replace with `Queue`",
+    r#"
+structure Queue = struct
+  val empty = []
+end
+val _ = Queue.empty
+"#,
+  );
+}
+
+#[test]
+fn fixes() {
+  check_fixes(
+    r#"
+fun increment x = x + 1
+val _ = incremnt 2
+"#,
+    r#"
+fun increment x = x + 1
+val _ = increment 2
+"#,
+  );
+}
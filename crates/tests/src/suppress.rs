@@ -0,0 +1,93 @@
+//! Suppressing diagnostics with comments.
+
+use crate::check::check_with_warnings;
+
+#[test]
+fn smoke() {
+  check_with_warnings(
+    r#"
+(* millet-ignore 5029 *)
+fun f x = ()
+"#,
+  );
+}
+
+#[test]
+fn all_codes() {
+  check_with_warnings(
+    r#"
+(* millet-ignore *)
+val _ = 3 + "hi"
+"#,
+  );
+}
+
+#[test]
+fn with_reason() {
+  check_with_warnings(
+    r#"
+(* millet-ignore 5029 5036 needed for the interface *)
+fun f x = ()
+"#,
+  );
+}
+
+#[test]
+fn other_code() {
+  check_with_warnings(
+    r#"
+(* millet-ignore 5006 *)
+fun f x = ()
+(**   ^ unused value: `x` *)
+"#,
+  );
+}
+
+#[test]
+fn only_next_dec() {
+  check_with_warnings(
+    r#"
+(* millet-ignore 5029 *)
+fun f x = ()
+fun g y = ()
+(**   ^ unused value: `y` *)
+"#,
+  );
+}
+
+#[test]
+fn whole_dec() {
+  check_with_warnings(
+    r#"
+(* millet-ignore 5029 *)
+fun f x =
+  let
+    val y = 3
+  in
+    ()
+  end
+"#,
+  );
+}
+
+#[test]
+fn file() {
+  check_with_warnings(
+    r#"
+(* millet-ignore-file 5029 *)
+fun f x = ()
+fun g y = ()
+"#,
+  );
+}
+
+#[test]
+fn unused() {
+  check_with_warnings(
+    r#"
+(* millet-ignore 5029 *)
+(** + unused suppression comment *)
+val x = 3
+"#,
+  );
+}
@@ -0,0 +1,94 @@
+//! Code actions to add type annotations.
+
+use crate::check::check_code_action;
+
+#[test]
+fn val() {
+  check_code_action(
+    r#"
+val $0x = 3
+"#,
+    "Add type annotation",
+    r#"
+val x : int = 3
+"#,
+  );
+}
+
+#[test]
+fn fun_param() {
+  check_code_action(
+    r#"
+fun inc $0x = x + 1
+"#,
+    "Add type annotation",
+    r#"
+fun inc (x : int) = x + 1
+"#,
+  );
+}
+
+#[test]
+fn fun_return() {
+  check_code_action(
+    r#"
+fun $0inc x = x + 1
+"#,
+    "Add return type annotation",
+    r#"
+fun inc x : int = x + 1
+"#,
+  );
+}
+
+#[test]
+fn fun_params() {
+  check_code_action(
+    r#"
+fun $0add x y = x + y
+"#,
+    "Add type annotations to parameters",
+    r#"
+fun add (x : int) (y : int) = x + y
+"#,
+  );
+}
+
+#[test]
+fn ty_var_binder() {
+  check_code_action(
+    r#"
+fun $0id x = x
+"#,
+    "Add type annotations to parameters",
+    r#"
+fun 'a id (x : 'a) = x
+"#,
+  );
+}
+
+#[test]
+fn ty_var_binder_outer_dec() {
+  check_code_action(
+    r#"
+fun f x = let val $0y = x in y end
+"#,
+    "Add type annotation",
+    r#"
+fun 'a f x = let val y : 'a = x in y end
+"#,
+  );
+}
+
+#[test]
+fn ty_var_binder_inner_dec() {
+  check_code_action(
+    r#"
+fun f x = let val $0g = fn y => y in g x end
+"#,
+    "Add type annotation",
+    r#"
+fun f x = let val 'a g : 'a -> 'a = fn y => y in g x end
+"#,
+  );
+}
@@ -0,0 +1,74 @@
+//! Showing the difference between long types in type mismatch messages.
+
+use crate::check::{check, raw};
+
+const RECORD: &str = r#"
+val xs : string list = []
+val ys : int list list = []
+fun f (r : { a : int, b : string list, c : int list list }) = r
+val _ = f { a = true, b = xs, c = ys }
+"#;
+
+const FN: &str = r#"
+val xs : (int * string) list = []
+fun f (g : (int * string) list -> (int * string) list -> int) = g xs xs
+val _ = f (fn _ => fn _ => "no")
+"#;
+
+#[test]
+fn record_one_line() {
+  check(&format!(
+    "{RECORD}{}",
+    "(** + expected `{ a : int, b : …, c : … }`, found `{ a : bool, b : …, c : … }` *)\n"
+  ));
+}
+
+#[test]
+fn fn_one_line() {
+  check(&format!("{FN}{}", "(** + expected `… -> … -> int`, found `… -> … -> string` *)\n"));
+}
+
+#[test]
+fn short() {
+  check(
+    r#"
+val _ : int list = ["no"]
+(** + exact: incompatible types: `int` and `string` are different type constructors: expected `int list`, found `string list` *)
+"#,
+  );
+}
+
+fn many_lines_message(sml: &str) -> String {
+  let options =
+    analysis::Options { lines: config::DiagnosticLines::Many, ..analysis::Options::default() };
+  let (mut an, input, store) =
+    raw::analysis_with_options(raw::one_file_fs(sml), raw::StdBasis::Minimal, options);
+  let path = raw::source_path(&input, &store, "f.sml");
+  an.get_many(&input)
+    .into_iter()
+    .filter(|&(id, _)| id == path)
+    .flat_map(|(_, ds)| ds)
+    .next()
+    .expect("no diagnostics")
+    .message
+}
+
+#[test]
+fn record_many_lines() {
+  let want = r#"incompatible types: `int` and `bool` are different type constructors
+  expected `{ a : int, b : …, c : … }`
+                  ^^^
+     found `{ a : bool, b : …, c : … }`
+                  ^^^^"#;
+  assert_eq!(want, many_lines_message(RECORD));
+}
+
+#[test]
+fn fn_many_lines() {
+  let want = r#"incompatible types: `int` and `string` are different type constructors
+  expected `… -> … -> int`
+                      ^^^
+     found `… -> … -> string`
+                      ^^^^^^"#;
+  assert_eq!(want, many_lines_message(FN));
+}